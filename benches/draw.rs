@@ -0,0 +1,126 @@
+// Sprite drawing speed: the packed `Display::blit_sprite_row` against the
+// old pixel-by-pixel loop over a `bool` grid, then whole drawing-heavy ROMs.
+//
+// Run with `cargo bench --bench draw`.
+
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::display::{Display, HEIGHT, WIDTH};
+use chip8_emulator::rom;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SPRITE_ROWS: usize = 2_000_000;
+const ROM_FRAMES: u32 = 2_000;
+const ROM_CYCLES_PER_FRAME: u32 = 1_000;
+
+const ROMS: [&str; 4] = [
+    "chip8-roms/demos/Particle Demo [zeroZshadow, 2008].ch8",
+    "chip8-roms/demos/Sierpinski [Sergey Naydenov, 2010].ch8",
+    "chip8-roms/demos/Maze [David Winter, 199x].ch8",
+    "chip8-roms/demos/Trip8 Demo (2008) [Revival Studios].ch8",
+];
+
+// How `Cpu::drw` drew a sprite row before the display was packed.
+struct BoolDisplay {
+    pixels: [[bool; WIDTH]; HEIGHT],
+}
+
+impl BoolDisplay {
+    fn blit_sprite_row(&mut self, x: usize, y: usize, sprite: u8) -> bool {
+        let mut collision = false;
+        for col in 0..8 {
+            if (sprite >> (7 - col)) & 1 == 1 {
+                let x = (x + col) % WIDTH;
+                let y = y % HEIGHT;
+                if self.pixels[y][x] {
+                    collision = true;
+                }
+                self.pixels[y][x] = !self.pixels[y][x];
+            }
+        }
+        collision
+    }
+}
+
+// A cheap, repeatable stream of sprite rows and positions.
+fn sprite_rows() -> impl Iterator<Item = (usize, usize, u8)> {
+    let mut state = 0x2545_F491u32;
+    (0..SPRITE_ROWS).map(move |_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (
+            (state & 0x3F) as usize,
+            ((state >> 8) & 0x1F) as usize,
+            (state >> 16) as u8,
+        )
+    })
+}
+
+fn time(f: impl FnOnce() -> usize) -> (Duration, usize) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+fn main() {
+    let (per_pixel, per_pixel_hits) = time(|| {
+        let mut display = BoolDisplay {
+            pixels: [[false; WIDTH]; HEIGHT],
+        };
+        sprite_rows()
+            .filter(|&(x, y, sprite)| black_box(&mut display).blit_sprite_row(x, y, sprite))
+            .count()
+    });
+    let (packed, packed_hits) = time(|| {
+        let mut display = Display::new();
+        sprite_rows()
+            .filter(|&(x, y, sprite)| black_box(&mut display).blit_sprite_row(x, y, sprite, false))
+            .count()
+    });
+    assert_eq!(per_pixel_hits, packed_hits, "the two displays disagree");
+
+    println!("{} sprite rows:", SPRITE_ROWS);
+    println!("  per pixel  {:>8.2} ms", per_pixel.as_secs_f64() * 1000.0);
+    println!(
+        "  packed     {:>8.2} ms  ({:.1}x)",
+        packed.as_secs_f64() * 1000.0,
+        per_pixel.as_secs_f64() / packed.as_secs_f64()
+    );
+
+    println!(
+        "{} frames at {} instructions per frame:",
+        ROM_FRAMES, ROM_CYCLES_PER_FRAME
+    );
+    for path in ROMS {
+        let program = match rom::read(path) {
+            Ok(program) => program,
+            Err(e) => {
+                eprintln!("  skipping {}: {}", path, e);
+                continue;
+            }
+        };
+        let mut cpu = Cpu::new();
+        cpu.seed_rng(1);
+        cpu.cycles_per_frame = ROM_CYCLES_PER_FRAME;
+        cpu.memory
+            .load_program(&program)
+            .expect("ROM should fit in memory");
+        let (elapsed, _) = time(|| {
+            for _ in 0..ROM_FRAMES {
+                cpu.run_frame();
+            }
+            0
+        });
+        let name = std::path::Path::new(path)
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or(path);
+        println!(
+            "  {:<45} {:>8.2} ms  ({:.0} frames/s)",
+            name,
+            elapsed.as_secs_f64() * 1000.0,
+            ROM_FRAMES as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
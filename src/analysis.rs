@@ -0,0 +1,417 @@
+use crate::disasm::{decode, Instruction};
+use crate::memory;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::ControlFlow;
+
+pub const PROGRAM_START: u16 = memory::PROGRAM_START as u16;
+pub const MEMORY_END: u16 = memory::MEMORY_SIZE as u16;
+
+// Opcodes outside plain CHIP-8 that give away which extension a program was
+// written for. XO-CHIP's `F000 nnnn` is the only four-byte instruction.
+pub fn extension(opcode: u16) -> Option<&'static str> {
+    match opcode {
+        0x00FB..=0x00FF => Some("schip"),
+        0x00C0..=0x00CF => Some("schip"),
+        0x00D0..=0x00DF => Some("xo-chip"),
+        0xF000 | 0xF002 => Some("xo-chip"),
+        _ => match (opcode & 0xF000, opcode & 0x000F, opcode & 0x00FF) {
+            (0xD000, 0x0, _) => Some("schip"),
+            (0xF000, _, 0x30 | 0x75 | 0x85) => Some("schip"),
+            (0xF000, _, 0x01 | 0x3A) => Some("xo-chip"),
+            (0x5000, 0x2 | 0x3, _) => Some("xo-chip"),
+            _ => None,
+        },
+    }
+}
+
+fn length(opcode: u16) -> u16 {
+    if opcode == 0xF000 {
+        4
+    } else {
+        2
+    }
+}
+
+pub fn is_skip(instruction: Instruction) -> bool {
+    use Instruction::*;
+
+    matches!(
+        instruction,
+        SeImm(..) | SneImm(..) | SeReg(..) | SneReg(..) | Skp(_) | Sknp(_)
+    )
+}
+
+// Whether the instruction may change VX (VF included), for constant
+// tracking. Errs on the side of yes.
+pub fn writes_register(instruction: Instruction, register: u8) -> bool {
+    use Instruction::*;
+
+    match instruction {
+        LdImm(x, _)
+        | AddImm(x, _)
+        | LdReg(x, _)
+        | Or(x, _)
+        | And(x, _)
+        | Xor(x, _)
+        | AddReg(x, _)
+        | Sub(x, _)
+        | Shr(x, _)
+        | Subn(x, _)
+        | Shl(x, _)
+        | Rnd(x, _)
+        | LdVxDt(x)
+        | LdKey(x) => x == register || register == 0xF,
+        Drw(..) => register == 0xF,
+        Load(x) => register <= x,
+        _ => false,
+    }
+}
+
+// Straight-line code: entered only at the top, and every instruction but the
+// last continues at the next one.
+pub struct Block {
+    pub start: u16,
+    // Address after the last instruction.
+    pub end: u16,
+    // Address, opcode and instruction.
+    pub instructions: Vec<(u16, u16, Instruction)>,
+}
+
+impl Block {
+    pub fn last(&self) -> (u16, u16, Instruction) {
+        self.instructions[self.instructions.len() - 1]
+    }
+}
+
+// How control gets from the end of one block to the start of another.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Edge {
+    Next,
+    Jump,
+    // Taken when a skip instruction skips.
+    Skip,
+    Call,
+    // From a call to the instruction after it, once the subroutine returns.
+    Return,
+}
+
+// A subroutine, or the main program at 0x200: the blocks reachable from its
+// entry without following calls.
+pub struct Function {
+    pub entry: u16,
+    // Block starts.
+    pub blocks: BTreeSet<u16>,
+    // Entries of the functions it calls.
+    pub calls: BTreeSet<u16>,
+    // Addresses of `Bnnn` jumps that can't be followed.
+    pub computed_jumps: Vec<u16>,
+}
+
+// What can be worked out about a program without running it, following
+// every branch from 0x200. Computed `Bnnn` jumps can't be followed.
+pub struct Analysis {
+    pub program: Vec<u8>,
+    pub instructions: BTreeMap<u16, Instruction>,
+    pub extensions: BTreeMap<u16, &'static str>,
+    pub computed_jumps: Vec<u16>,
+    code: BTreeSet<u16>,
+    leaders: BTreeSet<u16>,
+}
+
+impl Analysis {
+    pub fn new(program: &[u8]) -> Self {
+        let mut analysis = Self {
+            program: program.to_vec(),
+            instructions: BTreeMap::new(),
+            extensions: BTreeMap::new(),
+            computed_jumps: Vec::new(),
+            code: BTreeSet::new(),
+            leaders: BTreeSet::new(),
+        };
+
+        let mut pending = vec![PROGRAM_START];
+        while let Some(addr) = pending.pop() {
+            if analysis.instructions.contains_key(&addr) {
+                continue;
+            }
+            let Some(opcode) = analysis.opcode_at(addr) else {
+                continue;
+            };
+            let instruction = decode(opcode);
+            analysis.instructions.insert(addr, instruction);
+            for offset in 0..length(opcode) {
+                analysis.code.insert(addr + offset);
+            }
+            if let Some(name) = extension(opcode) {
+                analysis.extensions.insert(addr, name);
+            }
+            if let Instruction::JpV0(_) = instruction {
+                analysis.computed_jumps.push(addr);
+            }
+            pending.extend(analysis.successors(addr));
+        }
+
+        let mut leaders = BTreeSet::from([PROGRAM_START]);
+        for &addr in analysis.instructions.keys() {
+            let successors = analysis.successors(addr);
+            let next = analysis
+                .opcode_at(addr)
+                .map_or(addr + 2, |op| addr + length(op));
+            if successors != [next] {
+                leaders.extend(successors);
+            }
+        }
+        analysis.leaders = leaders;
+        analysis
+    }
+
+    // Only what the interpreter would load, so bytes past 0xFFF never count.
+    pub fn opcode_at(&self, addr: u16) -> Option<u16> {
+        if addr >= MEMORY_END - 1 {
+            return None;
+        }
+        let offset = addr.checked_sub(PROGRAM_START)? as usize;
+        let bytes = self.program.get(offset..offset + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    // Addresses execution can continue at after the instruction at `addr`.
+    pub fn successors(&self, addr: u16) -> Vec<u16> {
+        use Instruction::*;
+
+        let Some(opcode) = self.opcode_at(addr) else {
+            return Vec::new();
+        };
+        let next = addr + length(opcode);
+        match decode(opcode) {
+            Jp(target) => vec![target],
+            Call(target) => vec![target, next],
+            Ret | JpV0(_) => Vec::new(),
+            SeImm(..) | SneImm(..) | SeReg(..) | SneReg(..) | Skp(_) | Sknp(_) => {
+                let skipped = self.opcode_at(next).map(length).unwrap_or(2);
+                vec![next, next + skipped]
+            }
+            Sys(_) | Invalid(_) => match extension(opcode) {
+                Some(_) if opcode != 0x00FD => vec![next],
+                _ => Vec::new(),
+            },
+            _ => vec![next],
+        }
+    }
+
+    // Where blocks start: 0x200, and wherever an instruction that doesn't
+    // just carry on to the next one can continue.
+    pub fn leaders(&self) -> BTreeSet<u16> {
+        self.leaders.clone()
+    }
+
+    // The program split into blocks at `leaders`, in address order.
+    pub fn blocks(&self, leaders: &BTreeSet<u16>) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        for &start in leaders {
+            let mut block = Block {
+                start,
+                end: start,
+                instructions: Vec::new(),
+            };
+            let mut addr = start;
+            while let (Some(&instruction), Some(opcode)) =
+                (self.instructions.get(&addr), self.opcode_at(addr))
+            {
+                block.instructions.push((addr, opcode, instruction));
+                let next = addr + length(opcode);
+                block.end = next;
+                if self.successors(addr) != [next] || leaders.contains(&next) {
+                    break;
+                }
+                addr = next;
+            }
+            if !block.instructions.is_empty() {
+                blocks.push(block);
+            }
+        }
+        blocks
+    }
+
+    // Where control goes after `block`, and how.
+    pub fn edges(&self, block: &Block) -> Vec<(u16, Edge)> {
+        use Instruction::*;
+
+        let (addr, _, instruction) = block.last();
+        let successors = self.successors(addr);
+        match (instruction, successors.as_slice()) {
+            (Jp(target), _) => vec![(target, Edge::Jump)],
+            (Call(target), &[_, next]) => vec![(target, Edge::Call), (next, Edge::Return)],
+            (
+                SeImm(..) | SneImm(..) | SeReg(..) | SneReg(..) | Skp(_) | Sknp(_),
+                &[next, skipped],
+            ) => {
+                vec![(next, Edge::Next), (skipped, Edge::Skip)]
+            }
+            _ => successors
+                .into_iter()
+                .map(|next| (next, Edge::Next))
+                .collect(),
+        }
+    }
+
+    // The main program and every subroutine it calls, by entry, with the
+    // blocks each is made of. A block shared by two functions is in both.
+    pub fn functions(&self, blocks: &[Block]) -> BTreeMap<u16, Function> {
+        let by_start: BTreeMap<u16, &Block> = blocks.iter().map(|b| (b.start, b)).collect();
+        let mut entries = vec![PROGRAM_START];
+        let mut functions = BTreeMap::new();
+        while let Some(entry) = entries.pop() {
+            if functions.contains_key(&entry) || !by_start.contains_key(&entry) {
+                continue;
+            }
+            let mut function = Function {
+                entry,
+                blocks: BTreeSet::new(),
+                calls: BTreeSet::new(),
+                computed_jumps: Vec::new(),
+            };
+            let mut pending = vec![entry];
+            while let Some(start) = pending.pop() {
+                let Some(block) = by_start.get(&start) else {
+                    continue;
+                };
+                if !function.blocks.insert(start) {
+                    continue;
+                }
+                if let (addr, _, Instruction::JpV0(_)) = block.last() {
+                    function.computed_jumps.push(addr);
+                }
+                for (target, edge) in self.edges(block) {
+                    if edge == Edge::Call {
+                        function.calls.insert(target);
+                        entries.push(target);
+                    } else {
+                        pending.push(target);
+                    }
+                }
+            }
+            functions.insert(entry, function);
+        }
+        functions
+    }
+
+    pub fn is_code(&self, addr: u16) -> bool {
+        self.code.contains(&addr)
+    }
+
+    pub fn code_bytes(&self) -> usize {
+        self.code.len()
+    }
+
+    // Walks back through the straight-line code before `addr` until `step`
+    // breaks with an answer. Stops at anything another path could jump past.
+    pub fn scan_back<T>(
+        &self,
+        addr: u16,
+        step: impl Fn(Instruction) -> ControlFlow<Option<T>>,
+    ) -> Option<T> {
+        let mut addr = addr;
+        while addr >= PROGRAM_START + 2 {
+            // Something jumps, skips or returns here, so the instruction
+            // before it may not have run.
+            if self.leaders.contains(&addr) {
+                return None;
+            }
+            let before = addr - 2;
+            let instruction = *self.instructions.get(&before)?;
+            if self.successors(before) != [addr] {
+                return None;
+            }
+            if before >= PROGRAM_START + 2
+                && self
+                    .instructions
+                    .get(&(before - 2))
+                    .is_some_and(|&skip| is_skip(skip))
+            {
+                return None;
+            }
+            if let ControlFlow::Break(result) = step(instruction) {
+                return result;
+            }
+            addr = before;
+        }
+        None
+    }
+
+    // The value of I at `addr` when a recent `LD I, nnn` fixes it.
+    pub fn index_at(&self, addr: u16) -> Option<u16> {
+        use Instruction::*;
+
+        self.scan_back(addr, |instruction| match instruction {
+            LdI(target) => ControlFlow::Break(Some(target)),
+            AddI(_) | LdFont(_) | Store(_) | Load(_) => ControlFlow::Break(None),
+            _ => ControlFlow::Continue(()),
+        })
+    }
+
+    // The value of VX at `addr` when a recent `LD VX, nn` fixes it.
+    pub fn register_at(&self, addr: u16, register: u8) -> Option<u8> {
+        self.scan_back(addr, |instruction| match instruction {
+            Instruction::LdImm(x, nn) if x == register => ControlFlow::Break(Some(nn)),
+            _ if writes_register(instruction, register) => ControlFlow::Break(None),
+            _ => ControlFlow::Continue(()),
+        })
+    }
+
+    pub fn detected_variant(&self) -> &'static str {
+        let names: BTreeSet<&str> = self.extensions.values().copied().collect();
+        if names.contains("xo-chip") {
+            "xo-chip"
+        } else if names.contains("schip") {
+            "schip"
+        } else if self.program.starts_with(&[0x12, 0x60]) {
+            // The VIP's 64x64 mode ships its own interpreter patch at 0x200.
+            "chip8 (64x64 hires)"
+        } else {
+            "chip8"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_is_unknown_where_a_jump_comes_in() {
+        // 0x206 is reached both from 0x202, with I = 0xFFC, and from the
+        // LD I at 0x204, so I isn't fixed there.
+        let analysis = Analysis::new(&[0xAF, 0xFC, 0x12, 0x06, 0xA2, 0x10, 0xD0, 0x1F, 0x12, 0x04]);
+        assert_eq!(analysis.index_at(0x206), None);
+
+        let analysis = Analysis::new(&[0xA2, 0x10, 0x12, 0x06, 0xAF, 0xFC, 0xD0, 0x1F, 0x12, 0x04]);
+        assert_eq!(analysis.index_at(0x206), None);
+    }
+
+    #[test]
+    fn index_follows_straight_line_code() {
+        // LD I, 0x20A; LD V0, 1; DRW V0, V0, 5; JP 0x206
+        let analysis = Analysis::new(&[0xA2, 0x0A, 0x60, 0x01, 0xD0, 0x05, 0x12, 0x06]);
+        assert_eq!(analysis.index_at(0x204), Some(0x20A));
+        assert_eq!(analysis.register_at(0x204, 0), Some(1));
+    }
+
+    #[test]
+    fn register_is_unknown_after_a_skip() {
+        // SE V1, 0; LD V0, 1; DRW V0, V0, 1; JP 0x206
+        let analysis = Analysis::new(&[0x31, 0x00, 0x60, 0x01, 0xD0, 0x01, 0x12, 0x06]);
+        assert_eq!(analysis.register_at(0x204, 0), None);
+    }
+
+    #[test]
+    fn blocks_step_over_four_byte_instructions() {
+        // LD I, long 0x0300; CLS; JP 0x206
+        let analysis = Analysis::new(&[0xF0, 0x00, 0x03, 0x00, 0x00, 0xE0, 0x12, 0x06]);
+        let blocks = analysis.blocks(&analysis.leaders());
+        let starts: Vec<_> = blocks.iter().map(|block| block.start).collect();
+        assert_eq!(starts, [0x200, 0x206]);
+        let addresses: Vec<_> = blocks[0].instructions.iter().map(|i| i.0).collect();
+        assert_eq!(addresses, [0x200, 0x204]);
+    }
+}
@@ -1,15 +1,122 @@
+#[cfg(feature = "gui")]
 use crate::core::Core;
-use raylib::core::audio::Sound;
+#[cfg(feature = "gui")]
+use raylib::core::audio::AudioStream;
 
-pub fn generate_wav_file(frequency: f32, duration: f32, sample_rate: u32) -> Vec<u8> {
-    // Spooky magic.
+pub const SAMPLE_RATE: u32 = 44100;
+#[cfg(feature = "gui")]
+const BUFFER_FRAMES: usize = 512;
+const RAMP_SECONDS: f32 = 0.002;
 
-    let num_samples = (sample_rate as f32 * duration) as usize;
-    let mut wav_data = Vec::new();
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Waveform {
+    Square,
+    Sine,
+    Triangle,
+    Noise,
+}
+
+impl Waveform {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "square" => Some(Self::Square),
+            "sine" => Some(Self::Sine),
+            "triangle" => Some(Self::Triangle),
+            "noise" => Some(Self::Noise),
+            _ => None,
+        }
+    }
+}
+
+pub struct Buzzer {
+    pub waveform: Waveform,
+    pub pitch: f32,
+    pub volume: f32,
+    pub muted: bool,
+    phase: f32,
+    level: f32,
+    noise: u16,
+    noise_sample: f32,
+}
+
+impl Buzzer {
+    pub fn new() -> Self {
+        Self {
+            waveform: Waveform::Square,
+            pitch: 440.0,
+            volume: 0.25,
+            muted: false,
+            phase: 0.0,
+            level: 0.0,
+            noise: 0xACE1,
+            noise_sample: 1.0,
+        }
+    }
+
+    // Generates samples in -1.0..=1.0. While `active` the envelope ramps up
+    // to full level, otherwise it ramps down, so starting and stopping the
+    // tone never cuts the waveform mid-cycle.
+    pub fn fill(&mut self, active: bool, out: &mut [f32]) {
+        let target = if active && !self.muted { 1.0 } else { 0.0 };
+        let ramp_step = 1.0 / (RAMP_SECONDS * SAMPLE_RATE as f32);
+        let phase_step = self.pitch / SAMPLE_RATE as f32;
+
+        for sample in out.iter_mut() {
+            if self.level < target {
+                self.level = (self.level + ramp_step).min(target);
+            } else if self.level > target {
+                self.level = (self.level - ramp_step).max(target);
+            }
+
+            if self.level == 0.0 {
+                self.phase = 0.0;
+                *sample = 0.0;
+                continue;
+            }
+
+            let wave = match self.waveform {
+                Waveform::Square => {
+                    if self.phase < 0.5 {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                }
+                Waveform::Sine => (2.0 * std::f32::consts::PI * self.phase).sin(),
+                Waveform::Triangle => 4.0 * (self.phase - 0.5).abs() - 1.0,
+                Waveform::Noise => self.noise_sample,
+            };
+            *sample = wave * self.level * self.volume;
+
+            self.phase += phase_step;
+            if self.phase >= 1.0 {
+                self.phase -= 1.0;
+                // 16-bit Galois LFSR, clocked once per period.
+                let bit = self.noise & 1;
+                self.noise >>= 1;
+                if bit == 1 {
+                    self.noise ^= 0xB400;
+                }
+                self.noise_sample = if bit == 1 { 1.0 } else { -1.0 };
+            }
+        }
+    }
+}
+
+impl Default for Buzzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub const SAMPLES_PER_FRAME: usize = SAMPLE_RATE as usize / 60;
+
+pub fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let data_size = (samples.len() * 2) as u32;
+    let mut wav_data = Vec::with_capacity(44 + samples.len() * 2);
 
     wav_data.extend_from_slice(b"RIFF");
-    let file_size = 36 + (num_samples * 2) as u32;
-    wav_data.extend_from_slice(&file_size.to_le_bytes());
+    wav_data.extend_from_slice(&(36 + data_size).to_le_bytes());
     wav_data.extend_from_slice(b"WAVE");
 
     wav_data.extend_from_slice(b"fmt ");
@@ -23,21 +130,147 @@ pub fn generate_wav_file(frequency: f32, duration: f32, sample_rate: u32) -> Vec
     wav_data.extend_from_slice(&16u16.to_le_bytes());
 
     wav_data.extend_from_slice(b"data");
-    let data_size = (num_samples * 2) as u32;
     wav_data.extend_from_slice(&data_size.to_le_bytes());
-
-    for i in 0..num_samples {
-        let t = i as f32 / sample_rate as f32;
-        let sample = (2.0 * std::f32::consts::PI * frequency * t).sin();
-        let sample_i16 = (sample * 32767.0) as i16;
-        wav_data.extend_from_slice(&sample_i16.to_le_bytes());
+    for sample in samples {
+        wav_data.extend_from_slice(&sample.to_le_bytes());
     }
 
     wav_data
 }
 
-pub fn create_beep_sound(core: &Core) -> Option<Sound> {
-    let wav_data = generate_wav_file(440.0, 0.1, 44100);
-    let wave = core.audio.new_wave_from_memory(".wav", &wav_data).ok()?;
-    Some(core.audio.new_sound_from_wave(&wave).ok()?)
+pub struct WavRecorder {
+    samples: Vec<i16>,
+}
+
+impl WavRecorder {
+    pub fn new() -> Self {
+        Self {
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, samples: &[f32]) {
+        self.samples.extend(
+            samples
+                .iter()
+                .map(|sample| (sample * 32767.0).round() as i16),
+        );
+    }
+
+    pub fn finish(&self) -> Vec<u8> {
+        encode_wav(&self.samples, SAMPLE_RATE)
+    }
+}
+
+impl Default for WavRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "gui")]
+pub struct BuzzerStream<'a> {
+    stream: AudioStream<'a>,
+    samples: Vec<f32>,
+    pcm: Vec<u8>,
+    pub recorder: Option<WavRecorder>,
+}
+
+#[cfg(feature = "gui")]
+impl<'a> BuzzerStream<'a> {
+    // 8-bit mono: raylib-rs passes the slice length in bytes where raylib
+    // expects a frame count, so one byte per frame is the only size that
+    // doesn't over-read.
+    pub fn new(core: &'a Core) -> Self {
+        core.audio
+            .set_audio_stream_buffer_size_default(BUFFER_FRAMES as i32);
+        let stream = core.audio.new_audio_stream(SAMPLE_RATE, 8, 1);
+        stream.play();
+        Self {
+            stream,
+            samples: vec![0.0; BUFFER_FRAMES],
+            pcm: vec![128; BUFFER_FRAMES],
+            recorder: None,
+        }
+    }
+
+    // Refills the stream on demand, so the tone follows the sound timer with
+    // at most a couple of buffers of latency.
+    pub fn update(&mut self, buzzer: &mut Buzzer, active: bool) {
+        while self.stream.is_processed() {
+            buzzer.fill(active, &mut self.samples);
+            if let Some(recorder) = &mut self.recorder {
+                recorder.push(&self.samples);
+            }
+            for (pcm, sample) in self.pcm.iter_mut().zip(&self.samples) {
+                *pcm = (128.0 + sample * 127.0).round() as u8;
+            }
+            self.stream.update(&self.pcm);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(data: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([data[at], data[at + 1]])
+    }
+
+    fn u32_at(data: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn wav_header_and_data() {
+        let wav = encode_wav(&[0, 1, -1, i16::MAX, i16::MIN], 22050);
+        assert_eq!(wav.len(), 44 + 10);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(&wav, 4), 36 + 10);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32_at(&wav, 16), 16);
+        // PCM, mono, 22050 Hz, 44100 bytes/s, 2-byte frames of 16 bits.
+        assert_eq!(u16_at(&wav, 20), 1);
+        assert_eq!(u16_at(&wav, 22), 1);
+        assert_eq!(u32_at(&wav, 24), 22050);
+        assert_eq!(u32_at(&wav, 28), 44100);
+        assert_eq!(u16_at(&wav, 32), 2);
+        assert_eq!(u16_at(&wav, 34), 16);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(&wav, 40), 10);
+        assert_eq!(&wav[44..], [0, 0, 1, 0, 0xFF, 0xFF, 0xFF, 0x7F, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn tone_starts_with_the_sound_timer_and_ramps_out_after() {
+        // Two silent frames, three with the sound timer running, three silent.
+        let frames = [false, false, true, true, true, false, false, false];
+        let mut buzzer = Buzzer::new();
+        let mut recorder = WavRecorder::new();
+        let mut samples = vec![0.0; SAMPLES_PER_FRAME];
+        for active in frames {
+            buzzer.fill(active, &mut samples);
+            recorder.push(&samples);
+        }
+        let wav = recorder.finish();
+        assert_eq!(u32_at(&wav, 24), SAMPLE_RATE);
+        assert_eq!(wav.len(), 44 + frames.len() * SAMPLES_PER_FRAME * 2);
+
+        let audible: Vec<usize> = wav[44..]
+            .chunks(2)
+            .enumerate()
+            .filter(|(_, sample)| *sample != [0, 0])
+            .map(|(index, _)| index)
+            .collect();
+        let start = 2 * SAMPLES_PER_FRAME;
+        let stop = 5 * SAMPLES_PER_FRAME;
+        // The level falls a step per sample from `stop` and reaches zero on
+        // the ramp's last step.
+        let ramp = (RAMP_SECONDS * SAMPLE_RATE as f32).ceil() as usize;
+        let last = stop + ramp - 2;
+        assert_eq!(audible.first(), Some(&start));
+        assert_eq!(audible.last(), Some(&last));
+        assert_eq!(audible.len(), last + 1 - start);
+    }
 }
@@ -0,0 +1,247 @@
+use crate::cpu::{Cpu, Engine};
+use crate::disasm;
+use crate::json::Value;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_INSTRUCTIONS: u64 = 5_000_000;
+
+pub struct BenchOptions {
+    // ROM files, or directories to search for them.
+    pub rom_paths: Vec<String>,
+    pub instructions: u64,
+    pub engine: Engine,
+    pub json: bool,
+    // Results from an earlier `--json` run to compare against.
+    pub baseline: Option<String>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            rom_paths: Vec::new(),
+            instructions: DEFAULT_INSTRUCTIONS,
+            engine: Engine::Interpreter,
+            json: false,
+            baseline: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct ClassTime {
+    pub count: u64,
+    pub time: Duration,
+}
+
+pub struct BenchResult {
+    pub rom: String,
+    pub instructions: u64,
+    pub frames: u64,
+    pub elapsed: Duration,
+    // Keyed by `Instruction::class`.
+    pub classes: BTreeMap<&'static str, ClassTime>,
+}
+
+// Runs at least `instructions` instructions, in whole frames, as fast as the
+// CPU goes, then runs them again from the same start timing each instruction
+// for the per-class breakdown.
+pub fn run(rom: &str, cpu: Cpu, instructions: u64) -> BenchResult {
+    let frames = instructions
+        .div_ceil(cpu.cycles_per_frame.max(1) as u64)
+        .max(1);
+    let profiled = cpu.clone();
+
+    let mut cpu = cpu;
+    let start = Instant::now();
+    for _ in 0..frames {
+        cpu.run_frame();
+    }
+    let elapsed = start.elapsed();
+
+    BenchResult {
+        rom: rom.to_string(),
+        instructions: frames * cpu.cycles_per_frame as u64,
+        frames,
+        elapsed,
+        classes: profile(profiled, frames),
+    }
+}
+
+// The cost of reading the clock, taken off every timed instruction.
+fn timer_overhead() -> Duration {
+    const SAMPLES: u32 = 10_000;
+    let mut total = Duration::ZERO;
+    for _ in 0..SAMPLES {
+        let start = Instant::now();
+        total += start.elapsed();
+    }
+    total / SAMPLES
+}
+
+// Timing instructions one by one slows them all down, so these times are for
+// comparing classes with each other rather than with the untimed run.
+fn profile(mut cpu: Cpu, frames: u64) -> BTreeMap<&'static str, ClassTime> {
+    let overhead = timer_overhead();
+    let mut classes: BTreeMap<&'static str, ClassTime> = BTreeMap::new();
+    for _ in 0..frames {
+        for _ in 0..cpu.cycles_per_frame {
+            let pc = cpu.program_counter;
+            let opcode = (cpu.memory.read(pc) as u16) << 8 | cpu.memory.read(pc + 1) as u16;
+            let class = disasm::decode(opcode).class();
+
+            let start = Instant::now();
+            cpu.cycle();
+            let time = start.elapsed().saturating_sub(overhead);
+
+            let entry = classes.entry(class).or_default();
+            entry.count += 1;
+            entry.time += time;
+        }
+        cpu.tick_timers();
+    }
+    classes
+}
+
+impl BenchResult {
+    pub fn instructions_per_second(&self) -> f64 {
+        self.instructions as f64 / self.elapsed.as_secs_f64()
+    }
+
+    pub fn frames_per_second(&self) -> f64 {
+        self.frames as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn profiled_time(&self) -> Duration {
+        self.classes.values().map(|class| class.time).sum()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let total = self.profiled_time().as_secs_f64();
+        let classes = self
+            .classes
+            .iter()
+            .map(|(&name, class)| {
+                let fields = [
+                    ("count", Value::Number(class.count as f64)),
+                    ("nanoseconds", Value::Number(class.time.as_nanos() as f64)),
+                    ("share", Value::Number(share(class.time, total))),
+                ];
+                (name.to_string(), object(fields))
+            })
+            .collect();
+
+        object([
+            ("rom", Value::String(self.rom.clone())),
+            ("instructions", Value::Number(self.instructions as f64)),
+            ("frames", Value::Number(self.frames as f64)),
+            ("seconds", Value::Number(self.elapsed.as_secs_f64())),
+            (
+                "instructions_per_second",
+                Value::Number(self.instructions_per_second().round()),
+            ),
+            (
+                "frames_per_second",
+                Value::Number(self.frames_per_second().round()),
+            ),
+            ("classes", Value::Object(classes)),
+        ])
+    }
+
+    // A summary line and a table of instruction classes, slowest first.
+    // `baseline` is the instructions per second of an earlier run.
+    pub fn report(&self, baseline: Option<f64>) -> String {
+        let change = match baseline {
+            Some(before) if before > 0.0 => format!(
+                " ({:+.1}% vs baseline)",
+                (self.instructions_per_second() / before - 1.0) * 100.0
+            ),
+            _ => String::new(),
+        };
+        let mut lines = vec![
+            self.rom.clone(),
+            format!(
+                "  {} instructions in {:.3} s: {:.2}M instructions/s, {:.0} frames/s{}",
+                self.instructions,
+                self.elapsed.as_secs_f64(),
+                self.instructions_per_second() / 1e6,
+                self.frames_per_second(),
+                change
+            ),
+            format!(
+                "  {:<16} {:>10} {:>7} {:>9}",
+                "class", "count", "time", "ns each"
+            ),
+        ];
+
+        let total = self.profiled_time().as_secs_f64();
+        let mut classes: Vec<_> = self.classes.iter().collect();
+        classes.sort_by_key(|(_, class)| std::cmp::Reverse(class.time));
+        for (name, class) in classes {
+            lines.push(format!(
+                "  {:<16} {:>10} {:>6.1}% {:>9.1}",
+                name,
+                class.count,
+                share(class.time, total) * 100.0,
+                class.time.as_nanos() as f64 / class.count as f64
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+fn share(time: Duration, total: f64) -> f64 {
+    if total > 0.0 {
+        time.as_secs_f64() / total
+    } else {
+        0.0
+    }
+}
+
+fn object<const N: usize>(fields: [(&str, Value); N]) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+// The whole run as one JSON document. ROMs that failed are listed with their
+// error instead of numbers.
+pub fn to_json(options: &BenchOptions, results: &[Result<BenchResult, (String, String)>]) -> Value {
+    let results = results
+        .iter()
+        .map(|result| match result {
+            Ok(result) => result.to_json(),
+            Err((rom, error)) => object([
+                ("rom", Value::String(rom.clone())),
+                ("error", Value::String(error.clone())),
+            ]),
+        })
+        .collect();
+    object([
+        (
+            "version",
+            Value::String(env!("CARGO_PKG_VERSION").to_string()),
+        ),
+        ("engine", Value::String(options.engine.name().to_string())),
+        ("instructions", Value::Number(options.instructions as f64)),
+        ("results", Value::Array(results)),
+    ])
+}
+
+// Instructions per second by ROM from an earlier `--json` run.
+pub fn baseline(document: &Value) -> BTreeMap<String, f64> {
+    let Some(Value::Array(results)) = document.get("results") else {
+        return BTreeMap::new();
+    };
+    results
+        .iter()
+        .filter_map(|result| {
+            let rom = result.get("rom")?.as_str()?;
+            let speed = result.get("instructions_per_second")?.as_f64()?;
+            Some((rom.to_string(), speed))
+        })
+        .collect()
+}
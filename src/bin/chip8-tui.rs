@@ -0,0 +1,291 @@
+use chip8_emulator::frontend::{
+    AudioSink, Command, Emulator, Frame, Input, InputSource, VideoSink,
+};
+use chip8_emulator::{cli, cpu, keyboard, rom, tui, ui};
+use std::env;
+use std::io::{self, Read, Write};
+use std::process::{self, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const FRAME_TIME: Duration = Duration::from_micros(16_667);
+const DEFAULT_KEY_HOLD_MS: u64 = 200;
+const CTRL_C: u8 = 3;
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = process::Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!("stty {} failed", args.join(" "))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Puts the terminal in raw mode on the alternate screen, and restores it on
+// drop (including when the emulator panics).
+struct Terminal {
+    saved_mode: String,
+    columns: usize,
+}
+
+impl Terminal {
+    fn enter() -> io::Result<Self> {
+        let saved_mode = stty(&["-g"])?;
+        let columns = stty(&["size"])?
+            .split_whitespace()
+            .nth(1)
+            .and_then(|columns| columns.parse().ok())
+            .filter(|&columns| columns > 0)
+            .unwrap_or(80);
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l\x1b[2J");
+        io::stdout().flush()?;
+        Ok(Self {
+            saved_mode,
+            columns,
+        })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved_mode]);
+    }
+}
+
+fn spawn_input_reader() -> mpsc::Receiver<u8> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut byte = [0u8; 1];
+        while let Ok(1) = stdin.read(&mut byte) {
+            if sender.send(byte[0]).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+// Terminals only report key presses (and auto-repeats while a key is held),
+// never releases, so a key counts as held until no press has arrived for
+// `hold`. It should be longer than the terminal's repeat interval.
+struct TerminalInput {
+    bytes: mpsc::Receiver<u8>,
+    keymap: keyboard::Keymap,
+    last_pressed: [Option<Instant>; 16],
+    hold: Duration,
+}
+
+impl InputSource for TerminalInput {
+    fn poll(&mut self, _frame: u32) -> Input {
+        let now = Instant::now();
+        let mut commands = Vec::new();
+
+        while let Ok(byte) = self.bytes.try_recv() {
+            match byte {
+                CTRL_C => commands.push(Command::Quit),
+                b' ' => commands.push(Command::TogglePause),
+                b'\t' => commands.push(Command::ToggleStepMode),
+                b'b' | b'B' => commands.push(Command::ToggleFlicker),
+                _ => {
+                    if matches!(byte, b's' | b'S') {
+                        commands.push(Command::Step);
+                    }
+                    if let Some(key) = self.keymap.key_for_char(byte as char) {
+                        self.last_pressed[key as usize] = Some(now);
+                    }
+                }
+            }
+        }
+
+        let keys = self
+            .last_pressed
+            .iter()
+            .enumerate()
+            .filter(|(_, pressed)| pressed.is_some_and(|at| now.duration_since(at) < self.hold))
+            .fold(0, |mask, (i, _)| mask | (1 << i));
+
+        Input { keys, commands }
+    }
+}
+
+struct TerminalVideo {
+    columns: usize,
+    braille: bool,
+    last_frame: Instant,
+    fps: u32,
+    frames_this_second: u32,
+    second_start: Instant,
+}
+
+impl VideoSink for TerminalVideo {
+    fn present(&mut self, frame: &Frame) {
+        let mut text = String::from("\x1b[H");
+        if self.braille {
+            text.push_str(&tui::render_braille(frame.display, frame.palette));
+        } else {
+            text.push_str(&tui::render_half_blocks(frame.display, frame.palette));
+        }
+        let status: String = ui::status_text(frame.cpu, frame.rom_path, frame.patched, self.fps)
+            .chars()
+            .take(self.columns)
+            .collect();
+        text.push_str("\x1b[2K");
+        text.push_str(&status);
+
+        let mut stdout = io::stdout();
+        let _ = stdout
+            .write_all(text.as_bytes())
+            .and_then(|_| stdout.flush());
+
+        self.frames_this_second += 1;
+        if self.second_start.elapsed() >= Duration::from_secs(1) {
+            self.fps = self.frames_this_second;
+            self.frames_this_second = 0;
+            self.second_start = Instant::now();
+        }
+
+        if let Some(remaining) = FRAME_TIME.checked_sub(self.last_frame.elapsed()) {
+            thread::sleep(remaining);
+        }
+        self.last_frame = Instant::now();
+    }
+}
+
+// The only sound a terminal can make: ring the bell when the buzzer starts.
+struct TerminalBell {
+    was_active: bool,
+    muted: bool,
+}
+
+impl AudioSink for TerminalBell {
+    fn update(&mut self, active: bool) {
+        if active && !self.was_active && !self.muted {
+            print!("\x07");
+        }
+        self.was_active = active;
+    }
+
+    fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+    }
+}
+
+fn print_usage(exe_name: &str) {
+    eprintln!("Usage: {} <rom_path> [options]", exe_name);
+    eprintln!("\nOptions:");
+    eprintln!("  --braille          Render with braille dots instead of half blocks");
+    eprintln!(
+        "  --key-hold <ms>    How long a key stays down after a key press (default {})",
+        DEFAULT_KEY_HOLD_MS
+    );
+    eprintln!("  --keymap <keys>    Keys for CHIP-8 keys 0-F (default x123qweasdzc4rfv)");
+    eprintln!("\nControls: keypad on 1234/QWER/ASDF/ZXCV, Space: pause, Tab: step mode,");
+    eprintln!("S: single-step (in step mode), B: flicker reduction, Ctrl-C: quit");
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let exe_name = std::path::Path::new(&args[0])
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&args[0]);
+
+    let mut rom_path = None;
+    let mut braille = false;
+    let mut key_hold_ms = DEFAULT_KEY_HOLD_MS;
+    let mut keymap = keyboard::Keymap::default();
+
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--braille" => braille = true,
+            "--key-hold" => match rest.next().and_then(|ms| ms.parse().ok()) {
+                Some(ms) => key_hold_ms = ms,
+                None => {
+                    eprintln!("--key-hold expects a number of milliseconds");
+                    process::exit(1);
+                }
+            },
+            "--keymap" => match rest.next().and_then(|keys| keyboard::Keymap::parse(keys)) {
+                Some(parsed) => keymap = parsed,
+                None => {
+                    eprintln!("--keymap expects 16 distinct keys for CHIP-8 keys 0-F");
+                    process::exit(1);
+                }
+            },
+            _ if rom_path.is_none() && !arg.starts_with("--") => rom_path = Some(arg.clone()),
+            _ => {
+                eprintln!("Unexpected argument: {}", arg);
+                print_usage(exe_name);
+                process::exit(1);
+            }
+        }
+    }
+
+    let Some(rom_path) = rom_path else {
+        print_usage(exe_name);
+        process::exit(1);
+    };
+
+    let mut cpu = cpu::Cpu::new();
+    let rom = match rom::load_rom(&mut cpu, &rom_path, None) {
+        Ok(rom) => rom,
+        Err(e) => {
+            eprintln!("Failed to load ROM '{}': {}", rom_path, e);
+            process::exit(1);
+        }
+    };
+
+    // Speed, quirks and colours set by the ROM itself or its config file.
+    let settings = match cli::Options::from_args(std::slice::from_ref(&rom_path)) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    cpu.quirks = settings.quirks;
+    cpu.cycles_per_frame = settings.cycles_per_frame();
+    cpu.engine = settings.engine;
+
+    let terminal = match Terminal::enter() {
+        Ok(terminal) => terminal,
+        Err(e) => {
+            eprintln!("Failed to set up the terminal: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let input = TerminalInput {
+        bytes: spawn_input_reader(),
+        keymap,
+        last_pressed: [None; 16],
+        hold: Duration::from_millis(key_hold_ms),
+    };
+    let video = TerminalVideo {
+        columns: terminal.columns,
+        braille,
+        last_frame: Instant::now(),
+        fps: 0,
+        frames_this_second: 0,
+        second_start: Instant::now(),
+    };
+    let audio = TerminalBell {
+        was_active: false,
+        muted: false,
+    };
+
+    let mut emulator = Emulator::new(cpu, &rom_path, video, audio, input);
+    emulator.palette = settings.palette;
+    emulator.patched = rom.patch.is_some();
+    emulator.run();
+
+    drop(terminal);
+}
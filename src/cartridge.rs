@@ -0,0 +1,167 @@
+use crate::json::{self, Value};
+use crate::{gif, octo};
+
+// Octo cartridges are GIF images with a JSON payload hidden in the low two
+// bits of each pixel's palette index, four pixels to a byte, high bits first,
+// running on through every frame. The payload starts with its length as a
+// 32-bit big-endian number and holds `{"program": <Octo source>, "options":
+// {...}}`.
+pub struct Cartridge {
+    pub source: String,
+    pub options: Value,
+}
+
+pub fn is_cartridge(data: &[u8]) -> bool {
+    data.starts_with(b"GIF8")
+}
+
+pub fn decode(data: &[u8]) -> Result<Cartridge, String> {
+    let image = gif::decode(data)?;
+    let mut payload = Vec::new();
+    for frame in &image.frames {
+        payload.extend(frame.chunks_exact(4).map(|pixels| {
+            pixels
+                .iter()
+                .fold(0u8, |byte, &pixel| (byte << 2) | (pixel & 3))
+        }));
+    }
+
+    let not_a_cartridge = || "the GIF has no Octo cartridge payload".to_string();
+    let header = payload.get(..4).ok_or_else(not_a_cartridge)?;
+    let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let body = payload.get(4..4 + len).ok_or_else(not_a_cartridge)?;
+    // Older cartridges store one byte per character rather than UTF-8.
+    let text = match std::str::from_utf8(body) {
+        Ok(text) => text.to_string(),
+        Err(_) => body.iter().map(|&byte| byte as char).collect(),
+    };
+    let payload = json::parse(&text).map_err(|_| not_a_cartridge())?;
+
+    let source = payload
+        .get("program")
+        .and_then(Value::as_str)
+        .ok_or_else(not_a_cartridge)?
+        .to_string();
+    let options = payload.get("options").cloned().unwrap_or(Value::Null);
+    Ok(Cartridge { source, options })
+}
+
+// Octo option name, our quirk name, and whether Octo's flag means the quirk
+// is off. The display wait and VF ordering quirks have no equivalent here.
+const QUIRK_OPTIONS: [(&str, &str, bool); 5] = [
+    ("logicQuirks", "vfreset", false),
+    ("loadStoreQuirks", "memory", true),
+    ("shiftQuirks", "shifting", false),
+    ("jumpQuirks", "jumping", false),
+    ("clipQuirks", "clipping", false),
+];
+
+impl Cartridge {
+    pub fn assemble(&self) -> Result<Vec<u8>, String> {
+        octo::assemble(&self.source)
+    }
+
+    // The cartridge's settings as command-line options, so a config file or
+    // the command line can still override them.
+    pub fn settings(&self) -> Vec<String> {
+        let option = |name: &str| self.options.get(name);
+        let mut args = Vec::new();
+
+        if let Some(tickrate) = option("tickrate").and_then(Value::as_f64) {
+            args.push("--speed".to_string());
+            args.push((tickrate.max(1.0) as u32).saturating_mul(60).to_string());
+        }
+
+        let quirks: Vec<String> = QUIRK_OPTIONS
+            .iter()
+            .filter_map(|&(octo_name, name, inverted)| {
+                let on = option(octo_name)?.as_bool()? != inverted;
+                Some(format!("{}{}", if on { "" } else { "-" }, name))
+            })
+            .collect();
+        if !quirks.is_empty() {
+            args.push("--quirks".to_string());
+            args.push(quirks.join(","));
+        }
+
+        let color = |name: &str| option(name).and_then(Value::as_str);
+        if let (Some(background), Some(foreground)) = (color("backgroundColor"), color("fillColor"))
+        {
+            args.push("--palette".to_string());
+            args.push(format!("{},{}", background, foreground));
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gif::GifEncoder;
+
+    // A GIF carrying `json` in its pixels the way Octo saves it.
+    fn cartridge(json: &str) -> Vec<u8> {
+        let mut payload = (json.len() as u32).to_be_bytes().to_vec();
+        payload.extend_from_slice(json.as_bytes());
+        let mut pixels: Vec<u8> = payload
+            .iter()
+            .flat_map(|&byte| [6, 4, 2, 0].map(|shift| (byte >> shift) & 3))
+            .collect();
+        let width = 64;
+        pixels.resize(pixels.len().div_ceil(width) * width, 0);
+        let palette = [[0, 0, 0], [85, 85, 85], [170, 170, 170], [255, 255, 255]];
+        let mut gif = GifEncoder::new(width as u16, (pixels.len() / width) as u16, &palette);
+        gif.add_frame(&pixels, 0);
+        gif.finish()
+    }
+
+    fn with_options(options: &str) -> Cartridge {
+        let json = format!(
+            r#"{{"program": ": main\n  v0 := 1\n  jump main", "options": {{{}}}}}"#,
+            options
+        );
+        decode(&cartridge(&json)).unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_a_gif() {
+        let cartridge = with_options(r#""tickrate": 20"#);
+        assert_eq!(cartridge.source, ": main\n  v0 := 1\n  jump main");
+        assert_eq!(cartridge.assemble().unwrap(), [0x60, 0x01, 0x12, 0x00]);
+        assert_eq!(cartridge.settings(), ["--speed", "1200"]);
+    }
+
+    #[test]
+    fn quirk_flags_map_to_quirks() {
+        let on = with_options(
+            r#""logicQuirks": true, "loadStoreQuirks": true, "shiftQuirks": true, "jumpQuirks": true, "clipQuirks": true"#,
+        );
+        // Octo's load/store quirk leaves I alone, which is our memory quirk
+        // off.
+        assert_eq!(
+            on.settings(),
+            ["--quirks", "vfreset,-memory,shifting,jumping,clipping"]
+        );
+
+        let off = with_options(
+            r#""logicQuirks": false, "loadStoreQuirks": false, "shiftQuirks": false, "jumpQuirks": false, "clipQuirks": false"#,
+        );
+        assert_eq!(
+            off.settings(),
+            ["--quirks", "-vfreset,memory,-shifting,-jumping,-clipping"]
+        );
+    }
+
+    #[test]
+    fn colours_become_a_palette() {
+        let cartridge = with_options(r##""backgroundColor": "#111111", "fillColor": "#EEEEEE""##);
+        assert_eq!(cartridge.settings(), ["--palette", "#111111,#EEEEEE"]);
+    }
+
+    #[test]
+    fn a_plain_gif_is_not_a_cartridge() {
+        let mut gif = GifEncoder::new(4, 1, &[[0, 0, 0], [255, 255, 255]]);
+        gif.add_frame(&[0, 1, 0, 1], 0);
+        assert!(decode(&gif.finish()).is_err());
+    }
+}
@@ -0,0 +1,613 @@
+use crate::audio::{Buzzer, Waveform};
+use crate::bench::BenchOptions;
+use crate::coverage::{CoverageOptions, Report};
+use crate::cpu::{Engine, CYCLES_PER_FRAME};
+use crate::display::{Palette, PIXEL_SIZE};
+use crate::headless::{FrameCapture, HeadlessRun};
+use crate::keyboard::Keymap;
+use crate::layout::Scaling;
+use crate::quirks::{Quirks, Variant};
+use crate::rom;
+use crate::sprites::{Selection, SpriteOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::slice::Iter;
+
+pub enum Command {
+    Run(Options),
+    List,
+    Info(Options),
+    Disasm(Options),
+    Bench(BenchOptions),
+    // Runs ROMs under both engines in lockstep to check they agree.
+    CheckEngine(BenchOptions),
+    // Runs a ROM, once per replay, and reports which bytes were used how.
+    Coverage(CoverageOptions),
+    // Reports likely bugs in ROMs without running them.
+    Lint(Vec<String>),
+    // Shows a stretch of memory as sprites, marking those the ROM draws.
+    Sprites(SpriteOptions),
+    // Writes the ROM's control-flow graph, and optionally its call graph, as
+    // Graphviz DOT.
+    Cfg {
+        options: Options,
+        out_path: String,
+        calls_path: Option<String>,
+    },
+    // Writes the ROM out as Rust source.
+    Recompile {
+        options: Options,
+        out_path: String,
+    },
+    // Writes an IPS patch turning `original` into `modified`.
+    MakeIps {
+        original: String,
+        modified: String,
+        out_path: String,
+    },
+    Help,
+}
+
+pub struct Options {
+    pub rom_path: Option<String>,
+    pub patch_path: Option<String>,
+    pub config_path: Option<PathBuf>,
+    pub speed: u32,
+    pub variant: Option<Variant>,
+    pub quirks: Quirks,
+    pub engine: Engine,
+    pub scale: usize,
+    pub scaling: Scaling,
+    pub grid: bool,
+    pub palette: Palette,
+    pub seed: Option<u64>,
+    pub fullscreen: bool,
+    pub load_state: Option<String>,
+    pub save_state: Option<String>,
+    pub trace: bool,
+    // Where to write the profiler's report on exit.
+    pub profile_path: Option<String>,
+    pub keymap: Keymap,
+    pub buzzer: Buzzer,
+    pub headless: HeadlessRun,
+    pub replay_path: Option<String>,
+    pub record_input_path: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            rom_path: None,
+            patch_path: None,
+            config_path: None,
+            speed: CYCLES_PER_FRAME * 60,
+            variant: None,
+            quirks: Quirks::default(),
+            engine: Engine::Interpreter,
+            scale: PIXEL_SIZE as usize,
+            scaling: Scaling::Integer,
+            grid: false,
+            palette: Palette::default(),
+            seed: None,
+            fullscreen: false,
+            load_state: None,
+            save_state: None,
+            trace: false,
+            profile_path: None,
+            keymap: Keymap::default(),
+            buzzer: Buzzer::new(),
+            headless: HeadlessRun::default(),
+            replay_path: None,
+            record_input_path: None,
+        }
+    }
+}
+
+fn value<'a>(rest: &mut Iter<'a, String>, flag: &str, what: &str) -> Result<&'a str, String> {
+    rest.next()
+        .map(String::as_str)
+        .ok_or_else(|| format!("{} expects {}", flag, what))
+}
+
+fn number<T: std::str::FromStr>(rest: &mut Iter<String>, flag: &str) -> Result<T, String> {
+    value(rest, flag, "a number")?
+        .parse()
+        .map_err(|_| format!("{} expects a number", flag))
+}
+
+fn engine(rest: &mut Iter<String>, flag: &str) -> Result<Engine, String> {
+    let name = value(rest, flag, "an engine")?;
+    Engine::parse(name).ok_or_else(|| {
+        format!(
+            "unknown engine '{}' (expected {})",
+            name,
+            Engine::names().join(", ")
+        )
+    })
+}
+
+fn frame_capture(rest: &mut Iter<String>, flag: &str) -> Result<FrameCapture, String> {
+    let frames = number(rest, flag)?;
+    let out_path = value(rest, flag, "<frames> <out path>")?.to_string();
+    Ok(FrameCapture { frames, out_path })
+}
+
+impl Options {
+    // Parses command-line options. Settings embedded in the ROM and a config
+    // file next to it are applied first so the command line overrides them.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let rom_path = match options.apply(args)?.as_slice() {
+            [] => None,
+            [rom_path] => Some(rom_path.clone()),
+            [_, extra, ..] => return Err(format!("unexpected argument '{}'", extra)),
+        };
+
+        // Settings embedded in the ROM come first, then its config file,
+        // then the command line.
+        let embedded = rom_path
+            .as_deref()
+            .map(rom::embedded_settings)
+            .unwrap_or_default();
+        let config_path = rom_path
+            .as_deref()
+            .map(config_path)
+            .filter(|path| path.is_file());
+        if !embedded.is_empty() || config_path.is_some() {
+            options = Self::default();
+            options
+                .apply(&embedded)
+                .map_err(|e| format!("settings in the ROM: {}", e))?;
+            if let Some(config_path) = &config_path {
+                options.apply_config(config_path)?;
+            }
+            options.apply(args)?;
+            options.config_path = config_path;
+        }
+
+        options.rom_path = rom_path;
+        options.validate()?;
+        Ok(options)
+    }
+
+    // Returns the positional arguments.
+    fn apply(&mut self, args: &[String]) -> Result<Vec<String>, String> {
+        let mut positional = Vec::new();
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            let flag = arg.as_str();
+            match flag {
+                "--speed" => self.speed = number(&mut rest, flag)?,
+                "--patch" => self.patch_path = Some(value(&mut rest, flag, "a path")?.to_string()),
+                "--variant" => {
+                    let name = value(&mut rest, flag, "chip8 or schip")?;
+                    let variant = Variant::parse(name).ok_or_else(|| {
+                        format!("unknown variant '{}' (expected chip8 or schip)", name)
+                    })?;
+                    self.variant = Some(variant);
+                    self.quirks = variant.quirks();
+                }
+                "--quirks" => self
+                    .quirks
+                    .apply(value(&mut rest, flag, "a list of quirks")?)?,
+                "--engine" => self.engine = engine(&mut rest, flag)?,
+                "--scale" => self.scale = number(&mut rest, flag)?,
+                "--scaling" => {
+                    let name = value(&mut rest, flag, "a scaling mode")?;
+                    self.scaling = Scaling::parse(name).ok_or_else(|| {
+                        format!(
+                            "unknown scaling '{}' (expected {})",
+                            name,
+                            Scaling::names().join(", ")
+                        )
+                    })?;
+                }
+                "--grid" => self.grid = true,
+                "--palette" => {
+                    let spec = value(&mut rest, flag, "a palette")?;
+                    self.palette = Palette::parse(spec).ok_or_else(|| {
+                        format!(
+                            "unknown palette '{}' (expected {} or <background>,<foreground> hex colours)",
+                            spec,
+                            Palette::names().join(", ")
+                        )
+                    })?;
+                }
+                "--seed" => self.seed = Some(number(&mut rest, flag)?),
+                "--mute" => self.buzzer.muted = true,
+                "--fullscreen" => self.fullscreen = true,
+                "--headless" => self.headless.forced = true,
+                "--frames" => self.headless.frames = number(&mut rest, flag)?,
+                "--load-state" => {
+                    self.load_state = Some(value(&mut rest, flag, "a path")?.to_string())
+                }
+                "--save-state" => {
+                    self.save_state = Some(value(&mut rest, flag, "a path")?.to_string())
+                }
+                "--trace" => self.trace = true,
+                "--profile" => {
+                    self.profile_path = Some(value(&mut rest, flag, "a path")?.to_string())
+                }
+                "--keymap" => {
+                    let spec = value(&mut rest, flag, "16 keys")?;
+                    self.keymap = Keymap::parse(spec).ok_or_else(|| {
+                        format!(
+                            "--keymap expects 16 distinct letter or digit keys for CHIP-8 keys 0-F, got '{}'",
+                            spec
+                        )
+                    })?;
+                }
+                "--screenshot-after" => {
+                    self.headless.screenshot = Some(frame_capture(&mut rest, flag)?)
+                }
+                "--record-gif" => self.headless.gif = Some(frame_capture(&mut rest, flag)?),
+                "--gif-scale" => self.headless.gif_scale = number(&mut rest, flag)?,
+                "--gif-skip" => self.headless.gif_skip = number(&mut rest, flag)?,
+                "--waveform" => {
+                    let name = value(&mut rest, flag, "a waveform")?;
+                    self.buzzer.waveform = Waveform::parse(name).ok_or_else(|| {
+                        format!("{} expects square, sine, triangle or noise", flag)
+                    })?;
+                }
+                "--pitch" => self.buzzer.pitch = number(&mut rest, flag)?,
+                "--volume" => self.buzzer.volume = number::<f32>(&mut rest, flag)? / 100.0,
+                "--record-wav" => {
+                    self.headless.wav_path = Some(value(&mut rest, flag, "a path")?.to_string())
+                }
+                "--replay" => {
+                    self.replay_path = Some(value(&mut rest, flag, "a path")?.to_string())
+                }
+                "--record-input" => {
+                    self.record_input_path = Some(value(&mut rest, flag, "a path")?.to_string())
+                }
+                _ if !flag.starts_with("--") => positional.push(arg.clone()),
+                _ => return Err(format!("unknown option '{}'", flag)),
+            }
+        }
+        Ok(positional)
+    }
+
+    // One option per line, written without the leading dashes:
+    //   speed = 600
+    //   quirks = -vfreset,jumping
+    //   mute
+    fn apply_config(&mut self, path: &Path) -> Result<(), String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut args = Vec::new();
+            match line.split_once('=') {
+                Some((key, value)) => {
+                    args.push(format!("--{}", key.trim()));
+                    args.push(value.trim().to_string());
+                }
+                None => args.push(format!("--{}", line)),
+            }
+
+            let error =
+                |message: String| format!("{}:{}: {}", path.display(), line_number + 1, message);
+            let positional = self.apply(&args).map_err(error)?;
+            if !positional.is_empty() {
+                return Err(error(format!("unexpected value in '{}'", line)));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.speed == 0 {
+            return Err("--speed must be at least 1 instruction per second".to_string());
+        }
+        if self.scale == 0 {
+            return Err("--scale must be at least 1".to_string());
+        }
+        let headless = &self.headless;
+        if headless.forced && !headless.is_bounded() {
+            return Err(
+                "--headless needs --frames, --screenshot-after or --record-gif to know when to stop"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn cycles_per_frame(&self) -> u32 {
+        (self.speed.saturating_add(30) / 60).max(1)
+    }
+}
+
+// `Pong.ch8` is configured by `Pong.cfg` in the same directory.
+pub fn config_path(rom_path: &str) -> PathBuf {
+    Path::new(rom_path).with_extension("cfg")
+}
+
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        return Ok(Command::Help);
+    }
+
+    let (command, rest) = match args.first().map(String::as_str) {
+        Some(
+            name @ ("run" | "list" | "info" | "disasm" | "bench" | "check-engine" | "coverage"
+            | "sprites" | "cfg" | "lint" | "recompile" | "make-ips" | "help"),
+        ) => (name, &args[1..]),
+        _ => ("run", args),
+    };
+
+    match command {
+        "help" => Ok(Command::Help),
+        "list" => match rest.first() {
+            Some(extra) => Err(format!("unexpected argument '{}'", extra)),
+            None => Ok(Command::List),
+        },
+        "bench" => parse_bench(rest).map(Command::Bench),
+        "check-engine" => parse_bench(rest).map(Command::CheckEngine),
+        "coverage" => parse_coverage(rest).map(Command::Coverage),
+        "sprites" => parse_sprites(rest).map(Command::Sprites),
+        "lint" if rest.is_empty() => {
+            Err("lint expects one or more ROMs or directories".to_string())
+        }
+        "lint" => match rest.iter().find(|arg| arg.starts_with("--")) {
+            Some(flag) => Err(format!("unknown lint option '{}'", flag)),
+            None => Ok(Command::Lint(rest.to_vec())),
+        },
+        "cfg" => match rest {
+            [rom_path, out_path, calls_path @ ..] if calls_path.len() <= 1 => Ok(Command::Cfg {
+                options: Options::from_args(std::slice::from_ref(rom_path))?,
+                out_path: out_path.clone(),
+                calls_path: calls_path.first().cloned(),
+            }),
+            _ => Err("cfg expects <rom_path> <out.dot> [<calls.dot>]".to_string()),
+        },
+        "recompile" => match rest {
+            [rom_path, out_path] => Ok(Command::Recompile {
+                options: Options::from_args(std::slice::from_ref(rom_path))?,
+                out_path: out_path.clone(),
+            }),
+            _ => Err("recompile expects <rom_path> <out.rs>".to_string()),
+        },
+        "make-ips" => match rest {
+            [original, modified, out_path] => Ok(Command::MakeIps {
+                original: original.clone(),
+                modified: modified.clone(),
+                out_path: out_path.clone(),
+            }),
+            _ => Err("make-ips expects <original> <modified> <out.ips>".to_string()),
+        },
+        _ => {
+            let options = Options::from_args(rest)?;
+            match command {
+                "run" => Ok(Command::Run(options)),
+                _ if options.rom_path.is_none() => Err(format!("{} expects a ROM path", command)),
+                "info" => Ok(Command::Info(options)),
+                _ => Ok(Command::Disasm(options)),
+            }
+        }
+    }
+}
+
+fn parse_bench(args: &[String]) -> Result<BenchOptions, String> {
+    let mut options = BenchOptions::default();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let flag = arg.as_str();
+        match flag {
+            "--instructions" => options.instructions = number(&mut rest, flag)?,
+            "--engine" => options.engine = engine(&mut rest, flag)?,
+            "--json" => options.json = true,
+            "--baseline" => options.baseline = Some(value(&mut rest, flag, "a path")?.to_string()),
+            _ if flag.starts_with("--") => return Err(format!("unknown bench option '{}'", flag)),
+            _ => options.rom_paths.push(arg.clone()),
+        }
+    }
+    if options.rom_paths.is_empty() {
+        return Err("bench expects one or more ROMs or directories".to_string());
+    }
+    if options.instructions == 0 {
+        return Err("--instructions must be at least 1".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_coverage(args: &[String]) -> Result<CoverageOptions, String> {
+    let mut rom_path = None;
+    let mut options = CoverageOptions {
+        rom_path: String::new(),
+        replays: Vec::new(),
+        frames: None,
+        merge: Vec::new(),
+        report: Report::Map,
+    };
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let flag = arg.as_str();
+        match flag {
+            "--replay" => options
+                .replays
+                .push(value(&mut rest, flag, "a path")?.to_string()),
+            "--frames" => options.frames = Some(number(&mut rest, flag)?),
+            "--merge" => options
+                .merge
+                .push(value(&mut rest, flag, "a path")?.to_string()),
+            "--json" => options.report = Report::Json,
+            "--disasm" => options.report = Report::Listing,
+            _ if flag.starts_with("--") => {
+                return Err(format!("unknown coverage option '{}'", flag))
+            }
+            _ if rom_path.is_some() => return Err(format!("unexpected argument '{}'", flag)),
+            _ => rom_path = Some(arg.clone()),
+        }
+    }
+    options.rom_path = rom_path.ok_or("coverage expects a ROM path")?;
+    Ok(options)
+}
+
+// `0x2EA`, or `2EA`: always hexadecimal, within memory.
+fn address(text: &str) -> Option<u16> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    u16::from_str_radix(digits, 16)
+        .ok()
+        .filter(|&address| (address as usize) < crate::memory::MEMORY_SIZE)
+}
+
+fn address_value(rest: &mut Iter<String>, flag: &str) -> Result<u16, String> {
+    let text = value(rest, flag, "an address")?;
+    address(text)
+        .ok_or_else(|| format!("{} expects an address such as 0x2EA, not '{}'", flag, text))
+}
+
+// `0x2EA:6,0x300`, each address with an optional height.
+fn selection(text: &str) -> Result<Selection, String> {
+    if text == "drawn" {
+        return Ok(Selection::Drawn);
+    }
+    let mut sprites = Vec::new();
+    for item in text.split(',') {
+        let (start, height) = match item.split_once(':') {
+            Some((start, height)) => (start, Some(height)),
+            None => (item, None),
+        };
+        let height = match height.map(str::parse) {
+            Some(Ok(height @ 1..=15)) => Some(height),
+            Some(_) => return Err(format!("bad sprite height in '{}'", item)),
+            None => None,
+        };
+        let start = address(start).ok_or_else(|| format!("bad sprite address '{}'", item))?;
+        sprites.push((start, height));
+    }
+    Ok(Selection::Sprites(sprites))
+}
+
+fn parse_sprites(args: &[String]) -> Result<SpriteOptions, String> {
+    let mut rom_path = None;
+    let mut options = SpriteOptions {
+        rom_path: String::new(),
+        range: None,
+        width: 8,
+        height: 8,
+        replays: Vec::new(),
+        frames: None,
+        select: Selection::Range,
+        png_path: None,
+        db: false,
+    };
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let flag = arg.as_str();
+        match flag {
+            "--from" => {
+                let to = options.range.map_or(0xFFF, |(_, to)| to);
+                options.range = Some((address_value(&mut rest, flag)?, to));
+            }
+            "--to" => {
+                let from = options.range.map_or(0x200, |(from, _)| from);
+                options.range = Some((from, address_value(&mut rest, flag)?));
+            }
+            "--height" => match number(&mut rest, flag)? {
+                height @ 1..=15 => (options.width, options.height) = (8, height),
+                _ => return Err("--height must be between 1 and 15".to_string()),
+            },
+            "--wide" => (options.width, options.height) = (16, 16),
+            "--replay" => options
+                .replays
+                .push(value(&mut rest, flag, "a path")?.to_string()),
+            "--frames" => options.frames = Some(number(&mut rest, flag)?),
+            "--select" => options.select = selection(value(&mut rest, flag, "addresses")?)?,
+            "--png" => options.png_path = Some(value(&mut rest, flag, "a path")?.to_string()),
+            "--db" => options.db = true,
+            _ if flag.starts_with("--") => {
+                return Err(format!("unknown sprites option '{}'", flag))
+            }
+            _ if rom_path.is_some() => return Err(format!("unexpected argument '{}'", flag)),
+            _ => rom_path = Some(arg.clone()),
+        }
+    }
+    options.rom_path = rom_path.ok_or("sprites expects a ROM path")?;
+    if let Some((from, to)) = options.range {
+        if to < from {
+            return Err("--to must not come before --from".to_string());
+        }
+    }
+    Ok(options)
+}
+
+pub fn usage(exe_name: &str) -> String {
+    let default = Options::default();
+    let lines = [
+        format!("Usage: {} [run] <rom_path> [options]", exe_name),
+        format!("       {} list", exe_name),
+        format!("       {} info <rom_path>", exe_name),
+        format!("       {} disasm <rom_path>", exe_name),
+        format!("       {} bench <rom_or_dir>... [--instructions <n>] [--engine <name>] [--json] [--baseline <old.json>]", exe_name),
+        format!("       {} check-engine <rom_or_dir>... [--instructions <n>]", exe_name),
+        format!("       {} coverage <rom_path> [--replay <input.log>]... [--frames <n>] [--merge <coverage.json>]... [--json | --disasm]", exe_name),
+        format!("       {} sprites <rom_path> [--from <addr>] [--to <addr>] [--height <n> | --wide] [--replay <input.log>]... [--frames <n>] [--select drawn|<addr>[:<n>],...] [--png <out.png>] [--db]", exe_name),
+        format!("       {} lint <rom_or_dir>...", exe_name),
+        format!("       {} cfg <rom_path> <out.dot> [<calls.dot>]", exe_name),
+        format!("       {} recompile <rom_path> <out.rs>", exe_name),
+        format!("       {} make-ips <original> <modified> <out.ips>", exe_name),
+        String::new(),
+        "Commands:".to_string(),
+        "  run      Run a ROM (the default when no command is given)".to_string(),
+        "  list     List the ROMs under chip8-roms/".to_string(),
+        "  info     Print details about a ROM without running it".to_string(),
+        "  disasm   Print a linear disassembly of a ROM".to_string(),
+        format!("  bench    Run ROMs flat out for {} instructions (or --instructions) and report", crate::bench::DEFAULT_INSTRUCTIONS),
+        "           their speed and time per instruction class; --json for comparisons".to_string(),
+        "  check-engine  Run ROMs under both engines side by side and report where they differ".to_string(),
+        format!("  coverage Run a ROM ({} frames, or each replay) and map the bytes run as code, drawn", crate::coverage::DEFAULT_FRAMES),
+        "           as sprites, read, written or never touched; merge runs to find untested paths".to_string(),
+        "  sprites  Show memory as 8xN or 16x16 sprites, marking those the ROM draws while it runs;".to_string(),
+        "           export a selection as a PNG sheet or assembler db lines".to_string(),
+        "  lint     Warn about likely bugs in ROMs without running them, as file:address lines".to_string(),
+        "  cfg      Write the control-flow graph of a ROM as Graphviz DOT, one cluster per".to_string(),
+        "           subroutine, and optionally its call graph".to_string(),
+        "  recompile  Translate a ROM into a Rust module that runs it without decoding".to_string(),
+        "  make-ips Write an IPS patch with the changes between two ROMs".to_string(),
+        String::new(),
+        "Emulation:".to_string(),
+        format!("  --speed <ips>                          Instructions per second (default {})", default.speed),
+        "  --variant <chip8|schip>                Use the quirks of the given interpreter".to_string(),
+        format!("  --quirks <list>                        Turn quirks on, or off with a leading '-' (default {})", default.quirks.describe()),
+        "  --patch <file>                         Apply an IPS or BPS patch (default: <rom>.ips or <rom>.bps if present)".to_string(),
+        format!("  --engine <name>                        How instructions run: {} (default {})", Engine::names().join(", "), default.engine.name()),
+        "  --seed <n>                             Seed the random number generator".to_string(),
+        "  --load-state <file>                    Start from a saved state (F9 reloads it)".to_string(),
+        "  --save-state <file>                    Save the state on exit".to_string(),
+        "  --trace                                Print every executed instruction to stderr".to_string(),
+        "  --profile <out.txt>                    Count instructions and calls, and save a report on exit".to_string(),
+        String::new(),
+        "Display and input:".to_string(),
+        format!("  --scale <n>                            Starting window and capture pixel size (default {})", default.scale),
+        format!("  --scaling <mode>                       Fit the screen to the window: {} (default {})", Scaling::names().join(", "), default.scaling.name()),
+        "  --grid                                 Leave a gap between pixels".to_string(),
+        format!("  --palette <name|bg,fg>                 {} or hex colours, e.g. '#000000,#33ff66'", Palette::names().join(", ")),
+        "  --fullscreen                           Start in fullscreen".to_string(),
+        format!("  --keymap <keys>                        Keys for CHIP-8 keys 0-F (default {})", default.keymap.keys.iter().collect::<String>()),
+        String::new(),
+        "Sound:".to_string(),
+        "  --mute                                 Start with the buzzer muted".to_string(),
+        "  --waveform <name>                      Buzzer waveform: square, sine, triangle, noise".to_string(),
+        "  --pitch <hz>                           Buzzer pitch (default 440)".to_string(),
+        "  --volume <percent>                     Buzzer volume (default 25)".to_string(),
+        "  --record-wav <out.wav>                 Record buzzer output to a WAV file".to_string(),
+        String::new(),
+        "Headless runs and capture:".to_string(),
+        "  --headless                             Run without a window".to_string(),
+        "  --frames <n>                           Run headlessly for n frames and exit".to_string(),
+        "  --screenshot-after <frames> <out.png>  Run headlessly, save a screenshot and exit".to_string(),
+        "  --record-gif <frames> <out.gif>        Run headlessly, record a GIF and exit".to_string(),
+        "  --gif-scale <n>                        GIF pixel scale (default 4)".to_string(),
+        "  --gif-skip <n>                         Frames skipped between GIF frames (default 1)".to_string(),
+        "  --replay <input.log>                   Play back recorded keypad input".to_string(),
+        "  --record-input <input.log>             Record keypad input to a file".to_string(),
+        String::new(),
+        "Options can also be set per ROM in a .cfg file next to it (e.g. Pong.cfg for".to_string(),
+        "Pong.ch8), one 'name = value' or 'name' per line. The command line overrides it.".to_string(),
+    ];
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,202 @@
+use crate::checksum;
+use crate::disasm;
+use crate::json::Value;
+use crate::memory::{MEMORY_SIZE, PROGRAM_START};
+use std::collections::BTreeMap;
+
+// Ways a byte of memory gets used. One byte can be used several ways.
+pub const EXECUTED: u8 = 1;
+// Read by `DRW`.
+pub const SPRITE: u8 = 2;
+// Read by `Fx65`.
+pub const READ: u8 = 4;
+// Written by `Fx55` or `Fx33`.
+pub const WRITTEN: u8 = 8;
+
+const KINDS: [(u8, &str, char); 4] = [
+    (EXECUTED, "executed", 'x'),
+    (SPRITE, "sprite", 's'),
+    (READ, "read", 'r'),
+    (WRITTEN, "written", 'w'),
+];
+// Bytes to a line in the text map.
+const MAP_WIDTH: usize = 64;
+// Frames a replay keeps running after its last key change.
+pub const REPLAY_TAIL: u32 = 60;
+pub const DEFAULT_FRAMES: u32 = 600;
+
+// Which bytes of memory a run used, and how.
+#[derive(Clone)]
+pub struct Coverage {
+    pub access: Vec<u8>,
+    // How many runs have been merged into this one.
+    pub runs: u32,
+}
+
+impl Coverage {
+    pub fn new() -> Self {
+        Self {
+            access: vec![0; MEMORY_SIZE],
+            runs: 1,
+        }
+    }
+
+    pub fn mark(&mut self, address: u16, len: usize, kind: u8) {
+        let start = (address as usize).min(MEMORY_SIZE);
+        for flags in &mut self.access[start..(start + len).min(MEMORY_SIZE)] {
+            *flags |= kind;
+        }
+    }
+
+    pub fn merge(&mut self, other: &Coverage) {
+        for (flags, other) in self.access.iter_mut().zip(&other.access) {
+            *flags |= other;
+        }
+        self.runs += other.runs;
+    }
+
+    fn program(&self, len: usize) -> &[u8] {
+        &self.access[PROGRAM_START..(PROGRAM_START + len).min(MEMORY_SIZE)]
+    }
+
+    // How many of the program's bytes were used each way, and how many never.
+    pub fn summary(&self, len: usize) -> Vec<(&'static str, usize)> {
+        let program = self.program(len);
+        let mut summary: Vec<_> = KINDS
+            .iter()
+            .map(|&(kind, name, _)| (name, program.iter().filter(|&&f| f & kind != 0).count()))
+            .collect();
+        summary.push(("untouched", program.iter().filter(|&&f| f == 0).count()));
+        summary
+    }
+
+    // One character per byte of the program, 64 to a line.
+    pub fn map(&self, len: usize) -> String {
+        let mut lines = vec![
+            format!("Coverage over {} run(s)", self.runs),
+            self.summary(len)
+                .iter()
+                .map(|(name, count)| format!("{} {}", count, name))
+                .collect::<Vec<_>>()
+                .join(", "),
+            "x executed, s sprite, r read by Fx65, w written, * several, . untouched".to_string(),
+            String::new(),
+        ];
+        for (i, line) in self.program(len).chunks(MAP_WIDTH).enumerate() {
+            let symbols: String = line.iter().map(|&flags| symbol(flags)).collect();
+            lines.push(format!(
+                "0x{:03X}  {}",
+                PROGRAM_START + i * MAP_WIDTH,
+                symbols
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    // The linear disassembly with how each word was used in front, so code
+    // that never ran stands out.
+    pub fn annotated_listing(&self, program: &[u8]) -> String {
+        let listing = disasm::listing(program);
+        let words = self.program(program.len()).chunks(2);
+        listing
+            .lines()
+            .zip(words)
+            .map(|(line, word)| {
+                let symbols: String = word.iter().map(|&flags| symbol(flags)).collect();
+                let note = if word.iter().all(|&flags| flags == 0) {
+                    "  ; never touched"
+                } else {
+                    ""
+                };
+                format!("{:<2}  {}{}\n", symbols, line, note)
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self, rom: &str, program: &[u8]) -> Value {
+        let summary = self
+            .summary(program.len())
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), Value::Number(count as f64)))
+            .collect();
+        let flags: String = self
+            .program(program.len())
+            .iter()
+            .map(|flags| format!("{:x}", flags))
+            .collect();
+        let fields: BTreeMap<String, Value> = [
+            ("rom", Value::String(rom.to_string())),
+            (
+                "sha1",
+                Value::String(checksum::hex(&checksum::sha1(program))),
+            ),
+            ("runs", Value::Number(self.runs as f64)),
+            ("summary", Value::Object(summary)),
+            // One hex digit per byte from 0x200: 1 executed, 2 sprite, 4
+            // read, 8 written, added together.
+            ("flags", Value::String(flags)),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+        Value::Object(fields)
+    }
+
+    // Coverage saved by `to_json`, which must be for the same program.
+    pub fn from_json(document: &Value, program: &[u8]) -> Result<Self, String> {
+        let sha1 = checksum::hex(&checksum::sha1(program));
+        if document.get("sha1").and_then(Value::as_str) != Some(sha1.as_str()) {
+            return Err("it is for a different ROM".to_string());
+        }
+        let flags = document
+            .get("flags")
+            .and_then(Value::as_str)
+            .ok_or("it has no flags")?;
+        let mut coverage = Self::new();
+        for (i, digit) in flags.chars().enumerate() {
+            let value = digit
+                .to_digit(16)
+                .ok_or_else(|| format!("bad flag '{}'", digit))?;
+            if let Some(flags) = coverage.access.get_mut(PROGRAM_START + i) {
+                *flags = value as u8;
+            }
+        }
+        coverage.runs = document
+            .get("runs")
+            .and_then(Value::as_f64)
+            .map_or(1, |runs| runs as u32);
+        Ok(coverage)
+    }
+}
+
+impl Default for Coverage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn symbol(flags: u8) -> char {
+    match KINDS.iter().find(|&&(kind, _, _)| kind == flags) {
+        Some(&(_, _, symbol)) => symbol,
+        None if flags == 0 => '.',
+        None => '*',
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Report {
+    Map,
+    Json,
+    Listing,
+}
+
+pub struct CoverageOptions {
+    pub rom_path: String,
+    // Input logs to play back, one run each.
+    pub replays: Vec<String>,
+    // Overrides how long each run lasts.
+    pub frames: Option<u32>,
+    // Earlier `--json` output to add in.
+    pub merge: Vec<String>,
+    pub report: Report,
+}
@@ -1,6 +1,51 @@
+use crate::coverage;
+use crate::disasm::{self, Instruction};
 use crate::display::Display;
 use crate::keyboard::Keyboard;
 use crate::memory::Memory;
+use crate::profile::Profile;
+use crate::quirks::Quirks;
+use crate::sprites::SpriteLog;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub const CYCLES_PER_FRAME: u32 = 15;
+
+// How instructions are run. Both must behave identically, which
+// `lockstep::check` verifies.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Engine {
+    // Fetches and decodes every instruction as it runs.
+    Interpreter,
+    // Runs instructions decoded once and cached in `Memory`.
+    Cached,
+}
+
+const ENGINES: [(&str, Engine); 2] = [
+    ("interpreter", Engine::Interpreter),
+    ("cached", Engine::Cached),
+];
+
+impl Engine {
+    pub fn parse(name: &str) -> Option<Self> {
+        ENGINES
+            .iter()
+            .find(|(engine_name, _)| *engine_name == name)
+            .map(|&(_, engine)| engine)
+    }
+
+    pub fn name(&self) -> &'static str {
+        ENGINES
+            .iter()
+            .find(|(_, engine)| engine == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default()
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        ENGINES.iter().map(|(name, _)| *name).collect()
+    }
+}
 
 pub struct Cpu {
     pub memory: Memory,
@@ -12,6 +57,15 @@ pub struct Cpu {
     pub stack: Vec<u16>,
     pub delay_timer: u8,
     pub sound_timer: u8,
+    pub quirks: Quirks,
+    pub cycles_per_frame: u32,
+    pub engine: Engine,
+    pub trace: bool,
+    // Counts every instruction and call when set.
+    pub profile: Option<Box<Profile>>,
+    // Records the sprite address and height of every draw when set.
+    pub sprites: Option<Box<SpriteLog>>,
+    rng: StdRng,
 }
 
 impl Cpu {
@@ -41,9 +95,20 @@ impl Cpu {
             stack: Vec::new(),
             delay_timer: 0,
             sound_timer: 0,
+            quirks: Quirks::default(),
+            cycles_per_frame: CYCLES_PER_FRAME,
+            engine: Engine::Interpreter,
+            trace: false,
+            profile: None,
+            sprites: None,
+            rng: StdRng::from_entropy(),
         }
     }
 
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     pub fn reset(&mut self) {
         self.display.clear();
 
@@ -65,8 +130,33 @@ impl Cpu {
     }
 
     pub fn cycle(&mut self) {
-        let opcode = self.fetch_opcode();
-        self.execute_opcode(opcode);
+        let pc = self.program_counter;
+        if self.trace {
+            let opcode = (self.memory.read(pc) as u16) << 8 | self.memory.read(pc + 1) as u16;
+            eprintln!("0x{:03X}  {:04X}  {}", pc, opcode, disasm::decode(opcode));
+        }
+        if let Some(profile) = &mut self.profile {
+            profile.count(pc);
+        }
+        self.memory.mark(pc, 2, coverage::EXECUTED);
+        match self.engine {
+            Engine::Interpreter => {
+                let opcode = self.fetch_opcode();
+                self.execute_opcode(opcode);
+            }
+            Engine::Cached => {
+                let instruction = self.memory.instruction(pc);
+                self.program_counter += 2;
+                self.execute(instruction);
+            }
+        }
+    }
+
+    pub fn run_frame(&mut self) {
+        for _ in 0..self.cycles_per_frame {
+            self.cycle();
+        }
+        self.tick_timers();
     }
 
     pub fn tick_timers(&mut self) {
@@ -119,7 +209,7 @@ impl Cpu {
             },
             0x9000 => self.sne_reg(x, y),
             0xA000 => self.ld_i(nnn),
-            0xB000 => self.jp_v0(nnn),
+            0xB000 => self.jp_v0(x, nnn),
             0xC000 => self.rnd(x, nn),
             0xD000 => self.drw(x, y, n),
             0xE000 => match opcode & 0x00FF {
@@ -143,6 +233,52 @@ impl Cpu {
         }
     }
 
+    // Runs one decoded instruction, with the program counter already past
+    // it. The cached engine and recompiled code dispatch through here.
+    // Opcodes `disasm::decode` doesn't model go through the interpreter, so
+    // every path treats them the same.
+    pub fn execute(&mut self, instruction: Instruction) {
+        use Instruction::*;
+
+        match instruction {
+            Cls => self.cls(),
+            Ret => self.ret(),
+            Jp(addr) => self.jp(addr),
+            Call(addr) => self.call(addr),
+            SeImm(x, nn) => self.se(x as usize, nn),
+            SneImm(x, nn) => self.sne(x as usize, nn),
+            SeReg(x, y) => self.se_reg(x as usize, y as usize),
+            LdImm(x, nn) => self.ld(x as usize, nn),
+            AddImm(x, nn) => self.add(x as usize, nn),
+            LdReg(x, y) => self.ld_reg(x as usize, y as usize),
+            Or(x, y) => self.or(x as usize, y as usize),
+            And(x, y) => self.and(x as usize, y as usize),
+            Xor(x, y) => self.xor(x as usize, y as usize),
+            AddReg(x, y) => self.add_reg(x as usize, y as usize),
+            Sub(x, y) => self.sub(x as usize, y as usize),
+            Shr(x, y) => self.shr(x as usize, y as usize),
+            Subn(x, y) => self.subn(x as usize, y as usize),
+            Shl(x, y) => self.shl(x as usize, y as usize),
+            SneReg(x, y) => self.sne_reg(x as usize, y as usize),
+            LdI(addr) => self.ld_i(addr),
+            JpV0(addr) => self.jp_v0((addr >> 8) as usize, addr),
+            Rnd(x, nn) => self.rnd(x as usize, nn),
+            Drw(x, y, n) => self.drw(x as usize, y as usize, n),
+            Skp(x) => self.skp(x as usize),
+            Sknp(x) => self.sknp(x as usize),
+            LdVxDt(x) => self.ld_dt(x as usize),
+            LdKey(x) => self.ld_k(x as usize),
+            LdDtVx(x) => self.ld_delay(x as usize),
+            LdStVx(x) => self.ld_sound(x as usize),
+            AddI(x) => self.add_i(x as usize),
+            LdFont(x) => self.ld_f(x as usize),
+            LdBcd(x) => self.ld_b(x as usize),
+            Store(x) => self.ld_mem(x as usize),
+            Load(x) => self.ld_reg_mem(x as usize),
+            Sys(opcode) | Invalid(opcode) => self.execute_opcode(opcode),
+        }
+    }
+
     fn cls(&mut self) {
         self.display.clear();
     }
@@ -150,6 +286,9 @@ impl Cpu {
     fn ret(&mut self) {
         if let Some(addr) = self.stack.pop() {
             self.program_counter = addr;
+            if let Some(profile) = &mut self.profile {
+                profile.ret();
+            }
         }
     }
 
@@ -160,6 +299,9 @@ impl Cpu {
     fn call(&mut self, addr: u16) {
         self.stack.push(self.program_counter);
         self.program_counter = addr;
+        if let Some(profile) = &mut self.profile {
+            profile.call(addr);
+        }
     }
 
     fn se(&mut self, x: usize, nn: u8) {
@@ -194,17 +336,23 @@ impl Cpu {
 
     fn or(&mut self, x: usize, y: usize) {
         self.registers[x] |= self.registers[y];
-        self.registers[0xF] = 0;
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
     }
 
     fn and(&mut self, x: usize, y: usize) {
         self.registers[x] &= self.registers[y];
-        self.registers[0xF] = 0;
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
     }
 
     fn xor(&mut self, x: usize, y: usize) {
         self.registers[x] ^= self.registers[y];
-        self.registers[0xF] = 0;
+        if self.quirks.vf_reset {
+            self.registers[0xF] = 0;
+        }
     }
 
     fn add_reg(&mut self, x: usize, y: usize) {
@@ -223,8 +371,8 @@ impl Cpu {
         self.registers[0xF] = if vx >= vy { 1 } else { 0 };
     }
 
-    fn shr(&mut self, x: usize, _y: usize) {
-        let vx = self.registers[x];
+    fn shr(&mut self, x: usize, y: usize) {
+        let vx = self.registers[if self.quirks.shifting { x } else { y }];
         let result = vx >> 1;
         self.registers[x] = result;
         self.registers[0xF] = vx & 1;
@@ -238,8 +386,8 @@ impl Cpu {
         self.registers[0xF] = if vy >= vx { 1 } else { 0 };
     }
 
-    fn shl(&mut self, x: usize, _y: usize) {
-        let vx = self.registers[x];
+    fn shl(&mut self, x: usize, y: usize) {
+        let vx = self.registers[if self.quirks.shifting { x } else { y }];
         let result = vx << 1;
         self.registers[x] = result;
         self.registers[0xF] = (vx & 0x80) >> 7;
@@ -255,35 +403,46 @@ impl Cpu {
         self.index = addr;
     }
 
-    fn jp_v0(&mut self, addr: u16) {
-        self.program_counter = addr + self.registers[0] as u16;
+    fn jp_v0(&mut self, x: usize, addr: u16) {
+        let offset = self.registers[if self.quirks.jumping { x } else { 0 }];
+        self.program_counter = addr + offset as u16;
     }
 
     fn rnd(&mut self, x: usize, nn: u8) {
-        let random = rand::random::<u8>();
+        let random = self.rng.gen::<u8>();
         self.registers[x] = random & nn;
     }
 
     fn drw(&mut self, x: usize, y: usize, n: u8) {
-        let x_pos = self.registers[x] as usize;
-        let y_pos = self.registers[y] as usize;
+        let mut x_pos = self.registers[x] as usize;
+        let mut y_pos = self.registers[y] as usize;
+        if self.quirks.wrapping {
+            x_pos %= crate::display::WIDTH;
+            y_pos %= crate::display::HEIGHT;
+        }
 
         self.registers[0xF] = 0;
+        self.memory.mark(self.index, n as usize, coverage::SPRITE);
+        if let Some(sprites) = &mut self.sprites {
+            sprites.record(self.index, n);
+        }
+        // Without the wrapping quirk, a clipped sprite starting off the
+        // screen draws nothing.
+        if self.quirks.clipping && x_pos >= crate::display::WIDTH {
+            return;
+        }
 
         for row in 0..n {
             let sprite_byte = self.memory.read(self.index + row as u16);
-            for col in 0..8 {
-                let sprite_pixel = (sprite_byte >> (7 - col)) & 1;
-                if sprite_pixel == 1 {
-                    let display_x = x_pos + col as usize;
-                    let display_y = y_pos + row as usize;
-                    if display_x < crate::display::WIDTH && display_y < crate::display::HEIGHT {
-                        if self.display.get_pixel(display_x, display_y) {
-                            self.registers[0xF] = 1;
-                        }
-                        self.display.toggle_pixel(display_x, display_y);
-                    }
-                }
+            let mut display_y = y_pos + row as usize;
+            if !self.quirks.clipping {
+                display_y %= crate::display::HEIGHT;
+            }
+            if self
+                .display
+                .blit_sprite_row(x_pos, display_y, sprite_byte, self.quirks.clipping)
+            {
+                self.registers[0xF] = 1;
             }
         }
     }
@@ -330,22 +489,176 @@ impl Cpu {
 
     fn ld_b(&mut self, x: usize) {
         let value = self.registers[x];
+        self.memory.mark(self.index, 3, coverage::WRITTEN);
         self.memory.write(self.index, value / 100);
         self.memory.write(self.index + 1, (value / 10) % 10);
         self.memory.write(self.index + 2, value % 10);
     }
 
     fn ld_mem(&mut self, x: usize) {
+        self.memory.mark(self.index, x + 1, coverage::WRITTEN);
         for i in 0..=x {
             self.memory.write(self.index + i as u16, self.registers[i]);
         }
-        self.index += (x as u16) + 1;
+        if self.quirks.memory {
+            self.index += (x as u16) + 1;
+        }
     }
 
     fn ld_reg_mem(&mut self, x: usize) {
+        self.memory.mark(self.index, x + 1, coverage::READ);
         for i in 0..=x {
             self.registers[i] = self.memory.read(self.index + i as u16);
         }
-        self.index += (x as u16) + 1;
+        if self.quirks.memory {
+            self.index += (x as u16) + 1;
+        }
+    }
+}
+
+// Written out so `clone_from` reuses the memory, stack and profile buffers:
+// the emulation thread copies the CPU into a snapshot every frame.
+impl Clone for Cpu {
+    fn clone(&self) -> Self {
+        Self {
+            memory: self.memory.clone(),
+            display: self.display.clone(),
+            keyboard: self.keyboard.clone(),
+            registers: self.registers,
+            index: self.index,
+            program_counter: self.program_counter,
+            stack: self.stack.clone(),
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+            quirks: self.quirks,
+            cycles_per_frame: self.cycles_per_frame,
+            engine: self.engine,
+            trace: self.trace,
+            profile: self.profile.clone(),
+            sprites: self.sprites.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        let Self {
+            memory,
+            display,
+            keyboard,
+            registers,
+            index,
+            program_counter,
+            stack,
+            delay_timer,
+            sound_timer,
+            quirks,
+            cycles_per_frame,
+            engine,
+            trace,
+            profile,
+            sprites,
+            rng,
+        } = source;
+        self.memory.clone_from(memory);
+        self.display.clone_from(display);
+        self.keyboard.clone_from(keyboard);
+        self.registers = *registers;
+        self.index = *index;
+        self.program_counter = *program_counter;
+        self.stack.clone_from(stack);
+        self.delay_timer = *delay_timer;
+        self.sound_timer = *sound_timer;
+        self.quirks = *quirks;
+        self.cycles_per_frame = *cycles_per_frame;
+        self.engine = *engine;
+        self.trace = *trace;
+        self.profile.clone_from(profile);
+        self.sprites.clone_from(sprites);
+        self.rng.clone_from(rng);
+    }
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::WIDTH;
+    use crate::quirks::Variant;
+
+    // Draws the font's "0" (F0 90 90 90 F0) at (vx, vy).
+    fn draw_zero(quirks: Quirks, vx: u8, vy: u8) -> Cpu {
+        let mut cpu = Cpu::new();
+        cpu.quirks = quirks;
+        cpu.registers[0] = vx;
+        cpu.registers[1] = vy;
+        cpu.index = 0;
+        cpu.execute(Instruction::Drw(0, 1, 5));
+        cpu
+    }
+
+    fn lit(cpu: &Cpu) -> Vec<(usize, usize)> {
+        (0..crate::display::HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+            .filter(|&(x, y)| cpu.display.get_pixel(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn sprites_starting_off_screen_draw_nothing_by_default() {
+        assert!(lit(&draw_zero(Quirks::default(), 70, 2)).is_empty());
+        assert!(lit(&draw_zero(Quirks::default(), 2, 40)).is_empty());
+        for variant in [Variant::Chip8, Variant::Schip] {
+            assert!(lit(&draw_zero(variant.quirks(), 70, 40)).is_empty());
+        }
+    }
+
+    #[test]
+    fn the_wrapping_quirk_wraps_the_start_position() {
+        let mut quirks = Quirks::default();
+        quirks.apply("wrapping").unwrap();
+        let wrapped = draw_zero(quirks, 70, 40);
+        assert_eq!(lit(&wrapped), lit(&draw_zero(quirks, 6, 8)));
+        assert!(wrapped.display.get_pixel(6, 8));
+        assert!(wrapped.display.get_pixel(9, 12));
+    }
+
+    #[test]
+    fn without_clipping_sprites_wrap_from_any_start() {
+        let mut quirks = Quirks::default();
+        quirks.apply("-clipping").unwrap();
+        assert_eq!(
+            lit(&draw_zero(quirks, 70, 40)),
+            lit(&draw_zero(quirks, 6, 8))
+        );
+        // Starting at x = 62, the right half of each row wraps to column 0.
+        let cpu = draw_zero(quirks, 62, 0);
+        assert!(cpu.display.get_pixel(63, 0));
+        assert!(cpu.display.get_pixel(1, 0));
+        assert!(!cpu.display.get_pixel(2, 0));
+    }
+
+    #[test]
+    fn clone_from_reuses_the_buffers() {
+        let mut cpu = Cpu::new();
+        cpu.profile = Some(Box::default());
+        cpu.stack.extend([0x200, 0x300, 0x500]);
+        cpu.registers[3] = 7;
+        let mut snapshot = cpu.clone();
+        let stack = snapshot.stack.as_ptr();
+        let counts = snapshot.profile.as_ref().unwrap().counts.as_ptr();
+
+        cpu.execute(Instruction::Ret);
+        cpu.execute(Instruction::LdImm(3, 9));
+        snapshot.clone_from(&cpu);
+        assert_eq!(snapshot.stack, [0x200, 0x300]);
+        assert_eq!(snapshot.registers[3], 9);
+        assert_eq!(snapshot.program_counter, 0x500);
+        assert_eq!(snapshot.stack.as_ptr(), stack);
+        assert_eq!(snapshot.profile.as_ref().unwrap().counts.as_ptr(), counts);
     }
 }
@@ -0,0 +1,221 @@
+use std::fmt;
+
+// One decoded CHIP-8 instruction. Register operands are register numbers,
+// not values.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Instruction {
+    Cls,
+    Ret,
+    Sys(u16),
+    Jp(u16),
+    Call(u16),
+    SeImm(u8, u8),
+    SneImm(u8, u8),
+    SeReg(u8, u8),
+    LdImm(u8, u8),
+    AddImm(u8, u8),
+    LdReg(u8, u8),
+    Or(u8, u8),
+    And(u8, u8),
+    Xor(u8, u8),
+    AddReg(u8, u8),
+    Sub(u8, u8),
+    Shr(u8, u8),
+    Subn(u8, u8),
+    Shl(u8, u8),
+    SneReg(u8, u8),
+    LdI(u16),
+    JpV0(u16),
+    Rnd(u8, u8),
+    Drw(u8, u8, u8),
+    Skp(u8),
+    Sknp(u8),
+    LdVxDt(u8),
+    LdKey(u8),
+    LdDtVx(u8),
+    LdStVx(u8),
+    AddI(u8),
+    LdFont(u8),
+    LdBcd(u8),
+    Store(u8),
+    Load(u8),
+    Invalid(u16),
+}
+
+pub fn decode(opcode: u16) -> Instruction {
+    use Instruction::*;
+
+    let x = ((opcode & 0x0F00) >> 8) as u8;
+    let y = ((opcode & 0x00F0) >> 4) as u8;
+    let n = (opcode & 0x000F) as u8;
+    let nn = (opcode & 0x00FF) as u8;
+    let nnn = opcode & 0x0FFF;
+
+    match opcode & 0xF000 {
+        0x0000 => match opcode {
+            0x00E0 => Cls,
+            0x00EE => Ret,
+            _ => Sys(nnn),
+        },
+        0x1000 => Jp(nnn),
+        0x2000 => Call(nnn),
+        0x3000 => SeImm(x, nn),
+        0x4000 => SneImm(x, nn),
+        0x5000 if n == 0 => SeReg(x, y),
+        0x6000 => LdImm(x, nn),
+        0x7000 => AddImm(x, nn),
+        0x8000 => match n {
+            0x0 => LdReg(x, y),
+            0x1 => Or(x, y),
+            0x2 => And(x, y),
+            0x3 => Xor(x, y),
+            0x4 => AddReg(x, y),
+            0x5 => Sub(x, y),
+            0x6 => Shr(x, y),
+            0x7 => Subn(x, y),
+            0xE => Shl(x, y),
+            _ => Invalid(opcode),
+        },
+        0x9000 if n == 0 => SneReg(x, y),
+        0xA000 => LdI(nnn),
+        0xB000 => JpV0(nnn),
+        0xC000 => Rnd(x, nn),
+        0xD000 => Drw(x, y, n),
+        0xE000 => match nn {
+            0x9E => Skp(x),
+            0xA1 => Sknp(x),
+            _ => Invalid(opcode),
+        },
+        0xF000 => match nn {
+            0x07 => LdVxDt(x),
+            0x0A => LdKey(x),
+            0x15 => LdDtVx(x),
+            0x18 => LdStVx(x),
+            0x1E => AddI(x),
+            0x29 => LdFont(x),
+            0x33 => LdBcd(x),
+            0x55 => Store(x),
+            0x65 => Load(x),
+            _ => Invalid(opcode),
+        },
+        _ => Invalid(opcode),
+    }
+}
+
+impl Instruction {
+    // The opcode pattern and mnemonic, for grouping instructions by kind.
+    pub fn class(&self) -> &'static str {
+        use Instruction::*;
+
+        match self {
+            Cls => "00E0 CLS",
+            Ret => "00EE RET",
+            Sys(_) => "0nnn SYS",
+            Jp(_) => "1nnn JP",
+            Call(_) => "2nnn CALL",
+            SeImm(..) => "3xnn SE",
+            SneImm(..) => "4xnn SNE",
+            SeReg(..) => "5xy0 SE",
+            LdImm(..) => "6xnn LD",
+            AddImm(..) => "7xnn ADD",
+            LdReg(..) => "8xy0 LD",
+            Or(..) => "8xy1 OR",
+            And(..) => "8xy2 AND",
+            Xor(..) => "8xy3 XOR",
+            AddReg(..) => "8xy4 ADD",
+            Sub(..) => "8xy5 SUB",
+            Shr(..) => "8xy6 SHR",
+            Subn(..) => "8xy7 SUBN",
+            Shl(..) => "8xyE SHL",
+            SneReg(..) => "9xy0 SNE",
+            LdI(_) => "Annn LD I",
+            JpV0(_) => "Bnnn JP V0",
+            Rnd(..) => "Cxnn RND",
+            Drw(..) => "Dxyn DRW",
+            Skp(_) => "Ex9E SKP",
+            Sknp(_) => "ExA1 SKNP",
+            LdVxDt(_) => "Fx07 LD Vx, DT",
+            LdKey(_) => "Fx0A LD Vx, K",
+            LdDtVx(_) => "Fx15 LD DT",
+            LdStVx(_) => "Fx18 LD ST",
+            AddI(_) => "Fx1E ADD I",
+            LdFont(_) => "Fx29 LD F",
+            LdBcd(_) => "Fx33 LD B",
+            Store(_) => "Fx55 LD [I]",
+            Load(_) => "Fx65 LD Vx, [I]",
+            Invalid(_) => "invalid",
+        }
+    }
+}
+
+// Mnemonics follow Cowgod's CHIP-8 technical reference.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Instruction::*;
+
+        match *self {
+            Cls => write!(f, "CLS"),
+            Ret => write!(f, "RET"),
+            Sys(addr) => write!(f, "SYS 0x{:03X}", addr),
+            Jp(addr) => write!(f, "JP 0x{:03X}", addr),
+            Call(addr) => write!(f, "CALL 0x{:03X}", addr),
+            SeImm(x, nn) => write!(f, "SE V{:X}, 0x{:02X}", x, nn),
+            SneImm(x, nn) => write!(f, "SNE V{:X}, 0x{:02X}", x, nn),
+            SeReg(x, y) => write!(f, "SE V{:X}, V{:X}", x, y),
+            LdImm(x, nn) => write!(f, "LD V{:X}, 0x{:02X}", x, nn),
+            AddImm(x, nn) => write!(f, "ADD V{:X}, 0x{:02X}", x, nn),
+            LdReg(x, y) => write!(f, "LD V{:X}, V{:X}", x, y),
+            Or(x, y) => write!(f, "OR V{:X}, V{:X}", x, y),
+            And(x, y) => write!(f, "AND V{:X}, V{:X}", x, y),
+            Xor(x, y) => write!(f, "XOR V{:X}, V{:X}", x, y),
+            AddReg(x, y) => write!(f, "ADD V{:X}, V{:X}", x, y),
+            Sub(x, y) => write!(f, "SUB V{:X}, V{:X}", x, y),
+            Shr(x, y) => write!(f, "SHR V{:X}, V{:X}", x, y),
+            Subn(x, y) => write!(f, "SUBN V{:X}, V{:X}", x, y),
+            Shl(x, y) => write!(f, "SHL V{:X}, V{:X}", x, y),
+            SneReg(x, y) => write!(f, "SNE V{:X}, V{:X}", x, y),
+            LdI(addr) => write!(f, "LD I, 0x{:03X}", addr),
+            JpV0(addr) => write!(f, "JP V0, 0x{:03X}", addr),
+            Rnd(x, nn) => write!(f, "RND V{:X}, 0x{:02X}", x, nn),
+            Drw(x, y, n) => write!(f, "DRW V{:X}, V{:X}, {}", x, y, n),
+            Skp(x) => write!(f, "SKP V{:X}", x),
+            Sknp(x) => write!(f, "SKNP V{:X}", x),
+            LdVxDt(x) => write!(f, "LD V{:X}, DT", x),
+            LdKey(x) => write!(f, "LD V{:X}, K", x),
+            LdDtVx(x) => write!(f, "LD DT, V{:X}", x),
+            LdStVx(x) => write!(f, "LD ST, V{:X}", x),
+            AddI(x) => write!(f, "ADD I, V{:X}", x),
+            LdFont(x) => write!(f, "LD F, V{:X}", x),
+            LdBcd(x) => write!(f, "LD B, V{:X}", x),
+            Store(x) => write!(f, "LD [I], V{:X}", x),
+            Load(x) => write!(f, "LD V{:X}, [I]", x),
+            Invalid(opcode) => write!(f, "DW 0x{:04X}", opcode),
+        }
+    }
+}
+
+// Linear listing of a program loaded at 0x200, one word per line. Data mixed
+// into the code shows up as whatever it happens to decode to.
+pub fn listing(program: &[u8]) -> String {
+    let mut text = String::new();
+    for (i, word) in program.chunks(2).enumerate() {
+        let address = 0x200 + i * 2;
+        match *word {
+            [high, low] => {
+                let opcode = u16::from_be_bytes([high, low]);
+                text.push_str(&format!(
+                    "0x{:03X}  {:04X}  {}\n",
+                    address,
+                    opcode,
+                    decode(opcode)
+                ));
+            }
+            [byte] => text.push_str(&format!(
+                "0x{:03X}  {:02X}    DB 0x{:02X}\n",
+                address, byte, byte
+            )),
+            _ => unreachable!(),
+        }
+    }
+    text
+}
@@ -1,73 +1,359 @@
+#[cfg(feature = "gui")]
+use crate::layout::{Layout, Rect};
+#[cfg(feature = "gui")]
 use raylib::prelude::*;
 
 pub const WIDTH: usize = 64;
 pub const HEIGHT: usize = 32;
 
+// One bit per pixel, with the leftmost pixel in the top bit. A 128-wide
+// display would use `u128` rows the same way.
+pub type Row = u64;
+
+const ROW_BITS: usize = Row::BITS as usize;
+const _: () = assert!(WIDTH == ROW_BITS);
+
+#[derive(Clone, PartialEq)]
 pub struct Display {
-    pixels: [[bool; WIDTH]; HEIGHT],
+    rows: [Row; HEIGHT],
 }
 
 impl Display {
     pub fn new() -> Self {
-        Self {
-            pixels: [[false; WIDTH]; HEIGHT],
-        }
+        Self { rows: [0; HEIGHT] }
     }
 
     pub fn clear(&mut self) {
-        for row in &mut self.pixels {
-            for pixel in row {
-                *pixel = false;
-            }
-        }
+        self.rows = [0; HEIGHT];
+    }
+
+    fn mask(x: usize) -> Row {
+        1 << (ROW_BITS - 1 - x)
     }
 
     pub fn get_pixel(&self, x: usize, y: usize) -> bool {
+        x < WIDTH && y < HEIGHT && self.rows[y] & Self::mask(x) != 0
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, on: bool) {
         if x < WIDTH && y < HEIGHT {
-            self.pixels[y][x]
-        } else {
-            false
+            if on {
+                self.rows[y] |= Self::mask(x);
+            } else {
+                self.rows[y] &= !Self::mask(x);
+            }
         }
     }
 
     pub fn toggle_pixel(&mut self, x: usize, y: usize) {
         if x < WIDTH && y < HEIGHT {
-            self.pixels[y][x] = !self.pixels[y][x];
+            self.rows[y] ^= Self::mask(x);
         }
     }
+
+    pub fn row(&self, y: usize) -> Row {
+        self.rows.get(y).copied().unwrap_or(0)
+    }
+
+    // XORs an 8-pixel sprite row onto row `y` starting at column `x`, and
+    // returns whether any lit pixel was turned off. Pixels past the right
+    // edge wrap around to the left, or are dropped when `clip` is set.
+    pub fn blit_sprite_row(&mut self, x: usize, y: usize, sprite: u8, clip: bool) -> bool {
+        let Some(row) = self.rows.get_mut(y) else {
+            return false;
+        };
+        let sprite = (sprite as Row) << (ROW_BITS - 8);
+        let x = x % WIDTH;
+        let bits = if clip {
+            sprite >> x
+        } else {
+            sprite.rotate_right(x as u32)
+        };
+        let collision = *row & bits != 0;
+        *row ^= bits;
+        collision
+    }
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub background: [u8; 3],
+    pub foreground: [u8; 3],
 }
 
+const NAMED_PALETTES: [(&str, Palette); 6] = [
+    (
+        "default",
+        Palette {
+            background: [20, 20, 20],
+            foreground: [255, 255, 255],
+        },
+    ),
+    (
+        "amber",
+        Palette {
+            background: [26, 16, 0],
+            foreground: [255, 176, 0],
+        },
+    ),
+    (
+        "green",
+        Palette {
+            background: [0, 20, 8],
+            foreground: [51, 255, 102],
+        },
+    ),
+    (
+        "lcd",
+        Palette {
+            background: [155, 188, 15],
+            foreground: [15, 56, 15],
+        },
+    ),
+    (
+        "blue",
+        Palette {
+            background: [10, 20, 60],
+            foreground: [140, 200, 255],
+        },
+    ),
+    (
+        "paper",
+        Palette {
+            background: [240, 236, 224],
+            foreground: [30, 30, 30],
+        },
+    ),
+];
+
+fn parse_rgb(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
+
+impl Palette {
+    pub fn colors(&self) -> [[u8; 3]; 2] {
+        [self.background, self.foreground]
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        NAMED_PALETTES.iter().map(|(name, _)| *name).collect()
+    }
+
+    // A palette name, or `<background>,<foreground>` as hex colours such as
+    // `#000000,#33ff66`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        if let Some((_, palette)) = NAMED_PALETTES.iter().find(|(name, _)| *name == spec) {
+            return Some(*palette);
+        }
+        let (background, foreground) = spec.split_once(',')?;
+        Some(Self {
+            background: parse_rgb(background)?,
+            foreground: parse_rgb(foreground)?,
+        })
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        NAMED_PALETTES[0].1
+    }
+}
+
+// The capture scale for screenshots and the starting window size.
 pub const PIXEL_SIZE: i32 = 10;
 
-pub fn draw(d: &mut RaylibDrawHandle, display: &Display) {
-    let game_width = (WIDTH as i32) * PIXEL_SIZE;
-    let game_height = (HEIGHT as i32) * PIXEL_SIZE;
-    let rom_panel_height = 80;
-    let window_width = 1200;
-    let window_height = 800;
-
-    let offset_x = (window_width - game_width) / 2;
-    let offset_y = rom_panel_height + (window_height - rom_panel_height - game_height) / 2;
-
-    d.draw_rectangle(
-        offset_x,
-        offset_y,
-        game_width,
-        game_height,
-        Color::new(20, 20, 20, 255),
-    );
-
-    for y in 0..HEIGHT {
-        for x in 0..WIDTH {
-            if display.get_pixel(x, y) {
-                d.draw_rectangle(
-                    offset_x + (x as i32 * PIXEL_SIZE),
-                    offset_y + (y as i32 * PIXEL_SIZE),
-                    PIXEL_SIZE,
-                    PIXEL_SIZE,
-                    Color::WHITE,
-                );
+// Converts the framebuffer to RGBA bytes, four per pixel, row by row.
+// `out` must hold `WIDTH * HEIGHT * 4` bytes.
+pub fn fill_rgba(display: &Display, palette: &Palette, out: &mut [u8]) {
+    let [background, foreground] = palette.colors().map(|[r, g, b]| [r, g, b, 255]);
+    for (y, row) in out.chunks_exact_mut(WIDTH * 4).enumerate() {
+        let bits = display.row(y);
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let on = bits & (1 << (ROW_BITS - 1 - x)) != 0;
+            pixel.copy_from_slice(if on { &foreground } else { &background });
+        }
+    }
+}
+
+// The framebuffer as RGBA bytes, converted again only when the pixels or the
+// palette have changed since the last update.
+pub struct RgbaBuffer {
+    pub pixels: Vec<u8>,
+    last: Option<(Display, Palette)>,
+}
+
+impl RgbaBuffer {
+    pub fn new() -> Self {
+        Self {
+            pixels: vec![0; WIDTH * HEIGHT * 4],
+            last: None,
+        }
+    }
+
+    // Returns whether the buffer changed.
+    pub fn update(&mut self, display: &Display, palette: &Palette) -> bool {
+        if let Some((last_display, last_palette)) = &self.last {
+            if last_display == display && last_palette == palette {
+                return false;
+            }
+        }
+        fill_rgba(display, palette, &mut self.pixels);
+        self.last = Some((display.clone(), *palette));
+        true
+    }
+}
+
+impl Default for RgbaBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "gui")]
+fn to_color(rgb: [u8; 3]) -> Color {
+    Color::new(rgb[0], rgb[1], rgb[2], 255)
+}
+
+#[cfg(feature = "gui")]
+fn to_rectangle(rect: Rect) -> Rectangle {
+    Rectangle::new(rect.x, rect.y, rect.width, rect.height)
+}
+
+// Pixels this small lose too much to a gap, so the grid is left off.
+#[cfg(feature = "gui")]
+const MIN_GRID_PIXEL: f32 = 4.0;
+
+// The framebuffer in a texture, drawn scaled in one call.
+#[cfg(feature = "gui")]
+pub struct ScreenTexture {
+    texture: Texture2D,
+    rgba: RgbaBuffer,
+}
+
+#[cfg(feature = "gui")]
+impl ScreenTexture {
+    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> Result<Self, String> {
+        let image = Image::gen_image_color(WIDTH as i32, HEIGHT as i32, Color::BLACK);
+        let texture = rl
+            .load_texture_from_image(thread, &image)
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            texture,
+            rgba: RgbaBuffer::new(),
+        })
+    }
+
+    // Uploads the framebuffer, unless it hasn't changed.
+    pub fn update(&mut self, display: &Display, palette: &Palette) {
+        if self.rgba.update(display, palette) {
+            if let Err(e) = self.texture.update_texture(&self.rgba.pixels) {
+                eprintln!("Failed to update the screen texture: {}", e);
             }
         }
     }
+
+    // Draws the texture into `layout.screen`. With `grid`, a faint line is
+    // drawn along the edge of every pixel.
+    pub fn draw(&self, d: &mut RaylibDrawHandle, palette: &Palette, layout: &Layout, grid: bool) {
+        let source = Rectangle::new(0.0, 0.0, WIDTH as f32, HEIGHT as f32);
+        d.draw_texture_pro(
+            &self.texture,
+            source,
+            to_rectangle(layout.screen),
+            Vector2::new(0.0, 0.0),
+            0.0,
+            Color::WHITE,
+        );
+
+        if !grid || layout.pixel_width.min(layout.pixel_height) < MIN_GRID_PIXEL {
+            return;
+        }
+        let line = to_color([0, 1, 2].map(|i| {
+            ((palette.background[i] as u32 * 7 + palette.foreground[i] as u32) / 8) as u8
+        }));
+        let screen = layout.screen;
+        for x in 1..=WIDTH {
+            let right = layout.pixel(x, 0).x;
+            d.draw_rectangle_rec(
+                Rectangle::new(right - 1.0, screen.y, 1.0, screen.height),
+                line,
+            );
+        }
+        for y in 1..=HEIGHT {
+            let bottom = layout.pixel(0, y).y;
+            d.draw_rectangle_rec(
+                Rectangle::new(screen.x, bottom - 1.0, screen.width, 1.0),
+                line,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit_columns(display: &Display, y: usize) -> Vec<usize> {
+        (0..WIDTH).filter(|&x| display.get_pixel(x, y)).collect()
+    }
+
+    #[test]
+    fn overlapping_rows_collide_and_disjoint_rows_do_not() {
+        let mut display = Display::new();
+        assert!(!display.blit_sprite_row(0, 0, 0b1111_0000, true));
+        // The lit pixels sit next to the new ones without sharing any.
+        assert!(!display.blit_sprite_row(4, 0, 0b1111_0000, true));
+        assert_eq!(lit_columns(&display, 0), (0..8).collect::<Vec<_>>());
+        assert!(display.blit_sprite_row(6, 0, 0b1100_0000, true));
+        assert_eq!(lit_columns(&display, 0), (0..6).collect::<Vec<_>>());
+        // The same sprite on the next row doesn't see row 0.
+        assert!(!display.blit_sprite_row(0, 1, 0xFF, true));
+    }
+
+    #[test]
+    fn the_right_edge_clips_or_wraps() {
+        let mut clipped = Display::new();
+        clipped.blit_sprite_row(60, 0, 0xFF, true);
+        assert_eq!(lit_columns(&clipped, 0), [60, 61, 62, 63]);
+
+        let mut wrapped = Display::new();
+        wrapped.blit_sprite_row(60, 0, 0xFF, false);
+        assert_eq!(lit_columns(&wrapped, 0), [0, 1, 2, 3, 60, 61, 62, 63]);
+        // The wrapped part collides with what is already at the left edge.
+        assert!(wrapped.blit_sprite_row(62, 0, 0b0000_1000, false));
+        assert_eq!(lit_columns(&wrapped, 0), [0, 1, 3, 60, 61, 62, 63]);
+    }
+
+    #[test]
+    fn the_last_column_keeps_only_the_top_bit_when_clipped() {
+        let mut clipped = Display::new();
+        clipped.blit_sprite_row(63, 5, 0b1010_0000, true);
+        assert_eq!(lit_columns(&clipped, 5), [63]);
+
+        let mut wrapped = Display::new();
+        wrapped.blit_sprite_row(63, 5, 0b1010_0000, false);
+        assert_eq!(lit_columns(&wrapped, 5), [1, 63]);
+    }
+
+    #[test]
+    fn rows_below_the_screen_are_dropped() {
+        let mut display = Display::new();
+        assert!(!display.blit_sprite_row(0, HEIGHT - 1, 0xFF, true));
+        assert!(!display.blit_sprite_row(0, HEIGHT, 0xFF, true));
+        assert!(!display.blit_sprite_row(0, HEIGHT + 3, 0xFF, false));
+        assert_eq!(lit_columns(&display, HEIGHT - 1).len(), 8);
+        let lit_rows = (0..HEIGHT).filter(|&y| display.row(y) != 0).count();
+        assert_eq!(lit_rows, 1);
+    }
 }
@@ -0,0 +1,189 @@
+use crate::analysis::{Analysis, Block, Edge, Function, PROGRAM_START};
+use crate::disasm::Instruction;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", escape(text))
+}
+
+// Lines for a node, each ending in `\l` so they line up on the left.
+fn label(lines: &[String]) -> String {
+    let text: String = lines.iter().map(|line| escape(line) + "\\l").collect();
+    format!("\"{}\"", text)
+}
+
+fn function_name(entry: u16) -> String {
+    if entry == PROGRAM_START {
+        "main".to_string()
+    } else {
+        format!("sub_{:03X}", entry)
+    }
+}
+
+fn edge_style(edge: Edge) -> &'static str {
+    match edge {
+        Edge::Next | Edge::Jump => "",
+        Edge::Skip => " [style=dashed, label=\"skip\"]",
+        Edge::Call => " [style=bold, color=blue, label=\"call\"]",
+        Edge::Return => " [style=dotted, label=\"after return\"]",
+    }
+}
+
+// The function each block is drawn in: the first that reaches it, since a
+// block shared by several can only sit in one cluster.
+fn owners(functions: &BTreeMap<u16, Function>) -> BTreeMap<u16, u16> {
+    let mut owners = BTreeMap::new();
+    for function in functions.values() {
+        for &start in &function.blocks {
+            owners.entry(start).or_insert(function.entry);
+        }
+    }
+    owners
+}
+
+fn block_node(out: &mut String, block: &Block, indent: &str) {
+    let lines: Vec<String> = block
+        .instructions
+        .iter()
+        .map(|&(addr, opcode, instruction)| {
+            format!("0x{:03X}  {:04X}  {}", addr, opcode, instruction)
+        })
+        .collect();
+    let flag = match block.last().2 {
+        Instruction::JpV0(_) => ", color=red",
+        _ => "",
+    };
+    let _ = writeln!(
+        out,
+        "{}b_{:03X} [label={}{}];",
+        indent,
+        block.start,
+        label(&lines),
+        flag
+    );
+}
+
+// Every basic block, grouped into a cluster per function, with the edges
+// between them. Computed `Bnnn` jumps lead to a red node of their own.
+pub fn control_flow(name: &str, analysis: &Analysis) -> String {
+    let blocks = analysis.blocks(&analysis.leaders());
+    let functions = analysis.functions(&blocks);
+    let owners = owners(&functions);
+
+    let mut out = String::from("digraph control_flow {\n");
+    let _ = writeln!(out, "    label={};", quote(name));
+    out.push_str("    node [shape=box, fontname=monospace];\n");
+    for function in functions.values() {
+        let _ = writeln!(out, "\n    subgraph cluster_{:03X} {{", function.entry);
+        let _ = writeln!(out, "        label=\"{}\";", function_name(function.entry));
+        for block in &blocks {
+            if owners.get(&block.start) == Some(&function.entry) {
+                block_node(&mut out, block, "        ");
+            }
+        }
+        out.push_str("    }\n");
+    }
+
+    out.push('\n');
+    let mut missing = BTreeSet::new();
+    for block in &blocks {
+        for (target, edge) in analysis.edges(block) {
+            if !owners.contains_key(&target) {
+                missing.insert(target);
+            }
+            let _ = writeln!(
+                out,
+                "    b_{:03X} -> b_{:03X}{};",
+                block.start,
+                target,
+                edge_style(edge)
+            );
+        }
+        if let (addr, _, Instruction::JpV0(base)) = block.last() {
+            let _ = writeln!(
+                out,
+                "    computed_{:03X} [label=\"V0 + 0x{:03X}\\nunresolved\", shape=octagon, color=red];",
+                addr, base
+            );
+            let _ = writeln!(
+                out,
+                "    b_{:03X} -> computed_{:03X} [style=dashed, color=red];",
+                block.start, addr
+            );
+        }
+    }
+    // Targets with no code to show, such as jumps past the end of the ROM.
+    for target in missing {
+        let _ = writeln!(
+            out,
+            "    b_{:03X} [label=\"0x{:03X}\\nnot in the ROM\", shape=plaintext];",
+            target, target
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+// Which functions call which. Functions with a computed jump are red, since
+// the calls behind it can't be seen.
+pub fn call_graph(name: &str, analysis: &Analysis) -> String {
+    let blocks = analysis.blocks(&analysis.leaders());
+    let functions = analysis.functions(&blocks);
+    let sizes: BTreeMap<u16, usize> = blocks
+        .iter()
+        .map(|block| (block.start, block.instructions.len()))
+        .collect();
+
+    let mut out = String::from("digraph calls {\n");
+    let _ = writeln!(out, "    label={};", quote(name));
+    out.push_str("    node [shape=box, fontname=monospace];\n");
+    for function in functions.values() {
+        let instructions: usize = function.blocks.iter().map(|start| sizes[start]).sum();
+        let mut lines = vec![
+            function_name(function.entry),
+            format!(
+                "{} block(s), {} instruction(s)",
+                function.blocks.len(),
+                instructions
+            ),
+        ];
+        for addr in &function.computed_jumps {
+            lines.push(format!("unresolved JP V0 at 0x{:03X}", addr));
+        }
+        let flag = if function.computed_jumps.is_empty() {
+            ""
+        } else {
+            ", color=red"
+        };
+        let _ = writeln!(
+            out,
+            "    f_{:03X} [label={}{}];",
+            function.entry,
+            label(&lines),
+            flag
+        );
+    }
+    let mut missing = BTreeSet::new();
+    for function in functions.values() {
+        for &callee in &function.calls {
+            if !functions.contains_key(&callee) {
+                missing.insert(callee);
+            }
+            let _ = writeln!(out, "    f_{:03X} -> f_{:03X};", function.entry, callee);
+        }
+    }
+    for callee in missing {
+        let _ = writeln!(
+            out,
+            "    f_{:03X} [label=\"0x{:03X}\\nnot in the ROM\", shape=plaintext];",
+            callee, callee
+        );
+    }
+    out.push_str("}\n");
+    out
+}
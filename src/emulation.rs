@@ -0,0 +1,221 @@
+use crate::frontend::{Control, Machine, Snapshot};
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+pub const FRAME_TIME: Duration = Duration::from_micros(16_667);
+// Frames the thread may fall behind before it gives up catching up.
+const MAX_LAG: u32 = 5;
+
+// A triple buffer: the writer fills its back buffer and swaps it for the
+// middle one, and the reader swaps the middle one for its front buffer when
+// something new has been published. Which slot is in the middle, and whether
+// it's fresh, is a single atomic byte, so neither side copies or waits.
+const INDEX: u8 = 0b11;
+const FRESH: u8 = 0b100;
+
+struct Slots<T> {
+    buffers: [UnsafeCell<T>; 3],
+    // The middle buffer's index, with `FRESH` set while it holds a frame the
+    // reader hasn't taken.
+    middle: AtomicU8,
+}
+
+// The publisher only touches its back slot and the subscriber its front
+// slot, and slots only change hands through the swaps on `middle`, which
+// order the writes before them with the reads after.
+unsafe impl<T: Send> Sync for Slots<T> {}
+
+pub struct Publisher<T> {
+    slots: Arc<Slots<T>>,
+    back: u8,
+}
+
+impl<T> Publisher<T> {
+    pub fn back(&mut self) -> &mut T {
+        // SAFETY: the back slot is the publisher's until `publish` hands it
+        // over.
+        unsafe { &mut *self.slots.buffers[self.back as usize].get() }
+    }
+
+    pub fn publish(&mut self) {
+        let previous = self.slots.middle.swap(self.back | FRESH, Ordering::AcqRel);
+        self.back = previous & INDEX;
+    }
+}
+
+pub struct Subscriber<T> {
+    slots: Arc<Slots<T>>,
+    front: u8,
+}
+
+impl<T> Subscriber<T> {
+    // The most recently published buffer.
+    pub fn latest(&mut self) -> &T {
+        if self.slots.middle.load(Ordering::Relaxed) & FRESH != 0 {
+            let previous = self.slots.middle.swap(self.front, Ordering::AcqRel);
+            self.front = previous & INDEX;
+        }
+        // SAFETY: the front slot is the subscriber's until the next swap
+        // above, which needs `&mut self` and so outlives this borrow.
+        unsafe { &*self.slots.buffers[self.front as usize].get() }
+    }
+}
+
+pub fn triple_buffer<T: Clone>(initial: T) -> (Publisher<T>, Subscriber<T>) {
+    let slots = Arc::new(Slots {
+        buffers: [
+            UnsafeCell::new(initial.clone()),
+            UnsafeCell::new(initial.clone()),
+            UnsafeCell::new(initial),
+        ],
+        middle: AtomicU8::new(1),
+    });
+    (
+        Publisher {
+            slots: slots.clone(),
+            back: 0,
+        },
+        Subscriber { slots, front: 2 },
+    )
+}
+
+// Runs a `Machine` at 60 frames a second on its own thread, so window drags
+// and slow vsync don't stall the CPU or its timers. Controls go in over a
+// channel; finished frames come out through a triple buffer.
+pub struct EmulationThread {
+    controls: Sender<Control>,
+    frames: Subscriber<Snapshot>,
+    handle: JoinHandle<Machine>,
+}
+
+impl EmulationThread {
+    pub fn spawn(mut machine: Machine) -> Self {
+        let mut snapshot = Snapshot::default();
+        machine.snapshot_into(&mut snapshot);
+        let (mut publisher, frames) = triple_buffer(snapshot);
+        let (controls, received) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            let mut next = Instant::now();
+            while apply_controls(&mut machine, &received) {
+                machine.advance();
+                machine.snapshot_into(publisher.back());
+                publisher.publish();
+
+                next += FRAME_TIME;
+                let now = Instant::now();
+                if next > now {
+                    thread::sleep(next - now);
+                } else if now - next > FRAME_TIME * MAX_LAG {
+                    next = now;
+                }
+            }
+            machine
+        });
+
+        Self {
+            controls,
+            frames,
+            handle,
+        }
+    }
+
+    pub fn send(&self, control: Control) {
+        // Only fails once the thread has stopped, which `is_finished` reports.
+        let _ = self.controls.send(control);
+    }
+
+    pub fn latest(&mut self) -> &Snapshot {
+        self.frames.latest()
+    }
+
+    // True once the thread has stopped, which before `join` means the CPU
+    // panicked.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    // Stops the thread and returns the machine as it was after its last
+    // frame. A panic on the thread carries on here.
+    pub fn join(self) -> Machine {
+        drop(self.controls);
+        self.handle
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+// Applies every waiting control. Returns false once the sending side has
+// hung up.
+fn apply_controls(machine: &mut Machine, received: &Receiver<Control>) -> bool {
+    loop {
+        match received.try_recv() {
+            Ok(control) => machine.apply(control),
+            Err(TryRecvError::Empty) => return true,
+            Err(TryRecvError::Disconnected) => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::Cpu;
+    use crate::frontend::{Emulator, NullAudio, NullVideo, ScriptedInput};
+
+    #[test]
+    fn the_reader_sees_the_latest_publish() {
+        let (mut publisher, mut subscriber) = triple_buffer(0);
+        assert_eq!(*subscriber.latest(), 0);
+        *publisher.back() = 1;
+        publisher.publish();
+        *publisher.back() = 2;
+        publisher.publish();
+        assert_eq!(*subscriber.latest(), 2);
+        // Nothing new: the same buffer again.
+        assert_eq!(*subscriber.latest(), 2);
+        *publisher.back() = 3;
+        assert_eq!(*subscriber.latest(), 2);
+        publisher.publish();
+        assert_eq!(*subscriber.latest(), 3);
+    }
+
+    #[test]
+    fn frames_arrive_whole_and_in_order_across_threads() {
+        let (mut publisher, mut subscriber) = triple_buffer(vec![0u32; 256]);
+        let writer = thread::spawn(move || {
+            for frame in 1..=20_000 {
+                publisher.back().fill(frame);
+                publisher.publish();
+            }
+        });
+        let mut last = 0;
+        while last < 20_000 {
+            let frame = subscriber.latest();
+            assert!(frame.iter().all(|&value| value == frame[0]));
+            assert!(frame[0] >= last);
+            last = frame[0];
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn a_threaded_emulator_hands_the_machine_back() {
+        let mut cpu = Cpu::new();
+        // JP 0x200
+        cpu.memory.load_program(&[0x12, 0x00]).unwrap();
+        let input = ScriptedInput {
+            log: None,
+            frames: 5,
+        };
+        let mut emulator = Emulator::new(cpu, "", NullVideo, NullAudio, input);
+        emulator.threaded = true;
+        emulator.run();
+        assert!(emulator.machine.frame >= 5);
+        assert_eq!(emulator.machine.cpu.program_counter, 0x200);
+    }
+}
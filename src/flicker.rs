@@ -0,0 +1,127 @@
+use crate::display::{Display, HEIGHT, WIDTH};
+
+pub const MAX_FRAMES: u8 = 8;
+const DEFAULT_FRAMES: u8 = 3;
+
+// Games that erase and redraw their sprites with XOR every frame.
+const ROM_DEFAULTS: [(&str, u8); 8] = [
+    ("Pong", 2),
+    ("Brix", 2),
+    ("Breakout", 2),
+    ("Brick", 2),
+    ("Space Invaders", 3),
+    ("Blinky", 3),
+    ("Tank", 2),
+    ("UFO", 2),
+];
+
+pub fn default_frames(rom_path: &str) -> Option<u8> {
+    let rom_name = std::path::Path::new(rom_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(rom_path);
+
+    ROM_DEFAULTS
+        .iter()
+        .find(|(prefix, _)| rom_name.starts_with(prefix))
+        .map(|&(_, frames)| frames)
+}
+
+pub struct FlickerFilter {
+    pub enabled: bool,
+    frames: u8,
+    history: [[u8; WIDTH]; HEIGHT],
+    output: Display,
+}
+
+impl FlickerFilter {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            frames: DEFAULT_FRAMES,
+            history: [[0; WIDTH]; HEIGHT],
+            output: Display::new(),
+        }
+    }
+
+    pub fn for_rom(rom_path: &str) -> Self {
+        let mut filter = Self::new();
+        if let Some(frames) = default_frames(rom_path) {
+            filter.enabled = true;
+            filter.set_frames(frames);
+        }
+        filter
+    }
+
+    pub fn set_frames(&mut self, frames: u8) {
+        self.frames = frames.clamp(1, MAX_FRAMES);
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    // Called once per instruction batch. Each pixel keeps a bit of on/off
+    // history per batch, and is presented lit if it was on in any of the
+    // last `frames` batches.
+    pub fn update(&mut self, display: &Display) {
+        let mask = ((1u16 << self.frames) - 1) as u8;
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let history = &mut self.history[y][x];
+                *history = (*history << 1) | display.get_pixel(x, y) as u8;
+                self.output.set_pixel(x, y, *history & mask != 0);
+            }
+        }
+    }
+
+    pub fn present<'a>(&'a self, display: &'a Display) -> &'a Display {
+        if self.enabled {
+            &self.output
+        } else {
+            display
+        }
+    }
+}
+
+impl Default for FlickerFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_pixel_turned_off_stays_lit_for_the_window_then_clears() {
+        let mut filter = FlickerFilter::new();
+        filter.enabled = true;
+        filter.set_frames(3);
+        let mut display = Display::new();
+
+        display.set_pixel(5, 7, true);
+        filter.update(&display);
+        assert!(filter.present(&display).get_pixel(5, 7));
+
+        display.set_pixel(5, 7, false);
+        for _ in 0..2 {
+            filter.update(&display);
+            assert!(filter.present(&display).get_pixel(5, 7));
+        }
+        filter.update(&display);
+        assert!(!filter.present(&display).get_pixel(5, 7));
+    }
+
+    #[test]
+    fn disabled_filter_presents_the_display_unchanged() {
+        let mut filter = FlickerFilter::new();
+        let mut display = Display::new();
+        display.set_pixel(1, 1, true);
+        filter.update(&display);
+        display.set_pixel(1, 1, false);
+        filter.update(&display);
+        assert!(!filter.present(&display).get_pixel(1, 1));
+    }
+}
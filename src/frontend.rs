@@ -0,0 +1,440 @@
+use crate::audio::{Buzzer, WavRecorder, SAMPLES_PER_FRAME};
+use crate::cpu::Cpu;
+use crate::display::{Display, Palette};
+use crate::emulation::EmulationThread;
+use crate::flicker::FlickerFilter;
+use crate::layout::Scaling;
+use crate::recording::GifRecorder;
+use crate::replay::InputLog;
+use crate::screenshot;
+use crate::state;
+use crate::ui::UI;
+
+pub struct Frame<'a> {
+    pub display: &'a Display,
+    pub cpu: &'a Cpu,
+    pub rom_path: &'a str,
+    pub patched: bool,
+    pub palette: &'a Palette,
+    pub scaling: Scaling,
+    pub grid: bool,
+    // Shows the profiler's heatmap over the screen.
+    pub heatmap: bool,
+    pub ui: &'a UI,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    TogglePause,
+    ToggleStepMode,
+    Step,
+    ToggleFlicker,
+    ToggleMute,
+    Screenshot,
+    ToggleGifRecording,
+    ToggleFullscreen,
+    CycleScaling,
+    ToggleGrid,
+    ToggleHeatmap,
+    Reset,
+    LoadState,
+    Quit,
+}
+
+pub struct Input {
+    pub keys: u16,
+    pub commands: Vec<Command>,
+}
+
+pub trait VideoSink {
+    // Called once per loop iteration. Frontends that pace themselves (vsync,
+    // sleeping) do it here.
+    fn present(&mut self, frame: &Frame);
+
+    fn toggle_fullscreen(&mut self) {}
+}
+
+pub trait AudioSink {
+    // Called once per loop iteration with whether the buzzer should sound.
+    fn update(&mut self, active: bool);
+
+    fn toggle_mute(&mut self) {}
+}
+
+pub trait InputSource {
+    // `frame` counts emulated frames, not loop iterations; it stops while
+    // paused or stepping.
+    fn poll(&mut self, frame: u32) -> Input;
+}
+
+// Changes to the emulation itself. The emulator sends these to its
+// `Machine`, inline or on the emulation thread, once per frame.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Control {
+    Keys(u16),
+    TogglePause,
+    ToggleStepMode,
+    Step,
+    ToggleFlicker,
+    Reset,
+    LoadState(String),
+    // Turns the profiler on if it isn't already.
+    StartProfiling,
+}
+
+// Everything that moves forward with emulated time: the CPU, pause and step
+// state, the flicker filter and input recording and playback.
+pub struct Machine {
+    pub cpu: Cpu,
+    pub ui: UI,
+    pub flicker: FlickerFilter,
+    pub frame: u32,
+    pub input_log: Option<InputLog>,
+    // Replaces keys from the input source when set.
+    pub replay: Option<InputLog>,
+    keys: u16,
+    step: bool,
+    // The state to go back to on reset.
+    start: Vec<u8>,
+}
+
+impl Machine {
+    pub fn new(cpu: Cpu, rom_path: &str) -> Self {
+        Self {
+            start: state::encode(&cpu),
+            cpu,
+            ui: UI::new(),
+            flicker: FlickerFilter::for_rom(rom_path),
+            frame: 0,
+            input_log: None,
+            replay: None,
+            keys: 0,
+            step: false,
+        }
+    }
+
+    pub fn apply(&mut self, control: Control) {
+        match control {
+            Control::Keys(keys) => self.keys = keys,
+            Control::TogglePause => self.ui.paused = !self.ui.paused,
+            Control::ToggleStepMode => self.ui.step_mode = !self.ui.step_mode,
+            Control::Step => self.step = true,
+            Control::ToggleFlicker => self.flicker.toggle(),
+            Control::Reset => {
+                if let Err(e) = state::decode(&mut self.cpu, &self.start) {
+                    eprintln!("Failed to reset: {}", e);
+                }
+            }
+            Control::LoadState(path) => match state::load(&mut self.cpu, &path) {
+                Ok(()) => eprintln!("Loaded state: {}", path),
+                Err(e) => eprintln!("Failed to load state '{}': {}", path, e),
+            },
+            Control::StartProfiling => {
+                self.cpu.profile.get_or_insert_with(Box::default);
+            }
+        }
+    }
+
+    // Runs one frame, or one instruction when stepping and a step was asked
+    // for. Nothing runs while paused.
+    pub fn advance(&mut self) {
+        let keys = match &self.replay {
+            Some(replay) => replay.keys_at(self.frame),
+            None => self.keys,
+        };
+        self.cpu.keyboard.set_mask(keys);
+
+        let step = std::mem::take(&mut self.step);
+        if self.ui.paused {
+            return;
+        }
+        if self.ui.step_mode {
+            if step {
+                self.cpu.cycle();
+                self.flicker.update(&self.cpu.display);
+            }
+        } else {
+            if let Some(log) = &mut self.input_log {
+                log.record(self.frame, keys);
+            }
+            self.cpu.run_frame();
+            self.frame += 1;
+            self.flicker.update(&self.cpu.display);
+        }
+    }
+
+    pub fn snapshot_into(&self, snapshot: &mut Snapshot) {
+        snapshot.cpu.clone_from(&self.cpu);
+        snapshot
+            .display
+            .clone_from(self.flicker.present(&self.cpu.display));
+        snapshot.ui.clone_from(&self.ui);
+        snapshot.frame = self.frame;
+    }
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new(Cpu::new(), "")
+    }
+}
+
+// A finished frame as the frontend shows it.
+#[derive(Clone, Default)]
+pub struct Snapshot {
+    pub cpu: Cpu,
+    // After flicker reduction.
+    pub display: Display,
+    pub ui: UI,
+    pub frame: u32,
+}
+
+pub struct Emulator<V, A, I> {
+    pub machine: Machine,
+    pub rom_path: String,
+    pub patched: bool,
+    pub palette: Palette,
+    pub scale: usize,
+    pub scaling: Scaling,
+    pub grid: bool,
+    pub heatmap: bool,
+    pub video: V,
+    pub audio: A,
+    pub input: I,
+    pub gif_scale: usize,
+    pub gif_skip: u32,
+    pub gif_recorder: Option<GifRecorder>,
+    // Loaded by `Command::LoadState`.
+    pub state_path: Option<String>,
+    // Runs the machine on its own thread, so slow presents don't hold it up.
+    pub threaded: bool,
+    // Holds the machine while `threaded` runs it.
+    thread: Option<EmulationThread>,
+    screenshot_requested: bool,
+}
+
+impl<V: VideoSink, A: AudioSink, I: InputSource> Emulator<V, A, I> {
+    pub fn new(cpu: Cpu, rom_path: &str, video: V, audio: A, input: I) -> Self {
+        Self {
+            machine: Machine::new(cpu, rom_path),
+            rom_path: rom_path.to_string(),
+            patched: false,
+            palette: Palette::default(),
+            scale: crate::display::PIXEL_SIZE as usize,
+            scaling: Scaling::Integer,
+            grid: false,
+            heatmap: false,
+            video,
+            audio,
+            input,
+            gif_scale: 4,
+            gif_skip: 1,
+            gif_recorder: None,
+            state_path: None,
+            threaded: false,
+            thread: None,
+            screenshot_requested: false,
+        }
+    }
+
+    pub fn run(&mut self) {
+        if !self.threaded {
+            while self.run_frame() {}
+            return;
+        }
+        self.thread = Some(EmulationThread::spawn(std::mem::take(&mut self.machine)));
+        while self.run_threaded_frame() {}
+        if let Some(thread) = self.thread.take() {
+            self.machine = thread.join();
+        }
+    }
+
+    // Returns false once the input source asks to quit.
+    pub fn run_frame(&mut self) -> bool {
+        let input = self.input.poll(self.machine.frame);
+        let Some(controls) = self.handle(input) else {
+            return false;
+        };
+        for control in controls {
+            self.machine.apply(control);
+        }
+        self.machine.advance();
+        self.present();
+        true
+    }
+
+    // Returns false once the input source asks to quit or the thread has
+    // stopped.
+    fn run_threaded_frame(&mut self) -> bool {
+        let Some(thread) = &mut self.thread else {
+            return false;
+        };
+        if thread.is_finished() {
+            return false;
+        }
+        let frame = thread.latest().frame;
+        let input = self.input.poll(frame);
+        let Some(controls) = self.handle(input) else {
+            return false;
+        };
+        if let Some(thread) = &self.thread {
+            for control in controls {
+                thread.send(control);
+            }
+        }
+        self.present();
+        true
+    }
+
+    // Handles the frontend's own commands and turns the rest into controls
+    // for the machine. Returns `None` on quit.
+    fn handle(&mut self, input: Input) -> Option<Vec<Control>> {
+        let mut controls = vec![Control::Keys(input.keys)];
+        for command in input.commands {
+            match command {
+                Command::TogglePause => controls.push(Control::TogglePause),
+                Command::ToggleStepMode => controls.push(Control::ToggleStepMode),
+                Command::Step => controls.push(Control::Step),
+                Command::ToggleFlicker => controls.push(Control::ToggleFlicker),
+                Command::Reset => controls.push(Control::Reset),
+                Command::LoadState => match &self.state_path {
+                    Some(path) => controls.push(Control::LoadState(path.clone())),
+                    None => eprintln!("No state to load (start with --load-state <file>)"),
+                },
+                Command::ToggleMute => self.audio.toggle_mute(),
+                Command::Screenshot => self.screenshot_requested = true,
+                Command::ToggleGifRecording => self.toggle_gif_recording(),
+                Command::ToggleFullscreen => self.video.toggle_fullscreen(),
+                Command::CycleScaling => self.scaling = self.scaling.next(),
+                Command::ToggleGrid => self.grid = !self.grid,
+                Command::ToggleHeatmap => {
+                    self.heatmap = !self.heatmap;
+                    if self.heatmap {
+                        controls.push(Control::StartProfiling);
+                    }
+                }
+                Command::Quit => return None,
+            }
+        }
+        Some(controls)
+    }
+
+    // Shows the machine's state, or the thread's latest frame when it runs
+    // on its own, without copying either.
+    fn present(&mut self) {
+        let (display, cpu, ui) = match &mut self.thread {
+            Some(thread) => {
+                let snapshot = thread.latest();
+                (&snapshot.display, &snapshot.cpu, &snapshot.ui)
+            }
+            None => (
+                self.machine.flicker.present(&self.machine.cpu.display),
+                &self.machine.cpu,
+                &self.machine.ui,
+            ),
+        };
+        self.audio.update(cpu.sound_timer > 0 && !ui.paused);
+
+        if let Some(recorder) = &mut self.gif_recorder {
+            recorder.capture(display);
+        }
+        if std::mem::take(&mut self.screenshot_requested) {
+            match screenshot::save(display, &self.palette, self.scale, &self.rom_path) {
+                Ok(path) => eprintln!("Saved screenshot: {}", path.display()),
+                Err(e) => eprintln!("Failed to save screenshot: {}", e),
+            }
+        }
+        self.video.present(&Frame {
+            display,
+            cpu,
+            rom_path: &self.rom_path,
+            patched: self.patched,
+            palette: &self.palette,
+            scaling: self.scaling,
+            grid: self.grid,
+            heatmap: self.heatmap,
+            ui,
+        });
+    }
+
+    fn toggle_gif_recording(&mut self) {
+        match self.gif_recorder.take() {
+            Some(recorder) => {
+                let path = screenshot::capture_path(&self.rom_path, "gif");
+                match screenshot::write_file(&path, &recorder.finish()) {
+                    Ok(()) => eprintln!("Saved recording: {}", path.display()),
+                    Err(e) => eprintln!("Failed to save recording: {}", e),
+                }
+            }
+            None => {
+                self.gif_recorder = Some(GifRecorder::new(
+                    &self.palette,
+                    self.gif_scale,
+                    self.gif_skip,
+                ));
+                eprintln!("Recording GIF...");
+            }
+        }
+    }
+}
+
+pub struct NullVideo;
+
+impl VideoSink for NullVideo {
+    fn present(&mut self, _frame: &Frame) {}
+}
+
+pub struct NullAudio;
+
+impl AudioSink for NullAudio {
+    fn update(&mut self, _active: bool) {}
+}
+
+// Generates exactly one frame's worth of buzzer samples per update, for
+// headless runs where no audio device paces the emulator.
+pub struct FrameAudio {
+    pub buzzer: Buzzer,
+    pub recorder: Option<WavRecorder>,
+    samples: Vec<f32>,
+}
+
+impl FrameAudio {
+    pub fn new(buzzer: Buzzer, recorder: Option<WavRecorder>) -> Self {
+        Self {
+            buzzer,
+            recorder,
+            samples: vec![0.0; SAMPLES_PER_FRAME],
+        }
+    }
+}
+
+impl AudioSink for FrameAudio {
+    fn update(&mut self, active: bool) {
+        if let Some(recorder) = &mut self.recorder {
+            self.buzzer.fill(active, &mut self.samples);
+            recorder.push(&self.samples);
+        }
+    }
+
+    fn toggle_mute(&mut self) {
+        self.buzzer.muted = !self.buzzer.muted;
+    }
+}
+
+// Plays back an input log and quits after `frames` emulated frames.
+pub struct ScriptedInput {
+    pub log: Option<InputLog>,
+    pub frames: u32,
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self, frame: u32) -> Input {
+        let keys = self.log.as_ref().map(|log| log.keys_at(frame)).unwrap_or(0);
+        let commands = if frame >= self.frames {
+            vec![Command::Quit]
+        } else {
+            Vec::new()
+        };
+        Input { keys, commands }
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+
+const MAX_CODES: u16 = 4096;
+
+pub struct GifEncoder {
+    data: Vec<u8>,
+    width: u16,
+    height: u16,
+    min_code_size: u8,
+}
+
+impl GifEncoder {
+    pub fn new(width: u16, height: u16, palette: &[[u8; 3]]) -> Self {
+        assert!(!palette.is_empty() && palette.len() <= 256);
+
+        // The global colour table holds 2^(n+1) entries.
+        let table_bits = (palette.len().next_power_of_two().trailing_zeros() as u8).max(1);
+        let table_size = 1usize << table_bits;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"GIF89a");
+        data.extend_from_slice(&width.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.push(0x80 | ((table_bits - 1) << 4) | (table_bits - 1));
+        data.push(0);
+        data.push(0);
+        for i in 0..table_size {
+            data.extend_from_slice(&palette.get(i).copied().unwrap_or([0, 0, 0]));
+        }
+
+        // NETSCAPE2.0 extension: loop forever.
+        data.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+        data.extend_from_slice(b"NETSCAPE2.0");
+        data.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        Self {
+            data,
+            width,
+            height,
+            min_code_size: table_bits.max(2),
+        }
+    }
+
+    // `pixels` holds one palette index per pixel; `delay` is in hundredths of
+    // a second.
+    pub fn add_frame(&mut self, pixels: &[u8], delay: u16) {
+        assert_eq!(pixels.len(), self.width as usize * self.height as usize);
+
+        self.data.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        self.data.extend_from_slice(&delay.to_le_bytes());
+        self.data.extend_from_slice(&[0x00, 0x00]);
+
+        self.data.push(0x2C);
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data.extend_from_slice(&self.width.to_le_bytes());
+        self.data.extend_from_slice(&self.height.to_le_bytes());
+        self.data.push(0);
+
+        self.data.push(self.min_code_size);
+        let compressed = lzw_encode(self.min_code_size, pixels);
+        for block in compressed.chunks(255) {
+            self.data.push(block.len() as u8);
+            self.data.extend_from_slice(block);
+        }
+        self.data.push(0);
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.data.push(0x3B);
+        self.data
+    }
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn lzw_encode(min_code_size: u8, pixels: &[u8]) -> Vec<u8> {
+    let clear_code = 1u16 << min_code_size;
+    let end_code = clear_code + 1;
+
+    let mut writer = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut code_size = min_code_size + 1;
+    let mut next_code = end_code + 1;
+
+    writer.write(clear_code, code_size);
+
+    let Some((&first, rest)) = pixels.split_first() else {
+        writer.write(end_code, code_size);
+        return writer.finish();
+    };
+
+    let mut prefix = first as u16;
+    for &pixel in rest {
+        if let Some(&code) = dictionary.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.write(prefix, code_size);
+        dictionary.insert((prefix, pixel), next_code);
+        if next_code == 1 << code_size {
+            code_size += 1;
+        }
+        next_code += 1;
+
+        if next_code == MAX_CODES {
+            writer.write(clear_code, code_size);
+            dictionary.clear();
+            code_size = min_code_size + 1;
+            next_code = end_code + 1;
+        }
+
+        prefix = pixel as u16;
+    }
+
+    writer.write(prefix, code_size);
+    // The decoder adds one more table entry after reading the last code, which
+    // may bump the code size before it reads the end code.
+    if next_code == 1 << code_size && code_size < 12 {
+        code_size += 1;
+    }
+    writer.write(end_code, code_size);
+    writer.finish()
+}
+
+// A decoded GIF: the palette index of every pixel of every frame, in the
+// order they are stored. Frames keep their own size, without compositing.
+pub struct DecodedGif {
+    pub width: u16,
+    pub height: u16,
+    pub frames: Vec<Vec<u8>>,
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<u16, String> {
+    data.get(pos..pos + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| "truncated GIF".to_string())
+}
+
+// Joins a run of length-prefixed sub-blocks, returning the data and the
+// position after the terminator.
+fn sub_blocks(data: &[u8], mut pos: usize) -> Result<(Vec<u8>, usize), String> {
+    let mut joined = Vec::new();
+    loop {
+        let len = *data.get(pos).ok_or("truncated GIF")? as usize;
+        pos += 1;
+        if len == 0 {
+            return Ok((joined, pos));
+        }
+        joined.extend_from_slice(data.get(pos..pos + len).ok_or("truncated GIF")?);
+        pos += len;
+    }
+}
+
+pub fn decode(data: &[u8]) -> Result<DecodedGif, String> {
+    if !data.starts_with(b"GIF87a") && !data.starts_with(b"GIF89a") {
+        return Err("not a GIF image".to_string());
+    }
+    let width = read_u16(data, 6)?;
+    let height = read_u16(data, 8)?;
+    let flags = *data.get(10).ok_or("truncated GIF")?;
+    let mut pos = 13;
+    if flags & 0x80 != 0 {
+        pos += 3 << ((flags & 7) + 1);
+    }
+
+    let mut frames = Vec::new();
+    loop {
+        match data.get(pos) {
+            Some(0x21) => pos = sub_blocks(data, pos + 2)?.1,
+            Some(0x2C) => {
+                let frame_width = read_u16(data, pos + 5)? as usize;
+                let frame_height = read_u16(data, pos + 7)? as usize;
+                let flags = *data.get(pos + 9).ok_or("truncated GIF")?;
+                pos += 10;
+                if flags & 0x80 != 0 {
+                    pos += 3 << ((flags & 7) + 1);
+                }
+                let min_code_size = *data.get(pos).ok_or("truncated GIF")?;
+                let (compressed, next) = sub_blocks(data, pos + 1)?;
+                pos = next;
+                let mut pixels = lzw_decode(min_code_size, &compressed)?;
+                pixels.resize(frame_width * frame_height, 0);
+                frames.push(pixels);
+            }
+            Some(0x3B) | None => break,
+            Some(block) => return Err(format!("unknown GIF block 0x{:02X}", block)),
+        }
+    }
+    Ok(DecodedGif {
+        width,
+        height,
+        frames,
+    })
+}
+
+fn lzw_decode(min_code_size: u8, data: &[u8]) -> Result<Vec<u8>, String> {
+    if !(1..=11).contains(&min_code_size) {
+        return Err("bad GIF code size".to_string());
+    }
+    let clear_code = 1u16 << min_code_size;
+    let end_code = clear_code + 1;
+
+    // Each entry is (prefix code, last byte); roots have no prefix.
+    let mut table: Vec<(Option<u16>, u8)> = Vec::new();
+    let reset = |table: &mut Vec<(Option<u16>, u8)>| {
+        table.clear();
+        table.extend((0..clear_code).map(|i| (None, i as u8)));
+        table.push((None, 0));
+        table.push((None, 0));
+    };
+    reset(&mut table);
+
+    let expand = |table: &[(Option<u16>, u8)], mut code: u16, out: &mut Vec<u8>| -> u8 {
+        let start = out.len();
+        loop {
+            let (prefix, byte) = table[code as usize];
+            out.push(byte);
+            match prefix {
+                Some(prefix) => code = prefix,
+                None => break,
+            }
+        }
+        out[start..].reverse();
+        out[start]
+    };
+
+    let mut out = Vec::new();
+    let mut code_size = min_code_size + 1;
+    let mut previous: Option<u16> = None;
+    let (mut buffer, mut bits, mut pos) = (0u32, 0u8, 0);
+    loop {
+        while bits < code_size {
+            let Some(&byte) = data.get(pos) else {
+                return Ok(out);
+            };
+            buffer |= (byte as u32) << bits;
+            bits += 8;
+            pos += 1;
+        }
+        let code = (buffer & ((1 << code_size) - 1)) as u16;
+        buffer >>= code_size;
+        bits -= code_size;
+
+        if code == clear_code {
+            reset(&mut table);
+            code_size = min_code_size + 1;
+            previous = None;
+            continue;
+        }
+        if code == end_code {
+            return Ok(out);
+        }
+
+        let Some(prev) = previous else {
+            if code >= clear_code {
+                return Err("corrupt GIF data".to_string());
+            }
+            out.push(code as u8);
+            previous = Some(code);
+            continue;
+        };
+        if (code as usize) < table.len() {
+            let first = expand(&table, code, &mut out);
+            if table.len() < MAX_CODES as usize {
+                table.push((Some(prev), first));
+            }
+        } else if code as usize == table.len() {
+            // The code being defined right now: the previous string plus its
+            // own first byte.
+            let first = expand(&table, prev, &mut out);
+            out.push(first);
+            if table.len() < MAX_CODES as usize {
+                table.push((Some(prev), first));
+            }
+        } else {
+            return Err("corrupt GIF data".to_string());
+        }
+        if table.len() == 1 << code_size && code_size < 12 {
+            code_size += 1;
+        }
+        previous = Some(code);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Noise for the first half, so phrases stay short and the table fills
+    // quickly, then a repeating ramp that builds long phrases.
+    fn pixels(len: usize, colours: usize) -> Vec<u8> {
+        let mut state = 0x2545_F491u32;
+        (0..len)
+            .map(|i| {
+                if i < len / 2 {
+                    state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    ((state >> 16) as usize % colours) as u8
+                } else {
+                    (i / 7 % colours) as u8
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn frames_past_the_code_limit_round_trip() {
+        let (width, height) = (256u16, 256u16);
+        for colours in [2, 4, 16, 256] {
+            let frame = pixels(width as usize * height as usize, colours);
+            let palette: Vec<[u8; 3]> = (0..colours).map(|i| [i as u8; 3]).collect();
+            let mut encoder = GifEncoder::new(width, height, &palette);
+            encoder.add_frame(&frame, 2);
+            encoder.add_frame(&frame[..width as usize].repeat(height as usize), 2);
+
+            let min_code_size = encoder.min_code_size;
+            let compressed = lzw_encode(min_code_size, &frame);
+            // Codes are at most 12 bits, so this many bytes means the table
+            // filled, grew to 12 bits and was cleared at least once.
+            assert!(compressed.len() > MAX_CODES as usize * 12 / 8 + 2);
+            assert_eq!(lzw_decode(min_code_size, &compressed).unwrap(), frame);
+
+            let decoded = decode(&encoder.finish()).unwrap();
+            assert_eq!((decoded.width, decoded.height), (width, height));
+            assert_eq!(decoded.frames.len(), 2);
+            assert_eq!(decoded.frames[0], frame);
+            assert_eq!(
+                decoded.frames[1],
+                frame[..width as usize].repeat(height as usize)
+            );
+        }
+    }
+}
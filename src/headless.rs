@@ -0,0 +1,161 @@
+use crate::audio::{Buzzer, WavRecorder};
+use crate::cpu::Cpu;
+use crate::display::{self, Palette};
+use crate::frontend::{Emulator, Frame, FrameAudio, ScriptedInput, VideoSink};
+use crate::recording::GifRecorder;
+use crate::replay::InputLog;
+use crate::screenshot;
+use std::path::Path;
+
+pub struct FrameCapture {
+    pub frames: u32,
+    pub out_path: String,
+}
+
+pub struct HeadlessRun {
+    pub forced: bool,
+    pub frames: u32,
+    pub screenshot: Option<FrameCapture>,
+    pub gif: Option<FrameCapture>,
+    pub gif_scale: usize,
+    pub gif_skip: u32,
+    pub replay: Option<InputLog>,
+    pub wav_path: Option<String>,
+    pub palette: Palette,
+    pub scale: usize,
+}
+
+impl Default for HeadlessRun {
+    fn default() -> Self {
+        Self {
+            forced: false,
+            frames: 0,
+            screenshot: None,
+            gif: None,
+            gif_scale: 4,
+            gif_skip: 1,
+            replay: None,
+            wav_path: None,
+            palette: Palette::default(),
+            scale: display::PIXEL_SIZE as usize,
+        }
+    }
+}
+
+impl HeadlessRun {
+    // Whether something says when the run ends.
+    pub fn is_bounded(&self) -> bool {
+        self.frames > 0 || self.screenshot.is_some() || self.gif.is_some()
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.forced || self.is_bounded()
+    }
+
+    fn total_frames(&self) -> u32 {
+        [&self.screenshot, &self.gif]
+            .iter()
+            .filter_map(|capture| capture.as_ref().map(|capture| capture.frames))
+            .fold(self.frames, u32::max)
+    }
+}
+
+// Saves the screenshot and GIF frames as the emulator presents them; the
+// n-th presented frame is the display after n emulated frames.
+struct CaptureVideo {
+    screenshot: Option<FrameCapture>,
+    scale: usize,
+    gif: Option<(FrameCapture, GifRecorder)>,
+    presented: u32,
+    error: Option<std::io::Error>,
+}
+
+impl VideoSink for CaptureVideo {
+    fn present(&mut self, frame: &Frame) {
+        self.presented += 1;
+
+        if let Some(capture) = &self.screenshot {
+            if self.presented == capture.frames {
+                if let Err(e) = save_screenshot(capture, frame.display, frame.palette, self.scale) {
+                    self.error.get_or_insert(e);
+                }
+            }
+        }
+
+        if let Some((capture, recorder)) = &mut self.gif {
+            if self.presented <= capture.frames {
+                recorder.capture(frame.display);
+            }
+        }
+    }
+}
+
+fn save_screenshot(
+    capture: &FrameCapture,
+    display: &display::Display,
+    palette: &Palette,
+    scale: usize,
+) -> std::io::Result<()> {
+    let path = Path::new(&capture.out_path);
+    screenshot::write_png(path, display, palette, scale)?;
+    eprintln!("Saved screenshot: {}", capture.out_path);
+    Ok(())
+}
+
+// Audio is generated per emulated frame rather than pulled by a device, so
+// sample `n` of the WAV always belongs to frame `n / SAMPLES_PER_FRAME`.
+// Returns the CPU as it was after the last frame.
+pub fn run(
+    cpu: Cpu,
+    rom_path: &str,
+    mut options: HeadlessRun,
+    buzzer: Buzzer,
+) -> std::io::Result<Cpu> {
+    let palette = options.palette;
+    let total_frames = options.total_frames();
+
+    if let Some(capture) = &options.screenshot {
+        if capture.frames == 0 {
+            save_screenshot(capture, &cpu.display, &palette, options.scale)?;
+        }
+    }
+
+    let video = CaptureVideo {
+        screenshot: options.screenshot.take(),
+        scale: options.scale,
+        gif: options.gif.take().map(|capture| {
+            let recorder = GifRecorder::new(&palette, options.gif_scale, options.gif_skip);
+            (capture, recorder)
+        }),
+        presented: 0,
+        error: None,
+    };
+    let recorder = options.wav_path.as_ref().map(|_| WavRecorder::new());
+    let audio = FrameAudio::new(buzzer, recorder);
+    let input = ScriptedInput {
+        log: options.replay.take(),
+        frames: total_frames,
+    };
+
+    let mut emulator = Emulator::new(cpu, rom_path, video, audio, input);
+    emulator.palette = palette;
+    emulator.scale = options.scale;
+    emulator.machine.flicker.enabled = false;
+    emulator.run();
+
+    if let Some(e) = emulator.video.error.take() {
+        return Err(e);
+    }
+
+    if let Some((capture, recorder)) = emulator.video.gif.take() {
+        screenshot::write_file(Path::new(&capture.out_path), &recorder.finish())?;
+        eprintln!("Saved recording: {}", capture.out_path);
+    }
+
+    if let (Some(recorder), Some(path)) = (&emulator.audio.recorder, &options.wav_path) {
+        screenshot::write_file(Path::new(path), &recorder.finish())?;
+        eprintln!("Saved audio: {}", path);
+    }
+
+    Ok(emulator.machine.cpu)
+}
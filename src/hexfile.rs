@@ -0,0 +1,186 @@
+use crate::memory::PROGRAM_START;
+
+// Program bytes from a hex text dump. Two layouts are understood:
+//
+// - Intel HEX, as written by assemblers (`:10020000...` records).
+// - Listings as printed in magazines and manuals: hex bytes or words
+//   separated by spaces, optionally with an address at the start of each
+//   line (`0200: 6A02 6B0C` or `0200 6A02 6B0C`). `;`, `#` and `//` start
+//   comments.
+//
+// Gaps between addressed lines are filled with zeros.
+pub fn parse(text: &str) -> Result<Vec<u8>, String> {
+    let first = text.lines().map(str::trim).find(|line| !line.is_empty());
+    if first.is_some_and(|line| line.starts_with(':')) {
+        parse_intel(text)
+    } else {
+        parse_listing(text)
+    }
+}
+
+fn hex_bytes(token: &str) -> Option<Vec<u8>> {
+    let token = token.trim_start_matches("0x").trim_start_matches("0X");
+    if token.is_empty() || !token.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..token.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(token.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// Places `bytes` at absolute `address`, counting from 0x200.
+fn place(program: &mut Vec<u8>, address: usize, bytes: &[u8]) -> Result<(), String> {
+    let offset = address
+        .checked_sub(PROGRAM_START)
+        .ok_or_else(|| format!("address 0x{:03X} is below 0x200", address))?;
+    if program.len() < offset + bytes.len() {
+        program.resize(offset + bytes.len(), 0);
+    }
+    program[offset..offset + bytes.len()].copy_from_slice(bytes);
+    Ok(())
+}
+
+fn parse_listing(text: &str) -> Result<Vec<u8>, String> {
+    // (line number, tokens) for each line with something on it
+    let mut lines = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.split([';', '#']).next().unwrap_or_default();
+        let line = line.split("//").next().unwrap_or_default();
+        let tokens: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .collect();
+        if !tokens.is_empty() {
+            lines.push((line_number + 1, tokens));
+        }
+    }
+
+    // Without colons, a leading address is only recognised if every line
+    // has one, starting at 0x200 with no gaps.
+    let mut next_address = None;
+    let addressed = !lines.is_empty()
+        && lines.iter().all(|(_, tokens)| {
+            let address = tokens[0].trim_end_matches(':');
+            let Ok(address) = usize::from_str_radix(address, 16) else {
+                return false;
+            };
+            let bytes: usize = tokens[1..]
+                .iter()
+                .map(|token| hex_bytes(token).map_or(0, |bytes| bytes.len()))
+                .sum();
+            let follows = address == next_address.unwrap_or(PROGRAM_START);
+            next_address = Some(address + bytes);
+            tokens[0].ends_with(':') || follows
+        });
+
+    let mut program = Vec::new();
+    let mut address = PROGRAM_START;
+    for (line_number, tokens) in &lines {
+        let data = if addressed {
+            address = usize::from_str_radix(tokens[0].trim_end_matches(':'), 16)
+                .map_err(|_| format!("line {}: bad address '{}'", line_number, tokens[0]))?;
+            &tokens[1..]
+        } else {
+            &tokens[..]
+        };
+        for token in data {
+            let bytes = hex_bytes(token)
+                .ok_or_else(|| format!("line {}: '{}' isn't hex bytes", line_number, token))?;
+            place(&mut program, address, &bytes)?;
+            address += bytes.len();
+        }
+    }
+    Ok(program)
+}
+
+fn parse_intel(text: &str) -> Result<Vec<u8>, String> {
+    let mut records = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |message: &str| format!("line {}: {}", line_number + 1, message);
+        let bytes = line
+            .strip_prefix(':')
+            .and_then(hex_bytes)
+            .ok_or_else(|| error("not an Intel HEX record"))?;
+        if bytes.len() < 5 || bytes.len() != 5 + bytes[0] as usize {
+            return Err(error("record length doesn't match"));
+        }
+        if bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte)) != 0 {
+            return Err(error("checksum mismatch"));
+        }
+        match bytes[3] {
+            0x00 => {
+                let address = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+                records.push((address, bytes[4..bytes.len() - 1].to_vec()));
+            }
+            0x01 => break,
+            kind => return Err(error(&format!("unsupported record type {:02X}", kind))),
+        }
+    }
+
+    // Assemblers normally origin at 0x200, but some count from 0.
+    let base = records
+        .iter()
+        .map(|(address, _)| *address)
+        .min()
+        .unwrap_or(0);
+    let origin = if base >= PROGRAM_START {
+        0
+    } else {
+        PROGRAM_START
+    };
+    let mut program = Vec::new();
+    for (address, data) in &records {
+        place(&mut program, address + origin, data)?;
+    }
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intel_hex() {
+        let text = ":04020000A22A600CC2\n:00000001FF\n";
+        assert_eq!(parse(text).unwrap(), [0xA2, 0x2A, 0x60, 0x0C]);
+    }
+
+    #[test]
+    fn intel_hex_counted_from_zero() {
+        let text = ":0400000000E0A22A50\n:020004001204E4\n";
+        assert_eq!(parse(text).unwrap(), [0x00, 0xE0, 0xA2, 0x2A, 0x12, 0x04]);
+    }
+
+    #[test]
+    fn intel_hex_with_a_bad_checksum() {
+        assert!(parse(":04020000A22A600CC3\n").is_err());
+    }
+
+    #[test]
+    fn listing_with_addresses_and_comments() {
+        let text = "0200: 00E0 A22A ; clear\n0204: 600C     // V0 = 12\n0208: 12 08\n";
+        assert_eq!(
+            parse(text).unwrap(),
+            [0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x00, 0x00, 0x12, 0x08]
+        );
+    }
+
+    #[test]
+    fn listing_without_addresses() {
+        let text = "00E0 A22A\n# words or bytes\n60 0C, 1206\n";
+        assert_eq!(
+            parse(text).unwrap(),
+            [0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x12, 0x06]
+        );
+    }
+
+    #[test]
+    fn listing_with_something_not_hex() {
+        assert!(parse("00E0 A22G\n").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+// Raw DEFLATE (RFC 1951) decoder: enough for zip archives.
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+// Order in which code length code lengths are stored in a dynamic block.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, count: u32) -> Result<u32, String> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self.data.get(self.pos).ok_or("unexpected end of data")?;
+            value |= ((byte as u32 >> self.bit) & 1) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn align(&mut self) {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+    }
+}
+
+// Canonical Huffman code, decoded a bit at a time: `counts[len]` codes of
+// each length, and the symbols sorted by code.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        for len in 1..16 {
+            offsets[len] = offsets[len - 1] + counts[len - 1];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, String> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for len in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[len] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("invalid Huffman code".to_string())
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &symbol in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[symbol] = reader.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths);
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let symbol = code_length_code.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths.last().ok_or("repeat with no previous length")?;
+                (previous, 3 + reader.bits(2)?)
+            }
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths.len() > literal_count + distance_count {
+        return Err("code lengths overrun".to_string());
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literal_count]),
+        Huffman::new(&lengths[literal_count..]),
+    ))
+}
+
+fn too_large(limit: usize) -> String {
+    format!("unpacks to more than {} bytes", limit)
+}
+
+// Stops with an error once the output passes `limit` bytes, so a small
+// archive can't unpack to gigabytes.
+pub fn inflate(data: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    let mut reader = BitReader {
+        data,
+        pos: 0,
+        bit: 0,
+    };
+    let mut out = Vec::new();
+
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let header = data
+                    .get(reader.pos..reader.pos + 4)
+                    .ok_or("truncated stored block")?;
+                let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+                let start = reader.pos + 4;
+                let block = data
+                    .get(start..start + len)
+                    .ok_or("truncated stored block")?;
+                if out.len() + len > limit {
+                    return Err(too_large(limit));
+                }
+                out.extend_from_slice(block);
+                reader.pos = start + len;
+            }
+            kind @ (1 | 2) => {
+                let (literals, distances) = if kind == 1 {
+                    fixed_codes()
+                } else {
+                    dynamic_codes(&mut reader)?
+                };
+                loop {
+                    let symbol = literals.decode(&mut reader)? as usize;
+                    if symbol < 256 {
+                        if out.len() == limit {
+                            return Err(too_large(limit));
+                        }
+                        out.push(symbol as u8);
+                        continue;
+                    }
+                    if symbol == 256 {
+                        break;
+                    }
+                    let index = symbol - 257;
+                    if index >= LENGTH_BASE.len() {
+                        return Err("invalid length symbol".to_string());
+                    }
+                    let len = LENGTH_BASE[index] as usize
+                        + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+                    let index = distances.decode(&mut reader)? as usize;
+                    if index >= DISTANCE_BASE.len() {
+                        return Err("invalid distance symbol".to_string());
+                    }
+                    let distance = DISTANCE_BASE[index] as usize
+                        + reader.bits(DISTANCE_EXTRA[index] as u32)? as usize;
+                    if distance > out.len() {
+                        return Err("distance before start of data".to_string());
+                    }
+                    if out.len() + len > limit {
+                        return Err(too_large(limit));
+                    }
+                    for _ in 0..len {
+                        out.push(out[out.len() - distance]);
+                    }
+                }
+            }
+            _ => return Err("invalid block type".to_string()),
+        }
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_block() {
+        // "PONG PONG PONG PONG" at level 0.
+        let mut data = vec![0x01, 0x13, 0x00, 0xEC, 0xFF];
+        data.extend_from_slice(b"PONG PONG PONG PONG");
+        assert_eq!(inflate(&data, 100).unwrap(), b"PONG PONG PONG PONG");
+    }
+
+    #[test]
+    fn fixed_codes_with_back_references() {
+        let data = [0x0B, 0xF0, 0xF7, 0x73, 0x57, 0x08, 0x40, 0x21, 0x00];
+        assert_eq!(inflate(&data, 100).unwrap(), b"PONG PONG PONG PONG");
+    }
+
+    #[test]
+    fn dynamic_codes() {
+        let data = [
+            0x25, 0x8C, 0xC1, 0x11, 0x00, 0x30, 0x08, 0xC2, 0x66, 0x0B, 0xB0, 0xFF, 0x4C, 0x05,
+            0xEB, 0x47, 0x13, 0x38, 0x13, 0x9C, 0x38, 0x96, 0x2C, 0x2C, 0x53, 0x66, 0x5C, 0x0B,
+            0x84, 0x4C, 0xAA, 0x93, 0x1F, 0x79, 0xAD, 0xAF, 0x2B, 0xA7, 0x9A, 0x32, 0x56, 0xF6,
+            0x0A, 0xDF, 0xDD, 0xA5, 0x2B, 0xA2, 0x07,
+        ];
+        let expected: &[u8] = b"DDACDDCDCBBCBACBCAACDACDCBDDCAAADADCBCABBBBDAACDACCACBCDADCBCBBBACDAABBAADCBBDCDDCACADCBBACABCBCDAAB";
+        assert_eq!(inflate(&data, 100).unwrap(), expected);
+    }
+
+    #[test]
+    fn output_past_the_limit_is_an_error() {
+        // 300 zeros and A22A 600C.
+        let data = [
+            0x63, 0x60, 0x18, 0x05, 0xC4, 0x82, 0x45, 0x5A, 0x09, 0x3C, 0x00,
+        ];
+        let out = inflate(&data, 304).unwrap();
+        assert_eq!(out.len(), 304);
+        assert_eq!(out[300..], [0xA2, 0x2A, 0x60, 0x0C]);
+        assert!(inflate(&data, 303).is_err());
+        assert!(inflate(&data, 100).is_err());
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let data = [0x0B, 0xF0, 0xF7, 0x73];
+        assert!(inflate(&data, 100).is_err());
+    }
+}
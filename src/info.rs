@@ -0,0 +1,150 @@
+use crate::analysis::Analysis;
+use crate::checksum;
+use crate::disasm::Instruction;
+use crate::{rom, romdb};
+use std::collections::BTreeMap;
+
+fn addresses(sites: &[String]) -> String {
+    if sites.is_empty() {
+        "none".to_string()
+    } else {
+        sites.join(", ")
+    }
+}
+
+// Everything `info` can tell about a program without running it.
+pub fn report(rom_path: &str, program: &[u8]) -> String {
+    let analysis = Analysis::new(program);
+    let capacity = rom::capacity();
+    let mut lines = vec![format!("ROM:       {}", rom_path)];
+
+    if program.len() <= capacity {
+        lines.push(format!(
+            "Size:      {} bytes (fits in 0x200-0xFFF, {} bytes free)",
+            program.len(),
+            capacity - program.len()
+        ));
+    } else {
+        lines.push(format!(
+            "Size:      {} bytes (too large: {} bytes past 0xFFF)",
+            program.len(),
+            program.len() - capacity
+        ));
+    }
+    lines.push(format!(
+        "CRC32:     {:08x}",
+        checksum::crc32_update(0, program)
+    ));
+    lines.push(format!(
+        "SHA-1:     {}",
+        checksum::hex(&checksum::sha1(program))
+    ));
+
+    match romdb::lookup(program) {
+        Some(entry) if entry.author.is_empty() => lines.push(format!("Database:  {}", entry.title)),
+        Some(entry) => lines.push(format!("Database:  {} by {}", entry.title, entry.author)),
+        None => lines.push("Database:  no match".to_string()),
+    }
+
+    let variant = analysis.detected_variant();
+    let extension_sites: Vec<String> = analysis
+        .extensions
+        .iter()
+        .filter(|(_, name)| **name == variant)
+        .take(4)
+        .map(|(addr, _)| {
+            format!(
+                "{:04X} at 0x{:03X}",
+                analysis.opcode_at(*addr).unwrap(),
+                addr
+            )
+        })
+        .collect();
+    if extension_sites.is_empty() {
+        lines.push(format!("Variant:   {}", variant));
+    } else {
+        lines.push(format!(
+            "Variant:   {} ({})",
+            variant,
+            extension_sites.join(", ")
+        ));
+    }
+
+    let code_bytes = analysis.code_bytes();
+    lines.push(format!(
+        "Reachable: {} of {} bytes ({:.1}%), {} instructions",
+        code_bytes,
+        program.len(),
+        if program.is_empty() {
+            0.0
+        } else {
+            code_bytes as f64 * 100.0 / program.len() as f64
+        },
+        analysis.instructions.len()
+    ));
+
+    let mut keys = Vec::new();
+    let mut sound = Vec::new();
+    let mut self_modifying = Vec::new();
+    for (&addr, &instruction) in &analysis.instructions {
+        match instruction {
+            Instruction::Skp(x) | Instruction::Sknp(x) => {
+                let key = analysis.register_at(addr, x);
+                keys.push(match key {
+                    Some(key) => format!("0x{:03X} {} (key {:X})", addr, instruction, key & 0xF),
+                    None => format!("0x{:03X} {}", addr, instruction),
+                });
+            }
+            Instruction::LdKey(_) => keys.push(format!("0x{:03X} {}", addr, instruction)),
+            Instruction::LdStVx(_) => sound.push(format!("0x{:03X}", addr)),
+            Instruction::Store(_) | Instruction::LdBcd(_) => {
+                let len = match instruction {
+                    Instruction::Store(x) => x as u16 + 1,
+                    _ => 3,
+                };
+                if let Some(target) = analysis.index_at(addr) {
+                    if (target..target + len).any(|byte| analysis.is_code(byte)) {
+                        self_modifying.push(format!(
+                            "0x{:03X} {} writes code at 0x{:03X}",
+                            addr, instruction, target
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    lines.push(format!("Keys:      {}", addresses(&keys)));
+    lines.push(format!(
+        "Sound:     {}",
+        if sound.is_empty() {
+            "no".to_string()
+        } else {
+            format!("yes, LD ST at {}", sound.join(", "))
+        }
+    ));
+    lines.push(format!(
+        "Self-modifying writes: {}",
+        addresses(&self_modifying)
+    ));
+
+    let computed_jumps: Vec<String> = analysis
+        .computed_jumps
+        .iter()
+        .map(|addr| format!("0x{:03X} {}", addr, analysis.instructions[addr]))
+        .collect();
+    lines.push(format!("Computed jumps: {}", addresses(&computed_jumps)));
+
+    let mut histogram: BTreeMap<&str, usize> = BTreeMap::new();
+    for instruction in analysis.instructions.values() {
+        *histogram.entry(instruction.class()).or_default() += 1;
+    }
+    let mut histogram: Vec<(&str, usize)> = histogram.into_iter().collect();
+    histogram.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    lines.push("Opcodes (reachable):".to_string());
+    for (class, count) in histogram {
+        lines.push(format!("  {:<18} {:>4}", class, count));
+    }
+
+    lines.join("\n") + "\n"
+}
@@ -1,5 +1,47 @@
+#[cfg(feature = "gui")]
 use raylib::prelude::*;
 
+// Keyboard keys for CHIP-8 keys 0 to F. The default puts the 4x4 keypad on
+// 1234/QWER/ASDF/ZXCV.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Keymap {
+    pub keys: [char; 16],
+}
+
+const DEFAULT_KEYMAP: &str = "x123qweasdzc4rfv";
+
+impl Keymap {
+    // Accepts 16 distinct characters, e.g. the default "x123qweasdzc4rfv".
+    pub fn parse(spec: &str) -> Option<Self> {
+        let chars: Vec<char> = spec.chars().map(|c| c.to_ascii_lowercase()).collect();
+        let keys: [char; 16] = chars.clone().try_into().ok()?;
+        let distinct = chars
+            .iter()
+            .enumerate()
+            .all(|(i, c)| !chars[..i].contains(c));
+        let mappable = keys.iter().all(|&c| has_key(c));
+        (distinct && mappable).then_some(Self { keys })
+    }
+
+    pub fn key_for_char(&self, c: char) -> Option<u8> {
+        let c = c.to_ascii_lowercase();
+        self.keys.iter().position(|&key| key == c).map(|i| i as u8)
+    }
+}
+
+// Whether a keyboard key types `c` unshifted. These keys' codes are the
+// uppercase ASCII value, as `Keyboard::update` relies on.
+fn has_key(c: char) -> bool {
+    matches!(c.to_ascii_uppercase(), ' ' | '\'' | ',' | '-' | '.' | '/' | '0'..='9' | ';' | '=' | 'A'..='Z' | '[' | '\\' | ']' | '`')
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::parse(DEFAULT_KEYMAP).unwrap()
+    }
+}
+
+#[derive(Clone)]
 pub struct Keyboard {
     keys: [bool; 16],
 }
@@ -9,26 +51,13 @@ impl Keyboard {
         Self { keys: [false; 16] }
     }
 
-    pub fn update(&mut self, rl: &RaylibHandle) {
-        self.keys[0x1] = rl.is_key_down(KeyboardKey::KEY_ONE);
-        self.keys[0x2] = rl.is_key_down(KeyboardKey::KEY_TWO);
-        self.keys[0x3] = rl.is_key_down(KeyboardKey::KEY_THREE);
-        self.keys[0xC] = rl.is_key_down(KeyboardKey::KEY_FOUR);
-
-        self.keys[0x4] = rl.is_key_down(KeyboardKey::KEY_Q);
-        self.keys[0x5] = rl.is_key_down(KeyboardKey::KEY_W);
-        self.keys[0x6] = rl.is_key_down(KeyboardKey::KEY_E);
-        self.keys[0xD] = rl.is_key_down(KeyboardKey::KEY_R);
-
-        self.keys[0x7] = rl.is_key_down(KeyboardKey::KEY_A);
-        self.keys[0x8] = rl.is_key_down(KeyboardKey::KEY_S);
-        self.keys[0x9] = rl.is_key_down(KeyboardKey::KEY_D);
-        self.keys[0xE] = rl.is_key_down(KeyboardKey::KEY_F);
-
-        self.keys[0xA] = rl.is_key_down(KeyboardKey::KEY_Z);
-        self.keys[0x0] = rl.is_key_down(KeyboardKey::KEY_X);
-        self.keys[0xB] = rl.is_key_down(KeyboardKey::KEY_C);
-        self.keys[0xF] = rl.is_key_down(KeyboardKey::KEY_V);
+    #[cfg(feature = "gui")]
+    pub fn update(&mut self, rl: &RaylibHandle, keymap: &Keymap) {
+        for (key, &c) in self.keys.iter_mut().zip(&keymap.keys) {
+            // Letter and digit key codes are their uppercase ASCII values.
+            *key = key_from_i32(c.to_ascii_uppercase() as i32)
+                .is_some_and(|code| rl.is_key_down(code));
+        }
     }
 
     pub fn is_key_pressed(&self, key: u8) -> bool {
@@ -55,4 +84,23 @@ impl Keyboard {
     pub fn pressed_keys(&self) -> [bool; 16] {
         self.keys
     }
+
+    pub fn mask(&self) -> u16 {
+        self.keys
+            .iter()
+            .enumerate()
+            .fold(0, |mask, (i, &pressed)| mask | ((pressed as u16) << i))
+    }
+
+    pub fn set_mask(&mut self, mask: u16) {
+        for (i, key) in self.keys.iter_mut().enumerate() {
+            *key = mask & (1 << i) != 0;
+        }
+    }
+}
+
+impl Default for Keyboard {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,146 @@
+// Where everything goes in the window. The renderer, the status bar and any
+// debug panels all take their rectangles from here so they always agree,
+// whatever size the window is.
+
+pub const DEFAULT_WINDOW_WIDTH: i32 = 1200;
+pub const DEFAULT_WINDOW_HEIGHT: i32 = 800;
+pub const STATUS_BAR_HEIGHT: i32 = 25;
+// The status bar and the panel under it, above the screen.
+pub const TOP_HEIGHT: i32 = 80;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Scaling {
+    // The largest whole number of window pixels per CHIP-8 pixel.
+    Integer,
+    // As large as fits, keeping the 2:1 shape.
+    Fit,
+    // Fills the whole area, square pixels or not.
+    Stretch,
+}
+
+const SCALINGS: [(&str, Scaling); 3] = [
+    ("integer", Scaling::Integer),
+    ("fit", Scaling::Fit),
+    ("stretch", Scaling::Stretch),
+];
+
+impl Scaling {
+    pub fn parse(name: &str) -> Option<Self> {
+        SCALINGS
+            .iter()
+            .find(|(scaling_name, _)| *scaling_name == name)
+            .map(|&(_, scaling)| scaling)
+    }
+
+    pub fn name(&self) -> &'static str {
+        SCALINGS
+            .iter()
+            .find(|(_, scaling)| scaling == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default()
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        SCALINGS.iter().map(|(name, _)| *name).collect()
+    }
+
+    pub fn next(&self) -> Self {
+        let index = SCALINGS
+            .iter()
+            .position(|(_, scaling)| scaling == self)
+            .unwrap_or_default();
+        SCALINGS[(index + 1) % SCALINGS.len()].1
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Layout {
+    pub status_bar: Rect,
+    // Free space between the status bar and the screen area.
+    pub panel: Rect,
+    // Where the framebuffer is drawn, and the size of one CHIP-8 pixel in it.
+    pub screen: Rect,
+    pub pixel_width: f32,
+    pub pixel_height: f32,
+}
+
+impl Layout {
+    // Lays out a `columns` x `rows` framebuffer in a window of the given size.
+    pub fn new(
+        window_width: i32,
+        window_height: i32,
+        columns: usize,
+        rows: usize,
+        scaling: Scaling,
+    ) -> Self {
+        let width = window_width.max(1) as f32;
+        let top = TOP_HEIGHT.min(window_height) as f32;
+        let status_height = STATUS_BAR_HEIGHT.min(window_height) as f32;
+        let area_height = (window_height as f32 - top).max(1.0);
+        let (columns, rows) = (columns as f32, rows as f32);
+
+        let (pixel_width, pixel_height) = match scaling {
+            Scaling::Integer => {
+                let scale = (width / columns).min(area_height / rows).floor().max(1.0);
+                (scale, scale)
+            }
+            Scaling::Fit => {
+                let scale = (width / columns).min(area_height / rows);
+                (scale, scale)
+            }
+            Scaling::Stretch => (width / columns, area_height / rows),
+        };
+        let screen_width = pixel_width * columns;
+        let screen_height = pixel_height * rows;
+
+        Self {
+            status_bar: Rect::new(0.0, 0.0, width, status_height),
+            panel: Rect::new(0.0, status_height, width, top - status_height),
+            screen: Rect::new(
+                ((width - screen_width) / 2.0).floor(),
+                top + ((area_height - screen_height) / 2.0).floor(),
+                screen_width,
+                screen_height,
+            ),
+            pixel_width,
+            pixel_height,
+        }
+    }
+
+    // The rectangle covered by CHIP-8 pixel (`x`, `y`).
+    pub fn pixel(&self, x: usize, y: usize) -> Rect {
+        Rect::new(
+            self.screen.x + x as f32 * self.pixel_width,
+            self.screen.y + y as f32 * self.pixel_height,
+            self.pixel_width,
+            self.pixel_height,
+        )
+    }
+}
+
+// The starting window size: at least the default, and big enough for the
+// framebuffer at `scale` window pixels per CHIP-8 pixel.
+pub fn window_size(scale: i32) -> (i32, i32) {
+    let width = DEFAULT_WINDOW_WIDTH.max(crate::display::WIDTH as i32 * scale);
+    let height = DEFAULT_WINDOW_HEIGHT.max(TOP_HEIGHT + crate::display::HEIGHT as i32 * scale);
+    (width, height)
+}
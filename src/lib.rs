@@ -0,0 +1,45 @@
+pub mod analysis;
+pub mod audio;
+pub mod bench;
+pub mod cartridge;
+pub mod checksum;
+pub mod cli;
+#[cfg(feature = "gui")]
+pub mod core;
+pub mod coverage;
+pub mod cpu;
+pub mod disasm;
+pub mod display;
+pub mod dot;
+pub mod emulation;
+pub mod flicker;
+pub mod frontend;
+pub mod gif;
+pub mod headless;
+pub mod hexfile;
+pub mod inflate;
+pub mod info;
+pub mod json;
+pub mod keyboard;
+pub mod layout;
+pub mod lint;
+pub mod lockstep;
+pub mod memory;
+pub mod octo;
+pub mod patch;
+pub mod png;
+pub mod profile;
+pub mod quirks;
+#[cfg(feature = "gui")]
+pub mod raylib_frontend;
+pub mod recompile;
+pub mod recording;
+pub mod replay;
+pub mod rom;
+pub mod romdb;
+pub mod screenshot;
+pub mod sprites;
+pub mod state;
+pub mod tui;
+pub mod ui;
+pub mod zip;
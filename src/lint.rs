@@ -0,0 +1,424 @@
+use crate::analysis::{self, Analysis, Block, PROGRAM_START};
+use crate::disasm::{decode, Instruction};
+use crate::memory::MEMORY_SIZE;
+use std::collections::BTreeMap;
+
+// The built-in font fills 0x000-0x04F; everything else outside the ROM
+// starts out as zeros no program should rely on.
+const FONT_END: u16 = 0x50;
+
+// Names for each kind of warning, as printed after the message.
+pub const NEVER_RETURNS: &str = "never-returns";
+pub const EMPTY_STACK: &str = "empty-stack";
+pub const INDEX_RANGE: &str = "index-range";
+pub const UNINITIALISED: &str = "uninitialised";
+pub const SHIFT_QUIRK: &str = "shift-quirk";
+pub const MEMORY_QUIRK: &str = "memory-quirk";
+pub const LONG_SKIP: &str = "long-skip";
+pub const UNREACHABLE: &str = "unreachable";
+
+pub struct Warning {
+    pub address: u16,
+    pub lint: &'static str,
+    pub message: String,
+}
+
+// The bytes from I the instruction touches, and whether it writes them.
+fn index_access(instruction: Instruction) -> Option<(u16, bool)> {
+    match instruction {
+        // SCHIP draws a 16x16 sprite for n = 0.
+        Instruction::Drw(_, _, 0) => Some((32, false)),
+        Instruction::Drw(_, _, n) => Some((n as u16, false)),
+        Instruction::Load(x) => Some((x as u16 + 1, false)),
+        Instruction::Store(x) => Some((x as u16 + 1, true)),
+        Instruction::LdBcd(_) => Some((3, true)),
+        _ => None,
+    }
+}
+
+fn uses_index(instruction: Instruction) -> bool {
+    index_access(instruction).is_some() || matches!(instruction, Instruction::AddI(_))
+}
+
+fn range(start: u16, end: u16) -> String {
+    if end - start == 1 {
+        format!("0x{:03X}", start)
+    } else {
+        format!("0x{:03X}-0x{:03X}", start, end - 1)
+    }
+}
+
+struct Linter<'a> {
+    analysis: &'a Analysis,
+    warnings: Vec<Warning>,
+}
+
+impl Linter<'_> {
+    fn warn(&mut self, address: u16, lint: &'static str, message: String) {
+        self.warnings.push(Warning {
+            address,
+            lint,
+            message,
+        });
+    }
+
+    // Subroutines with no reachable `00EE` leave their return address on the
+    // stack every call; a `00EE` reachable from 0x200 without a call pops an
+    // empty one.
+    fn returns(&mut self) {
+        let analysis = self.analysis;
+        let blocks = analysis.blocks(&analysis.leaders());
+        let functions = analysis.functions(&blocks);
+        let by_start: BTreeMap<u16, &Block> = blocks.iter().map(|b| (b.start, b)).collect();
+        let ends_in_ret = |start: &u16| matches!(by_start[start].last().2, Instruction::Ret);
+
+        for (&addr, &instruction) in &analysis.instructions {
+            let Instruction::Call(target) = instruction else {
+                continue;
+            };
+            let Some(function) = functions.get(&target) else {
+                continue;
+            };
+            if function.computed_jumps.is_empty() && !function.blocks.iter().any(ends_in_ret) {
+                self.warn(
+                    addr,
+                    NEVER_RETURNS,
+                    format!(
+                        "CALL 0x{:03X} never returns: no 00EE is reachable from it, so each call leaves an address on the stack",
+                        target
+                    ),
+                );
+            }
+        }
+
+        if let Some(main) = functions.get(&PROGRAM_START) {
+            for start in main.blocks.iter().filter(|start| ends_in_ret(start)) {
+                let (addr, _, _) = by_start[start].last();
+                self.warn(
+                    addr,
+                    EMPTY_STACK,
+                    "00EE is reachable from 0x200 without a CALL, so it returns with an empty stack"
+                        .to_string(),
+                );
+            }
+        }
+    }
+
+    // `Dxyn`, `Fx33`, `Fx55` and `Fx65` where a nearby `LD I` fixes what they
+    // touch: past the end of memory, or memory nothing ever fills.
+    fn index(&mut self) {
+        let analysis = self.analysis;
+        let mut writes = Vec::new();
+        let mut unknown_writes = false;
+        for (&addr, &instruction) in &analysis.instructions {
+            if let Some((len, true)) = index_access(instruction) {
+                match analysis.index_at(addr) {
+                    Some(target) => writes.push((target, target + len)),
+                    None => unknown_writes = true,
+                }
+            }
+        }
+        let program_end = PROGRAM_START + analysis.program.len() as u16;
+        let initialised = |byte: u16| {
+            byte < FONT_END
+                || (PROGRAM_START..program_end).contains(&byte)
+                || writes
+                    .iter()
+                    .any(|&(start, end)| (start..end).contains(&byte))
+        };
+
+        for (&addr, &instruction) in &analysis.instructions {
+            let (Some((len, write)), Some(target)) =
+                (index_access(instruction), analysis.index_at(addr))
+            else {
+                continue;
+            };
+            let end = target as usize + len as usize;
+            if end > MEMORY_SIZE {
+                self.warn(
+                    addr,
+                    INDEX_RANGE,
+                    format!(
+                        "{} with I = 0x{:03X} reaches {} bytes past 0xFFF",
+                        instruction,
+                        target,
+                        end - MEMORY_SIZE
+                    ),
+                );
+                continue;
+            }
+            // A write with an unknown I could be what fills it.
+            if write || unknown_writes {
+                continue;
+            }
+            let end = end as u16;
+            if let Some(first) = (target..end).find(|&byte| !initialised(byte)) {
+                let last = (first..end).take_while(|&byte| !initialised(byte)).last();
+                self.warn(
+                    addr,
+                    UNINITIALISED,
+                    format!(
+                        "{} reads {}, which the ROM doesn't fill and nothing writes",
+                        instruction,
+                        range(first, last.unwrap_or(first) + 1)
+                    ),
+                );
+            }
+        }
+    }
+
+    // Instructions whose result depends on which interpreter runs them.
+    fn quirks(&mut self) {
+        let analysis = self.analysis;
+        for (&addr, &instruction) in &analysis.instructions {
+            match instruction {
+                Instruction::Shr(x, y) | Instruction::Shl(x, y) if x != y => self.warn(
+                    addr,
+                    SHIFT_QUIRK,
+                    format!(
+                        "{} depends on the shifting quirk: the VIP shifts V{:X} into V{:X}, SCHIP shifts V{:X} in place",
+                        instruction, y, x, x
+                    ),
+                ),
+                Instruction::Store(_) | Instruction::Load(_) => {
+                    if let Some(user) = self.index_used_after(addr) {
+                        self.warn(
+                            addr,
+                            MEMORY_QUIRK,
+                            format!(
+                                "{} at 0x{:03X} uses I after {} without setting it: the VIP leaves I past the last register, SCHIP doesn't move it",
+                                analysis.instructions[&user], user, instruction
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // The next instruction on from `addr` that uses I before anything sets it.
+    fn index_used_after(&self, addr: u16) -> Option<u16> {
+        let analysis = self.analysis;
+        let mut addr = addr;
+        while analysis.successors(addr) == [addr + 2] {
+            addr += 2;
+            let instruction = *analysis.instructions.get(&addr)?;
+            if uses_index(instruction) {
+                return Some(addr);
+            }
+            if matches!(instruction, Instruction::LdI(_) | Instruction::LdFont(_)) {
+                return None;
+            }
+        }
+        None
+    }
+
+    // Interpreters without XO-CHIP skip two bytes, landing on the operand.
+    fn long_skips(&mut self) {
+        let analysis = self.analysis;
+        for (&addr, &instruction) in &analysis.instructions {
+            if analysis::is_skip(instruction) && analysis.opcode_at(addr + 2) == Some(0xF000) {
+                self.warn(
+                    addr,
+                    LONG_SKIP,
+                    format!(
+                        "{} skips the four-byte F000 nnnn at 0x{:03X}; interpreters without XO-CHIP land on its second word",
+                        instruction,
+                        addr + 2
+                    ),
+                );
+            }
+        }
+    }
+
+    // Stretches outside the reachable code that still decode as instructions
+    // and that nothing points I or a computed jump at.
+    fn unreachable(&mut self) {
+        let analysis = self.analysis;
+        let mut referenced = Vec::new();
+        for &instruction in analysis.instructions.values() {
+            match instruction {
+                Instruction::LdI(target) => referenced.push((target, target + 1)),
+                Instruction::JpV0(base) => referenced.push((base, base + 0x100)),
+                _ => {}
+            }
+        }
+
+        let program_end = PROGRAM_START + analysis.program.len() as u16;
+        let mut start = PROGRAM_START;
+        while start < program_end {
+            if analysis.is_code(start) {
+                start += 1;
+                continue;
+            }
+            let end = (start..program_end)
+                .find(|&byte| analysis.is_code(byte))
+                .unwrap_or(program_end);
+            let bytes =
+                &analysis.program[(start - PROGRAM_START) as usize..(end - PROGRAM_START) as usize];
+            let is_referenced = referenced
+                .iter()
+                .any(|&(from, to)| from < end && start < to);
+            let looks_like_code = bytes.len() >= 4
+                && bytes.chunks_exact(2).all(|word| {
+                    let opcode = u16::from_be_bytes([word[0], word[1]]);
+                    match decode(opcode) {
+                        Instruction::Sys(_) | Instruction::Invalid(_) => {
+                            analysis::extension(opcode).is_some()
+                        }
+                        _ => true,
+                    }
+                });
+            if !is_referenced && looks_like_code && bytes.iter().any(|&byte| byte != 0) {
+                self.warn(
+                    start,
+                    UNREACHABLE,
+                    format!(
+                        "{} ({} bytes) decodes as code, but nothing jumps, calls or points I there",
+                        range(start, end),
+                        end - start
+                    ),
+                );
+            }
+            start = end;
+        }
+    }
+}
+
+// Likely bugs a program shows without running it, in address order.
+pub fn lint(program: &[u8]) -> Vec<Warning> {
+    let analysis = Analysis::new(program);
+    let mut linter = Linter {
+        analysis: &analysis,
+        warnings: Vec::new(),
+    };
+    linter.returns();
+    linter.index();
+    linter.quirks();
+    linter.long_skips();
+    linter.unreachable();
+
+    let mut warnings = linter.warnings;
+    warnings.sort_by_key(|warning| warning.address);
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lints(program: &[u8]) -> Vec<(u16, &'static str)> {
+        lint(program)
+            .iter()
+            .map(|warning| (warning.address, warning.lint))
+            .collect()
+    }
+
+    fn has(program: &[u8], kind: &str) -> bool {
+        lints(program).iter().any(|&(_, lint)| lint == kind)
+    }
+
+    #[test]
+    fn never_returns() {
+        // CALL 0x204; JP 0x202; JP 0x204
+        assert_eq!(
+            lints(&[0x22, 0x04, 0x12, 0x02, 0x12, 0x04]),
+            [(0x200, NEVER_RETURNS)]
+        );
+        // CALL 0x204; JP 0x202; RET
+        assert!(lints(&[0x22, 0x04, 0x12, 0x02, 0x00, 0xEE]).is_empty());
+    }
+
+    #[test]
+    fn empty_stack() {
+        // LD V0, 1; RET
+        assert_eq!(lints(&[0x60, 0x01, 0x00, 0xEE]), [(0x202, EMPTY_STACK)]);
+        // CALL 0x204; JP 0x202; RET
+        assert!(!has(&[0x22, 0x04, 0x12, 0x02, 0x00, 0xEE], EMPTY_STACK));
+    }
+
+    #[test]
+    fn index_range() {
+        // LD I, 0xFFC; DRW V0, V1, 15; JP 0x204
+        assert_eq!(
+            lints(&[0xAF, 0xFC, 0xD0, 0x1F, 0x12, 0x04]),
+            [(0x202, INDEX_RANGE)]
+        );
+        // LD I, 0xFF0 draws up to 0xFFE.
+        assert!(!has(&[0xAF, 0xF0, 0xD0, 0x1F, 0x12, 0x04], INDEX_RANGE));
+    }
+
+    #[test]
+    fn index_range_needs_i_fixed_on_every_path() {
+        // The DRW at 0x206 runs with I = 0x210 from 0x200 and I = 0xFFC from
+        // the loop back to 0x204, so neither is certain.
+        for program in [
+            [0xA2, 0x10, 0x12, 0x06, 0xAF, 0xFC, 0xD0, 0x1F, 0x12, 0x04],
+            [0xAF, 0xFC, 0x12, 0x06, 0xA2, 0x10, 0xD0, 0x1F, 0x12, 0x04],
+        ] {
+            assert!(!has(&program, INDEX_RANGE));
+            assert!(!has(&program, UNINITIALISED));
+        }
+    }
+
+    #[test]
+    fn uninitialised() {
+        // LD I, 0x300; DRW V0, V1, 5; JP 0x204
+        assert_eq!(
+            lints(&[0xA3, 0x00, 0xD0, 0x15, 0x12, 0x04]),
+            [(0x202, UNINITIALISED)]
+        );
+        // The sprite is in the ROM: LD I, 0x206; DRW V0, V1, 1; JP 0x204
+        assert!(lints(&[0xA2, 0x06, 0xD0, 0x11, 0x12, 0x04, 0xFF]).is_empty());
+        // Written first: LD I, 0x300; LD [I], V0; LD I, 0x300;
+        // DRW V0, V1, 1; JP 0x208
+        assert!(!has(
+            &[0xA3, 0x00, 0xF0, 0x55, 0xA3, 0x00, 0xD0, 0x11, 0x12, 0x08],
+            UNINITIALISED
+        ));
+    }
+
+    #[test]
+    fn shift_quirk() {
+        // SHR V0, V1; JP 0x202
+        assert_eq!(lints(&[0x80, 0x16, 0x12, 0x02]), [(0x200, SHIFT_QUIRK)]);
+        // SHR V0, V0; JP 0x202
+        assert!(lints(&[0x80, 0x06, 0x12, 0x02]).is_empty());
+    }
+
+    #[test]
+    fn memory_quirk() {
+        // LD I, 0x300; LD [I], V1; LD V1, [I]; JP 0x206
+        assert_eq!(
+            lints(&[0xA3, 0x00, 0xF1, 0x55, 0xF1, 0x65, 0x12, 0x06]),
+            [(0x202, MEMORY_QUIRK)]
+        );
+        // LD I is set again before the load.
+        assert!(lints(&[0xA3, 0x00, 0xF1, 0x55, 0xA3, 0x00, 0xF1, 0x65, 0x12, 0x08]).is_empty());
+    }
+
+    #[test]
+    fn long_skip() {
+        // SE V0, 0; LD I, long 0x0300; JP 0x206
+        assert!(has(
+            &[0x30, 0x00, 0xF0, 0x00, 0x03, 0x00, 0x12, 0x06],
+            LONG_SKIP
+        ));
+        // SE V0, 0; LD I, 0x300; JP 0x204
+        assert!(lints(&[0x30, 0x00, 0xA3, 0x00, 0x12, 0x04]).is_empty());
+    }
+
+    #[test]
+    fn unreachable() {
+        // JP 0x206 over LD V0, 1; LD V1, 2
+        assert_eq!(
+            lints(&[0x12, 0x06, 0x60, 0x01, 0x61, 0x02, 0x12, 0x06]),
+            [(0x202, UNREACHABLE)]
+        );
+        // The same bytes as sprite data: LD I, 0x206; DRW V0, V1, 4; JP 0x204
+        assert!(lints(&[0xA2, 0x06, 0xD0, 0x14, 0x12, 0x04, 0x60, 0x01, 0x61, 0x02]).is_empty());
+        // Padding.
+        assert!(lints(&[0x12, 0x06, 0x00, 0x00, 0x00, 0x00, 0x12, 0x06]).is_empty());
+    }
+}
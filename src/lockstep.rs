@@ -0,0 +1,95 @@
+use crate::cpu::{Cpu, Engine};
+use crate::disasm;
+use crate::memory::MEMORY_SIZE;
+
+// Where the two engines first disagreed. Runs after every instruction, so
+// it compares fields directly and only formats a difference it finds.
+fn difference(interpreter: &Cpu, cached: &Cpu) -> Option<String> {
+    let (expected, actual) = (interpreter, cached);
+    if expected.program_counter != actual.program_counter {
+        return Some(format!(
+            "PC is {:03X}, expected {:03X}",
+            actual.program_counter, expected.program_counter
+        ));
+    }
+    if expected.registers != actual.registers {
+        return Some(format!(
+            "registers are {:02X?}, expected {:02X?}",
+            actual.registers, expected.registers
+        ));
+    }
+    if expected.index != actual.index {
+        return Some(format!(
+            "I is {:03X}, expected {:03X}",
+            actual.index, expected.index
+        ));
+    }
+    if expected.stack != actual.stack {
+        return Some(format!(
+            "stack is {:03X?}, expected {:03X?}",
+            actual.stack, expected.stack
+        ));
+    }
+    if (expected.delay_timer, expected.sound_timer) != (actual.delay_timer, actual.sound_timer) {
+        return Some(format!(
+            "timers are {} {}, expected {} {}",
+            actual.delay_timer, actual.sound_timer, expected.delay_timer, expected.sound_timer
+        ));
+    }
+    if expected.display != actual.display {
+        return Some("the display differs".to_string());
+    }
+    None
+}
+
+// Runs `cpu` under the interpreter and the cached engine side by side for
+// `frames` frames, comparing registers and the display after every
+// instruction and all of memory after every frame. Returns the number of
+// instructions run, or a description of the first difference.
+pub fn check(cpu: &Cpu, frames: u64) -> Result<u64, String> {
+    let mut interpreter = cpu.clone();
+    interpreter.engine = Engine::Interpreter;
+    let mut cached = cpu.clone();
+    cached.engine = Engine::Cached;
+
+    let mut instructions = 0;
+    for frame in 0..frames {
+        for _ in 0..cpu.cycles_per_frame {
+            let pc = interpreter.program_counter;
+            let opcode =
+                (interpreter.memory.read(pc) as u16) << 8 | interpreter.memory.read(pc + 1) as u16;
+            interpreter.cycle();
+            cached.cycle();
+            instructions += 1;
+
+            if let Some(difference) = difference(&interpreter, &cached) {
+                return Err(format!(
+                    "after instruction {} ({:04X} {} at 0x{:03X}): {}",
+                    instructions,
+                    opcode,
+                    disasm::decode(opcode),
+                    pc,
+                    difference
+                ));
+            }
+        }
+        interpreter.tick_timers();
+        cached.tick_timers();
+
+        let expected = interpreter.memory.bytes(0, MEMORY_SIZE);
+        let actual = cached.memory.bytes(0, MEMORY_SIZE);
+        if expected != actual {
+            let address = (0..MEMORY_SIZE)
+                .find(|&i| expected[i] != actual[i])
+                .unwrap_or(0);
+            return Err(format!(
+                "memory differs after frame {}: 0x{:03X} is {:02X}, expected {:02X}",
+                frame + 1,
+                address,
+                actual[address],
+                expected[address]
+            ));
+        }
+    }
+    Ok(instructions)
+}
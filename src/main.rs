@@ -1,17 +1,28 @@
-mod audio;
-mod core;
-mod cpu;
-mod display;
-mod keyboard;
-mod memory;
-mod rom;
-mod ui;
-
-use raylib::prelude::*;
+use chip8_emulator::analysis::Analysis;
+use chip8_emulator::bench::{self, BenchOptions};
+use chip8_emulator::cli::{self, Command, Options};
+use chip8_emulator::coverage::{self, Coverage, CoverageOptions, Report};
+use chip8_emulator::cpu::Engine;
+use chip8_emulator::rom::RomError;
+use chip8_emulator::sprites::{self, Selection, Sprite, SpriteLog, SpriteOptions};
+#[cfg(feature = "gui")]
+use chip8_emulator::{audio, core, layout, raylib_frontend};
+use chip8_emulator::{
+    cpu, disasm, dot, frontend, headless, info, json, lint, lockstep, memory, patch, recompile,
+    replay, rom, screenshot, state,
+};
+use std::any::Any;
+#[cfg(feature = "gui")]
+use std::cell::RefCell;
 use std::env;
 use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+#[cfg(feature = "gui")]
+use std::rc::Rc;
 
-fn list_available_roms() {
+fn list_available_roms(out: &mut dyn Write) {
     let rom_directories = [
         "chip8-roms/games",
         "chip8-roms/demos",
@@ -20,111 +31,653 @@ fn list_available_roms() {
         "chip8-roms/tests",
     ];
 
-    eprintln!("\nAvailable ROMs:");
+    let _ = writeln!(out, "\nAvailable ROMs:");
 
     for dir in &rom_directories {
         if let Ok(entries) = fs::read_dir(dir) {
-            let category = dir.split('/').last().unwrap_or(dir);
-            eprintln!("\n{}:", category.to_uppercase());
-
-            let mut roms: Vec<String> = entries
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| {
-                    entry
-                        .path()
-                        .extension()
-                        .and_then(|ext| ext.to_str())
-                        .map(|ext| ext == "ch8")
-                        .unwrap_or(false)
-                })
-                .map(|entry| format!("  {}/{}", dir, entry.file_name().to_string_lossy()))
-                .collect();
+            let category = dir.split('/').next_back().unwrap_or(dir);
+            let _ = writeln!(out, "\n{}:", category.to_uppercase());
+
+            let mut roms: Vec<String> = Vec::new();
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let path = format!("{}/{}", dir, entry.file_name().to_string_lossy());
+                if rom::is_rom_name(&path) {
+                    roms.push(format!("  {}", path));
+                } else if path.to_ascii_lowercase().ends_with(".zip") {
+                    // One line per ROM inside, ready to pass as the ROM path.
+                    let contents = fs::read(&path).map_err(RomError::from);
+                    match contents.and_then(|data| rom::archive_roms(&data)) {
+                        Ok(names) => {
+                            roms.extend(names.iter().map(|name| format!("  {}:{}", path, name)))
+                        }
+                        Err(e) => roms.push(format!("  {} ({})", path, e)),
+                    }
+                }
+            }
 
             roms.sort();
             for rom in roms {
-                eprintln!("{}", rom);
+                let _ = writeln!(out, "{}", rom);
             }
         }
     }
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+// The program as it would be loaded, patched if there is a patch, but
+// without the size checks so `info` can report on anything.
+fn read_rom(options: &Options) -> (Vec<u8>, Option<String>) {
+    let rom_path = options.rom_path.as_deref().unwrap_or_default();
+    let fail_read =
+        |e: rom::RomError| -> ! { fail(&format!("Failed to read ROM '{}': {}", rom_path, e)) };
+    let (_, program) = rom::read_program(rom_path).unwrap_or_else(|e| fail_read(e));
+    let patch_path = options
+        .patch_path
+        .clone()
+        .or_else(|| rom::sibling_patch(rom_path));
+    match patch_path {
+        Some(patch_path) => (
+            rom::apply_patch(&program, &patch_path).unwrap_or_else(|e| fail_read(e)),
+            Some(patch_path),
+        ),
+        None => (program, None),
+    }
+}
+
+fn make_ips(original: &str, modified: &str, out_path: &str) {
+    let read = |path: &str| {
+        fs::read(path).unwrap_or_else(|e| fail(&format!("Failed to read '{}': {}", path, e)))
+    };
+    let ips = patch::create_ips(&read(original), &read(modified))
+        .unwrap_or_else(|e| fail(&format!("Failed to create patch: {}", e)));
+    match fs::write(out_path, &ips) {
+        Ok(()) => eprintln!("Saved patch: {} ({} bytes)", out_path, ips.len()),
+        Err(e) => fail(&format!("Failed to write '{}': {}", out_path, e)),
+    }
+}
+
+fn load_replays(paths: &[String]) -> Vec<Option<replay::InputLog>> {
+    paths
+        .iter()
+        .map(|path| {
+            let log = replay::InputLog::load(path)
+                .unwrap_or_else(|e| fail(&format!("Failed to load input log '{}': {}", path, e)));
+            Some(log)
+        })
+        .collect()
+}
+
+// Runs the ROM without a window, playing back `replay` if there is one, for
+// `frames` frames or until shortly after the replay's last key change.
+fn run_replay(
+    rom_path: &str,
+    replay: Option<replay::InputLog>,
+    frames: Option<u32>,
+    setup: impl FnOnce(&mut cpu::Cpu),
+) -> cpu::Cpu {
+    let mut cpu = bench_cpu(rom_path, Engine::Interpreter).unwrap_or_else(|e| fail(&e));
+    if let Some(log) = &replay {
+        log.apply_settings(&mut cpu);
+    }
+    setup(&mut cpu);
+    let frames = frames.unwrap_or(match &replay {
+        Some(log) => log.last_frame() + coverage::REPLAY_TAIL,
+        None => coverage::DEFAULT_FRAMES,
+    });
+    if let Some(log) = &replay {
+        cpu.seed_rng(log.seed);
+    }
+
+    let mut machine = frontend::Machine::new(cpu, rom_path);
+    machine.replay = replay;
+    for _ in 0..frames {
+        machine.advance();
+    }
+    machine.cpu
+}
+
+fn run_coverage(options: &CoverageOptions) {
+    let rom_path = options.rom_path.as_str();
+    let rom = rom::Rom::open(rom_path, None)
+        .unwrap_or_else(|e| fail(&format!("Failed to read ROM '{}': {}", rom_path, e)));
+
+    let mut total = Coverage::new();
+    total.runs = 0;
+    for path in &options.merge {
+        let coverage = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| json::parse(&text))
+            .and_then(|document| Coverage::from_json(&document, &rom.data))
+            .unwrap_or_else(|e| fail(&format!("Failed to merge '{}': {}", path, e)));
+        total.merge(&coverage);
+    }
+
+    let mut replays = load_replays(&options.replays);
+    if replays.is_empty() && options.merge.is_empty() {
+        replays.push(None);
+    }
+    for replay in replays {
+        let cpu = run_replay(rom_path, replay, options.frames, |cpu| {
+            cpu.memory.coverage = Some(Box::default());
+        });
+        if let Some(coverage) = &cpu.memory.coverage {
+            total.merge(coverage);
+        }
+    }
+
+    match options.report {
+        Report::Map => print!("{}", total.map(rom.data.len())),
+        Report::Json => println!("{}", total.to_json(rom_path, &rom.data).pretty()),
+        Report::Listing => print!("{}", total.annotated_listing(&rom.data)),
+    }
+}
+
+fn show_sprites(options: &SpriteOptions) {
+    let rom_path = options.rom_path.as_str();
+    let mut replays = load_replays(&options.replays);
+    if replays.is_empty() {
+        replays.push(None);
+    }
+    // Memory as the first run left it, so sprites the ROM builds show too.
+    let mut memory = None;
+    let mut log = SpriteLog::default();
+    for replay in replays {
+        let cpu = run_replay(rom_path, replay, options.frames, |cpu| {
+            cpu.sprites = Some(Box::default());
+        });
+        for (&draw, &count) in cpu.sprites.iter().flat_map(|sprites| &sprites.draws) {
+            *log.draws.entry(draw).or_default() += count;
+        }
+        memory.get_or_insert(cpu.memory);
+    }
+    let Some(memory) = memory else {
+        return;
+    };
+
+    let (from, to) = options.range.unwrap_or_else(|| {
+        let rom = rom::Rom::open(rom_path, None)
+            .unwrap_or_else(|e| fail(&format!("Failed to read ROM '{}': {}", rom_path, e)));
+        let end = (memory::PROGRAM_START + rom.data.len()).min(memory::MEMORY_SIZE);
+        (memory::PROGRAM_START as u16, end.saturating_sub(1) as u16)
+    });
+    let range = sprites::slice(from, to + 1, options.width, options.height);
+    let selected = match &options.select {
+        Selection::Range => range.clone(),
+        Selection::Drawn => sprites::drawn(&log)
+            .into_iter()
+            .map(|(sprite, _)| sprite)
+            .collect(),
+        Selection::Sprites(addresses) => addresses
+            .iter()
+            .map(|&(address, height)| Sprite {
+                address,
+                width: options.width,
+                height: height.unwrap_or(options.height),
+            })
+            .collect(),
+    };
 
-    if args.len() != 2 {
-        let exe_name = std::path::Path::new(&args[0])
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or(&args[0]);
-        eprintln!("Usage: {} <rom_path>", exe_name);
+    if let Some(png_path) = &options.png_path {
+        let data = sprites::png_sheet(&selected, &memory, &log);
+        match screenshot::write_file(Path::new(png_path), &data) {
+            Ok(()) => eprintln!("Saved {} ({} sprites)", png_path, selected.len()),
+            Err(e) => fail(&format!("Failed to write '{}': {}", png_path, e)),
+        }
+    }
+    if options.db {
+        print!("{}", sprites::db_lines(&selected, &memory, &log));
+    } else if options.png_path.is_none() {
+        println!(
+            "Sprites 0x{:03X}-0x{:03X}, {}x{}; * drawn by DRW",
+            from, to, options.width, options.height
+        );
+        println!();
+        print!("{}", sprites::grid(&range, &memory, &log));
+        println!("Drawn by DRW:");
+        for (sprite, count) in sprites::drawn(&log) {
+            println!(
+                "  0x{:03X}  {:>2}x{:<2} {:>9} time(s)",
+                sprite.address, sprite.width, sprite.height, count
+            );
+        }
+    }
+}
+
+fn rom_file_name(options: &Options) -> String {
+    let rom_path = options.rom_path.as_deref().unwrap_or_default();
+    Path::new(rom_path).file_name().map_or_else(
+        || rom_path.to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+fn save_text(out_path: &str, text: &str) {
+    match fs::write(out_path, text) {
+        Ok(()) => eprintln!("Saved {} ({} lines)", out_path, text.lines().count()),
+        Err(e) => fail(&format!("Failed to write '{}': {}", out_path, e)),
+    }
+}
+
+fn recompile_rom(options: &Options, out_path: &str) {
+    let source = recompile::recompile(&rom_file_name(options), &read_rom(options).0);
+    save_text(out_path, &source);
+}
+
+fn run_lint(paths: &[String]) {
+    let mut roms = Vec::new();
+    for path in paths {
+        find_roms(path, &mut roms);
+    }
+
+    let mut warned = 0;
+    for rom_path in &roms {
+        let rom = match rom::Rom::open(rom_path, None) {
+            Ok(rom) => rom,
+            Err(e) => fail(&format!("Failed to read ROM '{}': {}", rom_path, e)),
+        };
+        let warnings = lint::lint(&rom.data);
+        for warning in &warnings {
+            println!(
+                "{}:0x{:03X}: {} [{}]",
+                rom_path, warning.address, warning.message, warning.lint
+            );
+        }
+        warned += warnings.len();
+    }
+    if warned > 0 {
+        fail(&format!("{} warnings in {} ROMs", warned, roms.len()));
+    }
+}
+
+fn write_cfg(options: &Options, out_path: &str, calls_path: Option<&str>) {
+    let name = rom_file_name(options);
+    let analysis = Analysis::new(&read_rom(options).0);
+    save_text(out_path, &dot::control_flow(&name, &analysis));
+    if let Some(calls_path) = calls_path {
+        save_text(calls_path, &dot::call_graph(&name, &analysis));
+    }
+    for addr in &analysis.computed_jumps {
+        eprintln!("Unresolved computed jump at 0x{:03X}", addr);
+    }
+}
+
+// ROM files under `path`, searching subdirectories, or `path` itself if it
+// isn't a directory.
+fn find_roms(path: &str, roms: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(path) else {
+        roms.push(path.to_string());
+        return;
+    };
+    let mut paths: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name();
+            format!("{}/{}", path.trim_end_matches('/'), name.to_string_lossy())
+        })
+        .collect();
+    paths.sort();
+    for path in paths {
+        if Path::new(&path).is_dir() {
+            find_roms(&path, roms);
+        } else if rom::is_rom_name(&path) {
+            roms.push(path);
+        }
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => panic
+            .downcast_ref::<&str>()
+            .map_or("panicked", |message| message)
+            .to_string(),
+    }
+}
+
+// Loads a ROM with its own settings, a fixed seed and the given engine.
+fn bench_cpu(rom_path: &str, engine: Engine) -> Result<cpu::Cpu, String> {
+    let settings = Options::from_args(&[rom_path.to_string()])?;
+    let rom = rom::Rom::open(rom_path, None).map_err(|e| e.to_string())?;
+
+    let mut cpu = cpu::Cpu::new();
+    cpu.memory
+        .load_program(&rom.data)
+        .map_err(|e| e.to_string())?;
+    cpu.quirks = settings.quirks;
+    cpu.cycles_per_frame = settings.cycles_per_frame();
+    cpu.engine = engine;
+    cpu.seed_rng(1);
+    Ok(cpu)
+}
+
+// Runs `f` on every ROM found under `paths`, turning a panic (an invalid
+// opcode) into an error for that ROM rather than ending the run.
+fn for_each_rom<T>(
+    paths: &[String],
+    engine: Engine,
+    mut f: impl FnMut(&str, cpu::Cpu) -> T,
+) -> Vec<(String, Result<T, String>)> {
+    let mut roms = Vec::new();
+    for path in paths {
+        find_roms(path, &mut roms);
+    }
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let results = roms
+        .into_iter()
+        .map(|rom_path| {
+            let result = bench_cpu(&rom_path, engine).and_then(|cpu| {
+                panic::catch_unwind(AssertUnwindSafe(|| f(&rom_path, cpu))).map_err(panic_message)
+            });
+            (rom_path, result)
+        })
+        .collect();
+    panic::set_hook(hook);
+    results
+}
+
+fn run_bench(options: &BenchOptions) {
+    let baseline = match &options.baseline {
+        Some(path) => {
+            let document = fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| json::parse(&text))
+                .unwrap_or_else(|e| fail(&format!("Failed to read baseline '{}': {}", path, e)));
+            bench::baseline(&document)
+        }
+        None => Default::default(),
+    };
+
+    let results: Vec<_> = for_each_rom(&options.rom_paths, options.engine, |rom_path, cpu| {
+        let result = bench::run(rom_path, cpu, options.instructions);
+        if !options.json {
+            print!("{}", result.report(baseline.get(rom_path).copied()));
+        }
+        result
+    })
+    .into_iter()
+    .map(|(rom_path, result)| {
+        result.map_err(|e| {
+            if !options.json {
+                println!("{}\n  failed: {}", rom_path, e);
+            }
+            (rom_path, e)
+        })
+    })
+    .collect();
+
+    if options.json {
+        println!("{}", bench::to_json(options, &results).pretty());
+    }
+}
+
+fn check_engine(options: &BenchOptions) {
+    let results = for_each_rom(&options.rom_paths, Engine::Interpreter, |_, cpu| {
+        let frames = options
+            .instructions
+            .div_ceil(cpu.cycles_per_frame.max(1) as u64);
+        lockstep::check(&cpu, frames)
+    });
+
+    let mut failed = 0;
+    for (rom_path, result) in results {
+        match result {
+            Ok(Ok(instructions)) => println!("ok    {} ({} instructions)", rom_path, instructions),
+            Ok(Err(difference)) => {
+                failed += 1;
+                println!("DIFF  {}: {}", rom_path, difference);
+            }
+            Err(e) => println!("skip  {}: {}", rom_path, e),
+        }
+    }
+    if failed > 0 {
+        fail(&format!(
+            "{} ROMs ran differently under the cached engine",
+            failed
+        ));
+    }
+}
+
+fn print_info(options: &Options) {
+    let rom_path = options.rom_path.as_deref().unwrap_or_default();
+    let (program, patch_path) = read_rom(options);
+    print!("{}", info::report(rom_path, &program));
+
+    match &patch_path {
+        Some(path) => println!("Patch:     {}", path),
+        None => println!("Patch:     none"),
+    }
+    match &options.config_path {
+        Some(path) => println!("Config:    {}", path.display()),
+        None => println!("Config:    none"),
+    }
+    println!(
+        "Settings:  variant {}, quirks {}, {} instructions per second",
+        options
+            .variant
+            .map(|variant| variant.name())
+            .unwrap_or("default"),
+        options.quirks.describe(),
+        options.speed
+    );
+}
+
+fn run(exe_name: &str, mut options: Options) {
+    let Some(rom_path) = &options.rom_path else {
+        eprintln!("Usage: {} [run] <rom_path> [options]", exe_name);
         eprintln!(
             "Example: {} \"chip8-roms/games/Tetris [Fran Dachille, 1991].ch8\"",
             exe_name
         );
-        list_available_roms();
+        eprintln!("Run '{} --help' for all commands and options.", exe_name);
+        list_available_roms(&mut io::stderr());
         std::process::exit(1);
+    };
+    let mut headless = std::mem::take(&mut options.headless);
+    headless.palette = options.palette;
+    headless.scale = options.scale;
+
+    let mut cpu = cpu::Cpu::new();
+
+    let rom = rom::load_rom(&mut cpu, rom_path, options.patch_path.as_deref())
+        .unwrap_or_else(|e| fail(&format!("Failed to load ROM '{}': {}", rom_path, e)));
+
+    cpu.quirks = options.quirks;
+    cpu.cycles_per_frame = options.cycles_per_frame();
+    cpu.engine = options.engine;
+    cpu.trace = options.trace;
+    if options.profile_path.is_some() {
+        cpu.profile = Some(Box::default());
+    }
+
+    if let Some(path) = &options.load_state {
+        if let Err(e) = state::load(&mut cpu, path) {
+            fail(&format!("Failed to load state '{}': {}", path, e));
+        }
+    }
+
+    if let Some(path) = &options.replay_path {
+        match replay::InputLog::load(path) {
+            Ok(log) => {
+                log.apply_settings(&mut cpu);
+                headless.replay = Some(log);
+            }
+            Err(e) => fail(&format!("Failed to load input log '{}': {}", path, e)),
+        }
     }
 
-    let rom_path = &args[1];
+    let seed = options
+        .seed
+        .or(headless.replay.as_ref().map(|log| log.seed))
+        .unwrap_or_else(rand::random);
+    cpu.seed_rng(seed);
+
+    if headless.is_requested() {
+        match headless::run(cpu, rom_path, headless, options.buzzer) {
+            Ok(cpu) => {
+                save_state(&cpu, &options.save_state);
+                save_profile(&cpu, &options.profile_path);
+            }
+            Err(e) => fail(&format!("Headless run failed: {}", e)),
+        }
+        return;
+    }
+
+    let patched = rom.patch.is_some();
+    let rom_path = rom_path.clone();
+    run_window(cpu, &rom_path, patched, seed, options, headless);
+}
 
-    let (mut rl, thread) = raylib::init()
-        .size(ui::WINDOW_WIDTH, ui::WINDOW_HEIGHT)
+#[cfg(not(feature = "gui"))]
+fn run_window(
+    _cpu: cpu::Cpu,
+    _rom_path: &str,
+    _patched: bool,
+    _seed: u64,
+    _options: Options,
+    _headless: headless::HeadlessRun,
+) {
+    fail("Built without the window (the `gui` feature); use the headless options or chip8-tui.");
+}
+
+#[cfg(feature = "gui")]
+fn run_window(
+    cpu: cpu::Cpu,
+    rom_path: &str,
+    patched: bool,
+    seed: u64,
+    options: Options,
+    mut headless: headless::HeadlessRun,
+) {
+    let (width, height) = layout::window_size(options.scale as i32);
+    let mut builder = raylib::init();
+    builder
+        .size(width, height)
         .title("CHIP-8 Emulator")
-        .build();
+        .resizable();
+    if options.fullscreen {
+        builder.fullscreen();
+    }
+    let (mut rl, thread) = builder.build();
 
     rl.set_target_fps(60);
 
-    let mut cpu = cpu::Cpu::new();
-
-    let mut ui = ui::UI::new();
+    let rl = Rc::new(RefCell::new(rl));
 
     let core = core::Core::new().expect("Failed to initialize core systems");
 
-    let beep_sound = audio::create_beep_sound(&core);
-
-    if let Err(e) = rom::load_rom(&mut cpu, rom_path) {
-        eprintln!("Failed to load ROM '{}': {}", rom_path, e);
-        std::process::exit(1);
+    let mut stream = audio::BuzzerStream::new(&core);
+    if headless.wav_path.is_some() {
+        stream.recorder = Some(audio::WavRecorder::new());
     }
 
-    const CYCLES_PER_FRAME: u32 = 15;
+    let video = raylib_frontend::WindowVideo::new(rl.clone(), thread)
+        .unwrap_or_else(|e| fail(&format!("Failed to create the screen texture: {}", e)));
+    let audio = raylib_frontend::StreamAudio {
+        stream,
+        buzzer: options.buzzer,
+    };
+    let mut input = raylib_frontend::WindowInput::new(rl);
+    input.keymap = options.keymap;
+
+    let mut emulator = frontend::Emulator::new(cpu, rom_path, video, audio, input);
+    emulator.palette = options.palette;
+    emulator.patched = patched;
+    emulator.scale = options.scale;
+    emulator.scaling = options.scaling;
+    emulator.grid = options.grid;
+    emulator.gif_scale = headless.gif_scale;
+    emulator.gif_skip = headless.gif_skip;
+    emulator.machine.replay = headless.replay.take();
+    let cpu = &emulator.machine.cpu;
+    emulator.machine.input_log = options
+        .record_input_path
+        .as_ref()
+        .map(|_| replay::InputLog::for_cpu(seed, cpu));
 
-    while !rl.window_should_close() {
-        cpu.keyboard.update(&rl);
+    emulator.state_path = options.load_state.clone();
+    emulator.threaded = true;
+    emulator.run();
 
-        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
-            ui.paused = !ui.paused;
+    if let (Some(recorder), Some(path)) = (&emulator.audio.stream.recorder, &headless.wav_path) {
+        match screenshot::write_file(std::path::Path::new(path), &recorder.finish()) {
+            Ok(()) => eprintln!("Saved audio: {}", path),
+            Err(e) => eprintln!("Failed to save audio '{}': {}", path, e),
         }
-        if rl.is_key_pressed(KeyboardKey::KEY_TAB) {
-            ui.step_mode = !ui.step_mode;
+    }
+
+    if let (Some(log), Some(path)) = (&emulator.machine.input_log, &options.record_input_path) {
+        match log.save(path) {
+            Ok(()) => eprintln!("Saved input log: {}", path),
+            Err(e) => eprintln!("Failed to save input log '{}': {}", path, e),
         }
+    }
 
-        if !ui.paused {
-            if ui.step_mode {
-                if rl.is_key_pressed(KeyboardKey::KEY_S) {
-                    cpu.cycle();
-                }
-            } else {
-                for _ in 0..CYCLES_PER_FRAME {
-                    cpu.cycle();
-                }
-                cpu.tick_timers();
-            }
+    save_state(&emulator.machine.cpu, &options.save_state);
+    save_profile(&emulator.machine.cpu, &options.profile_path);
+}
+
+fn save_state(cpu: &cpu::Cpu, path: &Option<String>) {
+    if let Some(path) = path {
+        match state::save(cpu, path) {
+            Ok(()) => eprintln!("Saved state: {}", path),
+            Err(e) => eprintln!("Failed to save state '{}': {}", path, e),
         }
+    }
+}
 
-        if cpu.sound_timer > 0 {
-            if let Some(ref sound) = beep_sound {
-                if !sound.is_playing() {
-                    sound.play();
-                }
-            }
+fn save_profile(cpu: &cpu::Cpu, path: &Option<String>) {
+    if let (Some(profile), Some(path)) = (&cpu.profile, path) {
+        match fs::write(path, profile.report(&cpu.memory)) {
+            Ok(()) => eprintln!("Saved profile: {}", path),
+            Err(e) => eprintln!("Failed to save profile '{}': {}", path, e),
         }
+    }
+}
 
-        let mut d = rl.begin_drawing(&thread);
-        d.clear_background(Color::BLACK);
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let exe_name = std::path::Path::new(&args[0])
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&args[0]);
 
-        display::draw(&mut d, &cpu.display);
+    let command = cli::parse(&args[1..]).unwrap_or_else(|e| {
+        fail(&format!(
+            "{}\nRun '{} --help' for all commands and options.",
+            e, exe_name
+        ))
+    });
 
-        ui.render_status_bar(&mut d, &cpu, rom_path);
+    match command {
+        Command::Help => print!("{}", cli::usage(exe_name)),
+        Command::List => list_available_roms(&mut io::stdout()),
+        Command::Info(options) => print_info(&options),
+        Command::Disasm(options) => {
+            print!("{}", disasm::listing(&read_rom(&options).0));
+        }
+        Command::Bench(options) => run_bench(&options),
+        Command::CheckEngine(options) => check_engine(&options),
+        Command::Coverage(options) => run_coverage(&options),
+        Command::Sprites(options) => show_sprites(&options),
+        Command::Lint(paths) => run_lint(&paths),
+        Command::Cfg {
+            options,
+            out_path,
+            calls_path,
+        } => write_cfg(&options, &out_path, calls_path.as_deref()),
+        Command::Recompile { options, out_path } => recompile_rom(&options, &out_path),
+        Command::MakeIps {
+            original,
+            modified,
+            out_path,
+        } => make_ips(&original, &modified, &out_path),
+        Command::Run(options) => run(exe_name, options),
     }
 }
@@ -1,44 +1,101 @@
-use std::fs::File;
-use std::io::Read;
+use crate::coverage::Coverage;
+use crate::disasm::{self, Instruction};
+use crate::rom::RomError;
+
+pub const MEMORY_SIZE: usize = 4096;
+pub const PROGRAM_START: usize = 0x200;
 
 pub struct Memory {
-    ram: [u8; 4096],
+    ram: [u8; MEMORY_SIZE],
+    // Instructions decoded by the cached engine, by address. Any write to
+    // either byte of an instruction drops it, so self-modifying code is seen.
+    decoded: Vec<Option<Instruction>>,
+    // Records how each byte is used when set.
+    pub coverage: Option<Box<Coverage>>,
+}
+
+// `clone_from` copies into the existing buffers; see `Cpu`'s `Clone`.
+impl Clone for Memory {
+    fn clone(&self) -> Self {
+        Self {
+            ram: self.ram,
+            decoded: self.decoded.clone(),
+            coverage: self.coverage.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.ram = source.ram;
+        self.decoded.clone_from(&source.decoded);
+        self.coverage.clone_from(&source.coverage);
+    }
 }
 
 impl Memory {
     pub fn new() -> Self {
-        Self { ram: [0; 4096] }
+        Self {
+            ram: [0; MEMORY_SIZE],
+            decoded: vec![None; MEMORY_SIZE],
+            coverage: None,
+        }
+    }
+
+    // Drops cached instructions overlapping `start..end`.
+    fn invalidate(&mut self, start: usize, end: usize) {
+        let start = start.saturating_sub(1);
+        for entry in &mut self.decoded[start..end.min(MEMORY_SIZE)] {
+            *entry = None;
+        }
+    }
+
+    pub fn instruction(&mut self, address: u16) -> Instruction {
+        let address = address as usize;
+        if address + 1 >= MEMORY_SIZE {
+            return disasm::decode((self.read(address as u16) as u16) << 8);
+        }
+        *self.decoded[address].get_or_insert_with(|| {
+            disasm::decode((self.ram[address] as u16) << 8 | self.ram[address + 1] as u16)
+        })
     }
 
     pub fn read(&self, address: u16) -> u8 {
-        if address < 4096 {
+        if (address as usize) < MEMORY_SIZE {
             self.ram[address as usize]
         } else {
             0
         }
     }
 
-    pub fn write(&mut self, address: u16, value: u8) {
-        if address < 4096 {
-            self.ram[address as usize] = value;
-        }
+    // Up to `len` bytes from `address`, fewer at the end of memory.
+    pub fn bytes(&self, address: u16, len: usize) -> &[u8] {
+        let start = (address as usize).min(MEMORY_SIZE);
+        &self.ram[start..(start + len).min(MEMORY_SIZE)]
     }
 
-    pub fn load_program(&mut self, program: &[u8]) {
-        let start_address = 0x200;
-        for (i, &byte) in program.iter().enumerate() {
-            if start_address + i < 4096 {
-                self.ram[start_address + i] = byte;
-            }
+    // Notes `len` bytes from `address` as used the given `coverage` way.
+    pub fn mark(&mut self, address: u16, len: usize, kind: u8) {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.mark(address, len, kind);
         }
     }
 
-    pub fn load_rom(&mut self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let mut file = File::open(filename)?;
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
+    pub fn write(&mut self, address: u16, value: u8) {
+        if (address as usize) < MEMORY_SIZE {
+            self.ram[address as usize] = value;
+            self.invalidate(address as usize, address as usize + 1);
+        }
+    }
 
-        self.load_program(&buffer);
+    pub fn load_program(&mut self, program: &[u8]) -> Result<(), RomError> {
+        let end = PROGRAM_START + program.len();
+        if end > MEMORY_SIZE {
+            return Err(RomError::TooLarge {
+                size: program.len(),
+                capacity: MEMORY_SIZE - PROGRAM_START,
+            });
+        }
+        self.ram[PROGRAM_START..end].copy_from_slice(program);
+        self.invalidate(PROGRAM_START, end);
         Ok(())
     }
 
@@ -55,9 +112,17 @@ impl Memory {
         for (i, &byte) in fontset.iter().enumerate() {
             self.ram[i] = byte;
         }
+        self.invalidate(0, fontset.len());
     }
 
     pub fn clear(&mut self) {
-        self.ram = [0; 4096];
+        self.ram = [0; MEMORY_SIZE];
+        self.decoded.fill(None);
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -0,0 +1,990 @@
+use crate::memory::PROGRAM_START;
+use std::collections::HashMap;
+
+// An assembler for Octo, the CHIP-8 assembly language Octo cartridges carry
+// their source in. It covers the language as documented in the Octo manual:
+// labels, `:const`, `:alias`, `:org`, `:byte`, `:next`, `:unpack`, `:macro`,
+// `:calc` and inline `{ }` expressions, the structured `if`/`loop` forms and
+// the CHIP-8, SUPER-CHIP and XO-CHIP statements. `:stringmode` is not
+// supported.
+pub fn assemble(source: &str) -> Result<Vec<u8>, String> {
+    let mut assembler = Assembler::new(tokenize(source)?);
+    while !assembler.tokens.is_empty() {
+        assembler.statement()?;
+    }
+    assembler.finish()
+}
+
+#[derive(Clone)]
+struct Token {
+    text: String,
+    line: usize,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    for (line_number, line) in source.lines().enumerate() {
+        let line_number = line_number + 1;
+        let mut rest = line;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() || rest.starts_with('#') {
+                break;
+            }
+            let len = if let Some(quoted) = rest.strip_prefix('"') {
+                let close = quoted
+                    .find('"')
+                    .ok_or_else(|| format!("line {}: unterminated string", line_number))?;
+                close + 2
+            } else {
+                rest.find(char::is_whitespace).unwrap_or(rest.len())
+            };
+            tokens.push(Token {
+                text: rest[..len].to_string(),
+                line: line_number,
+            });
+            rest = &rest[len..];
+        }
+    }
+    Ok(tokens)
+}
+
+// How a forward reference is filled in once its label is defined.
+#[derive(Clone, Copy)]
+enum Patch {
+    // The low 12 bits of an instruction.
+    Address,
+    // A 16-bit big-endian word, for `i := long` and `:pointer`.
+    Long,
+    // `:unpack n label`: `v0 := n << 4 | label >> 8` and `v1 := label`.
+    Unpack,
+    // `:unpack long label`: `v0 := label >> 8` and `v1 := label`.
+    UnpackLong,
+}
+
+enum Flow {
+    // The address of the jump over the `begin` block, or over the `else`
+    // block once `else` has been seen.
+    If(usize),
+    // The start of the loop and the exit jumps of its `while`s.
+    Loop(usize, Vec<usize>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    Key,
+    NotKey,
+}
+
+impl Comparison {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "==" => Some(Self::Equal),
+            "!=" => Some(Self::NotEqual),
+            "<" => Some(Self::Less),
+            ">" => Some(Self::Greater),
+            "<=" => Some(Self::LessEqual),
+            ">=" => Some(Self::GreaterEqual),
+            "key" => Some(Self::Key),
+            "-key" => Some(Self::NotKey),
+            _ => None,
+        }
+    }
+
+    fn negate(self) -> Self {
+        match self {
+            Self::Equal => Self::NotEqual,
+            Self::NotEqual => Self::Equal,
+            Self::Less => Self::GreaterEqual,
+            Self::GreaterEqual => Self::Less,
+            Self::Greater => Self::LessEqual,
+            Self::LessEqual => Self::Greater,
+            Self::Key => Self::NotKey,
+            Self::NotKey => Self::Key,
+        }
+    }
+}
+
+struct Condition {
+    x: u16,
+    comparison: Comparison,
+    operand: Option<Operand>,
+}
+
+#[derive(Clone, Copy)]
+enum Operand {
+    Register(u8),
+    Number(i64),
+}
+
+struct Assembler {
+    // Pending tokens, last one first, so macro bodies can be pushed back.
+    tokens: Vec<Token>,
+    line: usize,
+    memory: Vec<u8>,
+    here: usize,
+    end: usize,
+    labels: HashMap<String, usize>,
+    constants: HashMap<String, i64>,
+    aliases: HashMap<String, u8>,
+    macros: HashMap<String, (Vec<String>, Vec<Token>)>,
+    patches: Vec<(usize, Patch, String, usize)>,
+    flow: Vec<(Flow, usize)>,
+    expansions: usize,
+    // Octo reserves 0x200 for a jump to `main`, unless `main` comes first.
+    jump_to_main: bool,
+}
+
+impl Assembler {
+    fn new(mut tokens: Vec<Token>) -> Self {
+        tokens.reverse();
+        Self {
+            tokens,
+            line: 0,
+            memory: vec![0; 0x10000],
+            here: PROGRAM_START + 2,
+            end: PROGRAM_START + 2,
+            labels: HashMap::new(),
+            constants: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            patches: Vec::new(),
+            flow: Vec::new(),
+            expansions: 0,
+            jump_to_main: true,
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("line {}: {}", self.line, message)
+    }
+
+    fn next(&mut self) -> Result<String, String> {
+        let token = self
+            .tokens
+            .pop()
+            .ok_or_else(|| self.error("unexpected end of program"))?;
+        self.line = token.line;
+        Ok(token.text)
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.last().map(|token| token.text.as_str())
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), String> {
+        let token = self.next()?;
+        if token == text {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}', found '{}'", text, token)))
+        }
+    }
+
+    fn byte(&mut self, value: u8) -> Result<(), String> {
+        if self.here >= self.memory.len() {
+            return Err(self.error("program doesn't fit in 64 KB"));
+        }
+        self.memory[self.here] = value;
+        self.here += 1;
+        self.end = self.end.max(self.here);
+        Ok(())
+    }
+
+    fn instruction(&mut self, opcode: u16) -> Result<(), String> {
+        self.byte((opcode >> 8) as u8)?;
+        self.byte(opcode as u8)
+    }
+
+    fn patch(&mut self, at: usize, kind: Patch, address: usize) {
+        match kind {
+            Patch::Address => {
+                self.memory[at] = (self.memory[at] & 0xF0) | ((address >> 8) & 0xF) as u8;
+                self.memory[at + 1] = address as u8;
+            }
+            Patch::Long => {
+                self.memory[at] = (address >> 8) as u8;
+                self.memory[at + 1] = address as u8;
+            }
+            Patch::Unpack => {
+                self.memory[at + 1] |= ((address >> 8) & 0xF) as u8;
+                self.memory[at + 3] = address as u8;
+            }
+            Patch::UnpackLong => {
+                self.memory[at + 1] = (address >> 8) as u8;
+                self.memory[at + 3] = address as u8;
+            }
+        }
+    }
+
+    // Fills in `kind` at `at` now if the address is known, or once the label
+    // is defined.
+    fn reference(&mut self, at: usize, kind: Patch, name: String) -> Result<(), String> {
+        match self.lookup(&name) {
+            Some(address) => self.patch(at, kind, address as usize),
+            None if is_name(&name) => self.patches.push((at, kind, name, self.line)),
+            None => return Err(self.error(&format!("'{}' is not a number or name", name))),
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str) -> Option<i64> {
+        parse_number(name)
+            .or_else(|| self.constants.get(name).copied())
+            .or_else(|| self.labels.get(name).map(|&address| address as i64))
+    }
+
+    fn register(&self, text: &str) -> Option<u8> {
+        if let Some(&register) = self.aliases.get(text) {
+            return Some(register);
+        }
+        let digit = text.strip_prefix(['v', 'V'])?;
+        if digit.len() == 1 {
+            u8::from_str_radix(digit, 16).ok()
+        } else {
+            None
+        }
+    }
+
+    fn expect_register(&mut self) -> Result<u8, String> {
+        let token = self.next()?;
+        self.register(&token)
+            .ok_or_else(|| self.error(&format!("expected a register, found '{}'", token)))
+    }
+
+    // A number, constant, label or `{ }` expression, all of which must be
+    // known by now.
+    fn value(&mut self) -> Result<i64, String> {
+        let token = self.next()?;
+        if token == "{" {
+            return self.calc();
+        }
+        self.lookup(&token)
+            .ok_or_else(|| self.error(&format!("unknown value '{}'", token)))
+    }
+
+    fn value_in(
+        &mut self,
+        range: std::ops::RangeInclusive<i64>,
+        what: &str,
+    ) -> Result<i64, String> {
+        let value = self.value()?;
+        if range.contains(&value) {
+            Ok(value)
+        } else {
+            Err(self.error(&format!("{} is out of range for {}", value, what)))
+        }
+    }
+
+    fn immediate(&mut self) -> Result<u8, String> {
+        Ok(self.value_in(-128..=255, "a byte")? as u8)
+    }
+
+    // An address operand; labels may be defined later.
+    fn address(&mut self, opcode: u16) -> Result<(), String> {
+        let at = self.here;
+        self.instruction(opcode)?;
+        let token = self.next()?;
+        if token == "{" {
+            let address = self.calc()?;
+            self.patch(at, Patch::Address, address as usize);
+            return Ok(());
+        }
+        self.reference(at, Patch::Address, token)
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.peek().and_then(|token| self.register(token)) {
+            Some(register) => {
+                self.next()?;
+                Ok(Operand::Register(register))
+            }
+            None => Ok(Operand::Number(self.immediate()? as i64)),
+        }
+    }
+
+    // Tokens up to the matching `}`, the `{` having been read already.
+    fn braced(&mut self) -> Result<Vec<Token>, String> {
+        let mut body = Vec::new();
+        let mut depth = 1;
+        loop {
+            let token = self.tokens.pop().ok_or_else(|| self.error("missing '}'"))?;
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(body);
+                    }
+                }
+                _ => {}
+            }
+            body.push(token);
+        }
+    }
+
+    fn calc(&mut self) -> Result<i64, String> {
+        let body = self.braced()?;
+        let tokens: Vec<&str> = body.iter().map(|token| token.text.as_str()).collect();
+        let mut pos = 0;
+        let value = self.expression(&tokens, &mut pos)?;
+        if pos != tokens.len() {
+            return Err(self.error(&format!("unexpected '{}' in expression", tokens[pos])));
+        }
+        Ok(value.floor() as i64)
+    }
+
+    // Octo evaluates expressions right to left without precedence, so
+    // `2 * 3 + 1` is 8.
+    fn expression(&self, tokens: &[&str], pos: &mut usize) -> Result<f64, String> {
+        let left = self.term(tokens, pos)?;
+        let Some(&op) = tokens.get(*pos) else {
+            return Ok(left);
+        };
+        if op == ")" {
+            return Ok(left);
+        }
+        *pos += 1;
+        let right = self.expression(tokens, pos)?;
+        let truth = |b: bool| if b { 1.0 } else { 0.0 };
+        let int = |v: f64| v as i64;
+        Ok(match op {
+            "+" => left + right,
+            "-" => left - right,
+            "*" => left * right,
+            "/" => left / right,
+            "%" => left % right,
+            "&" => (int(left) & int(right)) as f64,
+            "|" => (int(left) | int(right)) as f64,
+            "^" => (int(left) ^ int(right)) as f64,
+            "<<" => (int(left) << int(right)) as f64,
+            ">>" => (int(left) >> int(right)) as f64,
+            "pow" => left.powf(right),
+            "min" => left.min(right),
+            "max" => left.max(right),
+            "<" => truth(left < right),
+            ">" => truth(left > right),
+            "<=" => truth(left <= right),
+            ">=" => truth(left >= right),
+            "==" => truth(left == right),
+            "!=" => truth(left != right),
+            _ => return Err(self.error(&format!("unknown operator '{}'", op))),
+        })
+    }
+
+    fn term(&self, tokens: &[&str], pos: &mut usize) -> Result<f64, String> {
+        let token = *tokens
+            .get(*pos)
+            .ok_or_else(|| self.error("incomplete expression"))?;
+        *pos += 1;
+        let unary = |f: fn(f64) -> f64, pos: &mut usize| -> Result<f64, String> {
+            Ok(f(self.term(tokens, pos)?))
+        };
+        match token {
+            "(" => {
+                let value = self.expression(tokens, pos)?;
+                if tokens.get(*pos) != Some(&")") {
+                    return Err(self.error("missing ')'"));
+                }
+                *pos += 1;
+                Ok(value)
+            }
+            "-" => unary(|v| -v, pos),
+            "~" => unary(|v| !(v as i64) as f64, pos),
+            "!" => unary(|v| if v == 0.0 { 1.0 } else { 0.0 }, pos),
+            "sin" => unary(f64::sin, pos),
+            "cos" => unary(f64::cos, pos),
+            "tan" => unary(f64::tan, pos),
+            "exp" => unary(f64::exp, pos),
+            "log" => unary(f64::ln, pos),
+            "abs" => unary(f64::abs, pos),
+            "sqrt" => unary(f64::sqrt, pos),
+            "sign" => unary(f64::signum, pos),
+            "ceil" => unary(f64::ceil, pos),
+            "floor" => unary(f64::floor, pos),
+            "@" => {
+                let address = self.term(tokens, pos)? as usize;
+                Ok(self.memory.get(address).copied().unwrap_or(0) as f64)
+            }
+            "PI" => Ok(std::f64::consts::PI),
+            "E" => Ok(std::f64::consts::E),
+            "HERE" => Ok(self.here as f64),
+            _ => {
+                if let Ok(value) = token.parse::<f64>() {
+                    return Ok(value);
+                }
+                self.lookup(token)
+                    .map(|value| value as f64)
+                    .ok_or_else(|| self.error(&format!("unknown value '{}'", token)))
+            }
+        }
+    }
+
+    fn define_label(&mut self, name: String) -> Result<(), String> {
+        if !is_name(&name) || self.register(&name).is_some() {
+            return Err(self.error(&format!("'{}' can't be used as a label", name)));
+        }
+        if name == "main" && self.jump_to_main && self.here == PROGRAM_START + 2 {
+            self.jump_to_main = false;
+            self.here = PROGRAM_START;
+        }
+        if self.labels.insert(name.clone(), self.here).is_some() {
+            return Err(self.error(&format!("label '{}' is defined twice", name)));
+        }
+        Ok(())
+    }
+
+    fn condition(&mut self) -> Result<Condition, String> {
+        let x = self.expect_register()? as u16;
+        let token = self.next()?;
+        let comparison = Comparison::parse(&token)
+            .ok_or_else(|| self.error(&format!("unknown comparison '{}'", token)))?;
+        let operand = match comparison {
+            Comparison::Key | Comparison::NotKey => None,
+            _ => Some(self.operand()?),
+        };
+        Ok(Condition {
+            x,
+            comparison,
+            operand,
+        })
+    }
+
+    // Instructions that skip the next one when the condition is false, or
+    // when it is true if `negate` is set.
+    fn skip_unless(&mut self, condition: Condition, negate: bool) -> Result<(), String> {
+        let x = condition.x;
+        let comparison = if negate {
+            condition.comparison.negate()
+        } else {
+            condition.comparison
+        };
+        let operand = match (comparison, condition.operand) {
+            (Comparison::Key, _) => return self.instruction(0xE0A1 | x << 8),
+            (Comparison::NotKey, _) => return self.instruction(0xE09E | x << 8),
+            (_, Some(operand)) => operand,
+            (_, None) => return Err(self.error("comparison needs a value")),
+        };
+        match (comparison, operand) {
+            (Comparison::Equal, Operand::Number(n)) => self.instruction(0x4000 | x << 8 | n as u16),
+            (Comparison::Equal, Operand::Register(y)) => {
+                self.instruction(0x9000 | x << 8 | (y as u16) << 4)
+            }
+            (Comparison::NotEqual, Operand::Number(n)) => {
+                self.instruction(0x3000 | x << 8 | n as u16)
+            }
+            (Comparison::NotEqual, Operand::Register(y)) => {
+                self.instruction(0x5000 | x << 8 | (y as u16) << 4)
+            }
+            // Ordered comparisons subtract through VF and test the borrow.
+            (comparison, operand) => {
+                match operand {
+                    Operand::Register(y) => self.instruction(0x8F00 | (y as u16) << 4)?,
+                    Operand::Number(n) => self.instruction(0x6F00 | n as u16)?,
+                }
+                match comparison {
+                    // VF := VX - VF, so VF is 1 when VX >= operand.
+                    Comparison::Less | Comparison::GreaterEqual => {
+                        self.instruction(0x8F07 | x << 4)?
+                    }
+                    // VF := VF - VX, so VF is 1 when operand >= VX.
+                    _ => self.instruction(0x8F05 | x << 4)?,
+                }
+                match comparison {
+                    Comparison::Less | Comparison::Greater => self.instruction(0x3F01),
+                    _ => self.instruction(0x4F01),
+                }
+            }
+        }
+    }
+
+    fn register_statement(&mut self, x: u8) -> Result<(), String> {
+        let x = x as u16;
+        let op = self.next()?;
+        let alu = |y: u8, n: u16| 0x8000 | x << 8 | (y as u16) << 4 | n;
+        match op.as_str() {
+            ":=" => match self.peek() {
+                Some("delay") => {
+                    self.next()?;
+                    self.instruction(0xF007 | x << 8)
+                }
+                Some("key") => {
+                    self.next()?;
+                    self.instruction(0xF00A | x << 8)
+                }
+                Some("random") => {
+                    self.next()?;
+                    let mask = self.immediate()? as u16;
+                    self.instruction(0xC000 | x << 8 | mask)
+                }
+                _ => match self.operand()? {
+                    Operand::Register(y) => self.instruction(alu(y, 0)),
+                    Operand::Number(n) => self.instruction(0x6000 | x << 8 | n as u16),
+                },
+            },
+            "+=" => match self.operand()? {
+                Operand::Register(y) => self.instruction(alu(y, 4)),
+                Operand::Number(n) => self.instruction(0x7000 | x << 8 | n as u16),
+            },
+            "-=" => match self.operand()? {
+                Operand::Register(y) => self.instruction(alu(y, 5)),
+                Operand::Number(n) => {
+                    self.instruction(0x7000 | x << 8 | (n as u8).wrapping_neg() as u16)
+                }
+            },
+            "=-" | "|=" | "&=" | "^=" | ">>=" | "<<=" => {
+                let y = self.expect_register()?;
+                let n = match op.as_str() {
+                    "=-" => 7,
+                    "|=" => 1,
+                    "&=" => 2,
+                    "^=" => 3,
+                    ">>=" => 6,
+                    _ => 0xE,
+                };
+                self.instruction(alu(y, n))
+            }
+            _ => Err(self.error(&format!("unknown operator '{}'", op))),
+        }
+    }
+
+    fn index_statement(&mut self) -> Result<(), String> {
+        let op = self.next()?;
+        match op.as_str() {
+            ":=" => match self.peek() {
+                Some("hex") => {
+                    self.next()?;
+                    let x = self.expect_register()? as u16;
+                    self.instruction(0xF029 | x << 8)
+                }
+                Some("bighex") => {
+                    self.next()?;
+                    let x = self.expect_register()? as u16;
+                    self.instruction(0xF030 | x << 8)
+                }
+                Some("long") => {
+                    self.next()?;
+                    self.instruction(0xF000)?;
+                    let at = self.here;
+                    self.instruction(0)?;
+                    let token = self.next()?;
+                    if token == "{" {
+                        let address = self.calc()?;
+                        self.patch(at, Patch::Long, address as usize);
+                        Ok(())
+                    } else {
+                        self.reference(at, Patch::Long, token)
+                    }
+                }
+                _ => self.address(0xA000),
+            },
+            "+=" => {
+                let x = self.expect_register()? as u16;
+                self.instruction(0xF01E | x << 8)
+            }
+            _ => Err(self.error(&format!("unknown operator '{}' for i", op))),
+        }
+    }
+
+    fn register_op(&mut self, opcode: u16) -> Result<(), String> {
+        let x = self.expect_register()? as u16;
+        self.instruction(opcode | x << 8)
+    }
+
+    // `save vx`, or XO-CHIP's `save vx - vy`.
+    fn save_load(&mut self, single: u16, range: u16) -> Result<(), String> {
+        let x = self.expect_register()? as u16;
+        if self.peek() == Some("-") {
+            self.next()?;
+            let y = self.expect_register()? as u16;
+            return self.instruction(range | x << 8 | y << 4);
+        }
+        self.instruction(single | x << 8)
+    }
+
+    fn directive(&mut self, token: &str) -> Result<(), String> {
+        match token {
+            ":" => {
+                let name = self.next()?;
+                self.define_label(name)
+            }
+            ":const" => {
+                let name = self.next()?;
+                let value = self.value()?;
+                self.constants.insert(name, value);
+                Ok(())
+            }
+            ":calc" => {
+                let name = self.next()?;
+                self.expect("{")?;
+                let value = self.calc()?;
+                self.constants.insert(name, value);
+                Ok(())
+            }
+            ":alias" => {
+                let name = self.next()?;
+                let register = self.expect_register()?;
+                self.aliases.insert(name, register);
+                Ok(())
+            }
+            ":org" => {
+                self.here = self.value_in(0..=0xFFFF, "an address")? as usize;
+                Ok(())
+            }
+            ":byte" => {
+                let value = self.immediate()?;
+                self.byte(value)
+            }
+            ":pointer" => {
+                let at = self.here;
+                self.instruction(0)?;
+                let token = self.next()?;
+                self.reference(at, Patch::Long, token)
+            }
+            ":call" => self.address(0x2000),
+            ":next" => {
+                let name = self.next()?;
+                self.labels.insert(name, self.here + 1);
+                Ok(())
+            }
+            ":unpack" => {
+                let (kind, high) = if self.peek() == Some("long") {
+                    self.next()?;
+                    (Patch::UnpackLong, 0)
+                } else {
+                    (Patch::Unpack, self.value_in(0..=15, "a nybble")? as u16)
+                };
+                let at = self.here;
+                self.instruction(0x6000 | high << 4)?;
+                self.instruction(0x6100)?;
+                let token = self.next()?;
+                self.reference(at, kind, token)
+            }
+            ":macro" => {
+                let name = self.next()?;
+                let mut args = Vec::new();
+                loop {
+                    let token = self.next()?;
+                    if token == "{" {
+                        break;
+                    }
+                    args.push(token);
+                }
+                let body = self.braced()?;
+                self.macros.insert(name, (args, body));
+                Ok(())
+            }
+            ":breakpoint" => self.next().map(|_| ()),
+            ":monitor" => {
+                self.next()?;
+                self.next().map(|_| ())
+            }
+            ":assert" => {
+                let message = match self.peek() {
+                    Some(text) if text.starts_with('"') => self.next()?,
+                    _ => "assertion failed".to_string(),
+                };
+                self.expect("{")?;
+                if self.calc()? == 0 {
+                    return Err(self.error(message.trim_matches('"')));
+                }
+                Ok(())
+            }
+            _ => Err(self.error(&format!("unsupported directive '{}'", token))),
+        }
+    }
+
+    fn expand_macro(&mut self, name: &str) -> Result<(), String> {
+        self.expansions += 1;
+        if self.expansions > 100_000 {
+            return Err(self.error("too many macro expansions, is a macro recursive?"));
+        }
+        let (params, body) = self.macros[name].clone();
+        let mut args = HashMap::new();
+        for param in params {
+            let arg = self.next()?;
+            args.insert(param, arg);
+        }
+        for token in body.into_iter().rev() {
+            let text = args.get(&token.text).cloned().unwrap_or(token.text);
+            self.tokens.push(Token {
+                text,
+                line: token.line,
+            });
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), String> {
+        let token = self.next()?;
+        if token.starts_with(':') {
+            return self.directive(&token);
+        }
+        if let Some(x) = self.register(&token) {
+            return self.register_statement(x);
+        }
+        match token.as_str() {
+            "clear" => self.instruction(0x00E0),
+            "return" | ";" => self.instruction(0x00EE),
+            "exit" => self.instruction(0x00FD),
+            "lores" => self.instruction(0x00FE),
+            "hires" => self.instruction(0x00FF),
+            "scroll-down" => {
+                let n = self.value_in(0..=15, "a scroll amount")? as u16;
+                self.instruction(0x00C0 | n)
+            }
+            "scroll-up" => {
+                let n = self.value_in(0..=15, "a scroll amount")? as u16;
+                self.instruction(0x00D0 | n)
+            }
+            "scroll-right" => self.instruction(0x00FB),
+            "scroll-left" => self.instruction(0x00FC),
+            "audio" => self.instruction(0xF002),
+            "plane" => {
+                let n = self.value_in(0..=15, "a plane mask")? as u16;
+                self.instruction(0xF001 | n << 8)
+            }
+            "jump" => self.address(0x1000),
+            "jump0" => self.address(0xB000),
+            "native" => self.address(0x0000),
+            "sprite" => {
+                let x = self.expect_register()? as u16;
+                let y = self.expect_register()? as u16;
+                let n = self.value_in(0..=15, "a sprite height")? as u16;
+                self.instruction(0xD000 | x << 8 | y << 4 | n)
+            }
+            "bcd" => self.register_op(0xF033),
+            "saveflags" => self.register_op(0xF075),
+            "loadflags" => self.register_op(0xF085),
+            "delay" | "buzzer" | "pitch" => {
+                self.expect(":=")?;
+                self.register_op(match token.as_str() {
+                    "delay" => 0xF015,
+                    "buzzer" => 0xF018,
+                    _ => 0xF03A,
+                })
+            }
+            "save" => self.save_load(0xF055, 0x5002),
+            "load" => self.save_load(0xF065, 0x5003),
+            "i" => self.index_statement(),
+            "if" => {
+                let condition = self.condition()?;
+                match self.next()?.as_str() {
+                    "then" => self.skip_unless(condition, false),
+                    // Skip the jump over the block when the condition holds.
+                    "begin" => {
+                        self.skip_unless(condition, true)?;
+                        let at = self.here;
+                        self.instruction(0x1000)?;
+                        self.flow.push((Flow::If(at), self.line));
+                        Ok(())
+                    }
+                    other => {
+                        Err(self.error(&format!("expected 'then' or 'begin', found '{}'", other)))
+                    }
+                }
+            }
+            "else" => match self.flow.pop() {
+                Some((Flow::If(at), line)) => {
+                    let jump = self.here;
+                    self.instruction(0x1000)?;
+                    self.patch(at, Patch::Address, self.here);
+                    self.flow.push((Flow::If(jump), line));
+                    Ok(())
+                }
+                _ => Err(self.error("'else' without 'if ... begin'")),
+            },
+            "end" => match self.flow.pop() {
+                Some((Flow::If(at), _)) => {
+                    self.patch(at, Patch::Address, self.here);
+                    Ok(())
+                }
+                _ => Err(self.error("'end' without 'if ... begin'")),
+            },
+            "loop" => {
+                self.flow
+                    .push((Flow::Loop(self.here, Vec::new()), self.line));
+                Ok(())
+            }
+            "while" => {
+                let condition = self.condition()?;
+                self.skip_unless(condition, true)?;
+                let at = self.here;
+                self.instruction(0x1000)?;
+                match self.flow.iter_mut().rev().find_map(|(flow, _)| match flow {
+                    Flow::Loop(_, exits) => Some(exits),
+                    _ => None,
+                }) {
+                    Some(exits) => {
+                        exits.push(at);
+                        Ok(())
+                    }
+                    None => Err(self.error("'while' outside a loop")),
+                }
+            }
+            "again" => match self.flow.pop() {
+                Some((Flow::Loop(start, exits), _)) => {
+                    self.instruction(0x1000 | (start & 0xFFF) as u16)?;
+                    for at in exits {
+                        self.patch(at, Patch::Address, self.here);
+                    }
+                    Ok(())
+                }
+                _ => Err(self.error("'again' without 'loop'")),
+            },
+            "{" => {
+                let value = self.calc()?;
+                self.byte(value as u8)
+            }
+            _ if self.macros.contains_key(&token) => self.expand_macro(&token),
+            _ => match self.lookup(&token) {
+                // A bare number or constant is a data byte.
+                Some(value) if !self.labels.contains_key(&token) => {
+                    if !(-128..=255).contains(&value) {
+                        return Err(self.error(&format!("{} is out of range for a byte", value)));
+                    }
+                    self.byte(value as u8)
+                }
+                // Any other name is a subroutine call.
+                _ if is_name(&token) => {
+                    let at = self.here;
+                    self.instruction(0x2000)?;
+                    self.reference(at, Patch::Address, token)
+                }
+                _ => Err(self.error(&format!("unknown statement '{}'", token))),
+            },
+        }
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, String> {
+        if let Some((_, line)) = self.flow.last() {
+            self.line = *line;
+            return Err(self.error("'begin' or 'loop' is never closed"));
+        }
+        for (at, kind, name, line) in std::mem::take(&mut self.patches) {
+            match self.labels.get(&name) {
+                Some(&address) => self.patch(at, kind, address),
+                None => return Err(format!("line {}: undefined name '{}'", line, name)),
+            }
+        }
+        if self.jump_to_main {
+            let main = *self
+                .labels
+                .get("main")
+                .ok_or("the program has no 'main' label")?;
+            self.memory[PROGRAM_START] = 0x10 | ((main >> 8) & 0xF) as u8;
+            self.memory[PROGRAM_START + 1] = main as u8;
+        }
+        Ok(self.memory[PROGRAM_START..self.end].to_vec())
+    }
+}
+
+fn parse_number(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = digits
+        .strip_prefix("0b")
+        .or_else(|| digits.strip_prefix("0B"))
+    {
+        i64::from_str_radix(binary, 2).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+fn is_name(text: &str) -> bool {
+    !text.is_empty()
+        && parse_number(text).is_none()
+        && !text.starts_with(':')
+        && !matches!(text, "{" | "}" | ":=" | "+=" | "-=")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_then() {
+        let source = "
+            : main
+              v0 := 1
+              if v0 == 1 then v1 := 2
+              if v0 != v1 then v2 += 3
+        ";
+        assert_eq!(
+            assemble(source).unwrap(),
+            [0x60, 0x01, 0x40, 0x01, 0x61, 0x02, 0x50, 0x10, 0x72, 0x03]
+        );
+    }
+
+    #[test]
+    fn if_begin_else_end() {
+        let source = "
+            : main
+              if v0 == 5 begin
+                v1 := 1
+              else
+                v1 := 2
+              end
+              v2 := 3
+        ";
+        assert_eq!(
+            assemble(source).unwrap(),
+            [0x30, 0x05, 0x12, 0x08, 0x61, 0x01, 0x12, 0x0A, 0x61, 0x02, 0x62, 0x03]
+        );
+    }
+
+    #[test]
+    fn loop_while_again() {
+        let source = "
+            : main
+              loop
+                v0 += 1
+                while v0 != 10
+              again
+        ";
+        assert_eq!(
+            assemble(source).unwrap(),
+            [0x70, 0x01, 0x40, 0x0A, 0x12, 0x08, 0x12, 0x00]
+        );
+    }
+
+    #[test]
+    fn constants_macros_and_a_jump_to_main() {
+        let source = "
+            :const SPEED 4
+            :macro bump reg amount { reg += amount }
+            : data
+              0xF0 0x90
+            : main
+              bump v3 SPEED
+              i := data
+              jump main
+        ";
+        assert_eq!(
+            assemble(source).unwrap(),
+            [0x12, 0x04, 0xF0, 0x90, 0x73, 0x04, 0xA2, 0x02, 0x12, 0x04]
+        );
+    }
+
+    #[test]
+    fn unbalanced_blocks_are_errors() {
+        assert!(assemble(": main\n  else").is_err());
+        assert!(assemble(": main\n  again").is_err());
+        assert!(assemble(": main\n  if v0 == 1 begin\n  v1 := 2").is_err());
+    }
+}
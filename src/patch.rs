@@ -0,0 +1,403 @@
+use crate::checksum::crc32_update;
+use crate::rom::capacity;
+
+const IPS_MAGIC: &[u8] = b"PATCH";
+const IPS_END: &[u8] = b"EOF";
+const BPS_MAGIC: &[u8] = b"BPS1";
+
+// Patch files checked for next to a ROM, in order.
+pub const EXTENSIONS: [&str; 2] = ["ips", "bps"];
+
+pub fn apply(rom: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    if patch.starts_with(IPS_MAGIC) {
+        apply_ips(rom, patch)
+    } else if patch.starts_with(BPS_MAGIC) {
+        apply_bps(rom, patch)
+    } else {
+        Err("not an IPS or BPS patch".to_string())
+    }
+}
+
+fn read_be(patch: &[u8], pos: usize, len: usize) -> Result<usize, String> {
+    let bytes = patch.get(pos..pos + len).ok_or("the patch is truncated")?;
+    Ok(bytes
+        .iter()
+        .fold(0, |value, &byte| (value << 8) | byte as usize))
+}
+
+// IPS: records of a 24-bit offset and 16-bit length followed by the bytes,
+// or a zero length, a 16-bit count and one byte to repeat. An optional
+// 24-bit size after `EOF` truncates the result.
+pub fn apply_ips(rom: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = rom.to_vec();
+    let mut pos = IPS_MAGIC.len();
+    loop {
+        if patch[pos..].starts_with(IPS_END) {
+            pos += IPS_END.len();
+            break;
+        }
+        let offset = read_be(patch, pos, 3)?;
+        let len = read_be(patch, pos + 3, 2)?;
+        pos += 5;
+        let bytes = if len == 0 {
+            let count = read_be(patch, pos, 2)?;
+            let value = read_be(patch, pos + 2, 1)? as u8;
+            pos += 3;
+            vec![value; count]
+        } else {
+            let bytes = patch.get(pos..pos + len).ok_or("the patch is truncated")?;
+            pos += len;
+            bytes.to_vec()
+        };
+        if out.len() < offset + bytes.len() {
+            out.resize(offset + bytes.len(), 0);
+        }
+        out[offset..offset + bytes.len()].copy_from_slice(&bytes);
+    }
+    if patch.len() >= pos + 3 {
+        out.truncate(read_be(patch, pos, 3)?);
+    }
+    Ok(out)
+}
+
+struct BpsReader<'a> {
+    patch: &'a [u8],
+    pos: usize,
+}
+
+impl BpsReader<'_> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self.patch.get(self.pos).ok_or("the patch is truncated")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    // BPS numbers: 7 bits per byte, low first, the last byte flagged with
+    // 0x80, and each continuation adding one so encodings are unique.
+    // Longer numbers than a usize holds are errors rather than wrapping.
+    fn number(&mut self) -> Result<usize, String> {
+        let bad = || "bad number in the patch".to_string();
+        let mut value = 0usize;
+        let mut shift = 1usize;
+        loop {
+            let byte = self.byte()?;
+            value = ((byte & 0x7F) as usize)
+                .checked_mul(shift)
+                .and_then(|bits| value.checked_add(bits))
+                .ok_or_else(bad)?;
+            if byte & 0x80 != 0 {
+                return Ok(value);
+            }
+            if shift > usize::MAX >> 7 {
+                return Err(bad());
+            }
+            shift <<= 7;
+            value = value.checked_add(shift).ok_or_else(bad)?;
+        }
+    }
+
+    fn signed(&mut self) -> Result<isize, String> {
+        let value = self.number()?;
+        let magnitude = (value >> 1) as isize;
+        Ok(if value & 1 != 0 {
+            -magnitude
+        } else {
+            magnitude
+        })
+    }
+}
+
+fn u32_at(data: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]])
+}
+
+// BPS: a copy-based delta with CRC32s of the source, target and patch, so a
+// patch made for a different ROM is refused instead of producing garbage.
+pub fn apply_bps(rom: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    if patch.len() < BPS_MAGIC.len() + 12 {
+        return Err("the patch is truncated".to_string());
+    }
+    let footer = patch.len() - 12;
+    if crc32_update(0, &patch[..patch.len() - 4]) != u32_at(patch, footer + 8) {
+        return Err("the patch is corrupt (checksum mismatch)".to_string());
+    }
+    let source_crc = u32_at(patch, footer);
+    let target_crc = u32_at(patch, footer + 4);
+
+    let mut reader = BpsReader {
+        patch: &patch[..footer],
+        pos: BPS_MAGIC.len(),
+    };
+    let truncated = || "the patch is truncated or corrupt".to_string();
+    let source_size = reader.number()?;
+    let target_size = reader.number()?;
+    // Checked before allocating: the sizes come straight from the patch.
+    for size in [source_size, target_size] {
+        if size > capacity() {
+            return Err(format!(
+                "the patch is for a {}-byte ROM, only {} bytes fit in memory",
+                size,
+                capacity()
+            ));
+        }
+    }
+    let metadata_size = reader.number()?;
+    reader.pos = reader
+        .pos
+        .checked_add(metadata_size)
+        .ok_or_else(truncated)?;
+
+    if source_size != rom.len() || crc32_update(0, rom) != source_crc {
+        return Err("the patch was made for a different ROM (checksum mismatch)".to_string());
+    }
+
+    let mut out: Vec<u8> = Vec::with_capacity(target_size);
+    let mut source_offset = 0isize;
+    let mut target_offset = 0isize;
+    while reader.pos < footer {
+        let command = reader.number()?;
+        let len = (command >> 2) + 1;
+        if out.len() + len > target_size {
+            return Err(truncated());
+        }
+        match command & 3 {
+            // SourceRead: the bytes at the same offset in the ROM.
+            0 => {
+                let start = out.len();
+                out.extend_from_slice(rom.get(start..start + len).ok_or_else(truncated)?);
+            }
+            // TargetRead: bytes stored in the patch.
+            1 => {
+                let bytes = reader
+                    .patch
+                    .get(reader.pos..reader.pos + len)
+                    .ok_or_else(truncated)?;
+                out.extend_from_slice(bytes);
+                reader.pos += len;
+            }
+            // SourceCopy: bytes from anywhere in the ROM.
+            2 => {
+                source_offset = source_offset
+                    .checked_add(reader.signed()?)
+                    .ok_or_else(truncated)?;
+                let start = usize::try_from(source_offset).map_err(|_| truncated())?;
+                out.extend_from_slice(rom.get(start..start + len).ok_or_else(truncated)?);
+                source_offset += len as isize;
+            }
+            // TargetCopy: bytes already written, possibly overlapping.
+            _ => {
+                target_offset = target_offset
+                    .checked_add(reader.signed()?)
+                    .ok_or_else(truncated)?;
+                for _ in 0..len {
+                    let from = usize::try_from(target_offset).map_err(|_| truncated())?;
+                    let byte = *out.get(from).ok_or_else(truncated)?;
+                    out.push(byte);
+                    target_offset += 1;
+                }
+            }
+        }
+    }
+
+    if out.len() != target_size || crc32_update(0, &out) != target_crc {
+        return Err("patching failed (the result's checksum doesn't match)".to_string());
+    }
+    Ok(out)
+}
+
+// An IPS patch turning `original` into `modified`. Runs of unchanged bytes
+// shorter than a record header are folded into the surrounding record.
+pub fn create_ips(original: &[u8], modified: &[u8]) -> Result<Vec<u8>, String> {
+    if modified.len() > 0xFFFFFF {
+        return Err("IPS patches can't address files over 16 MB".to_string());
+    }
+    let differs = |i: usize| original.get(i) != Some(&modified[i]);
+    let mut out = IPS_MAGIC.to_vec();
+    let mut i = 0;
+    while i < modified.len() {
+        if !differs(i) {
+            i += 1;
+            continue;
+        }
+        // An offset spelling "EOF" would end the patch early.
+        let start = if i == 0x454F46 { i - 1 } else { i };
+        let mut end = i + 1;
+        while end < modified.len() && end - start < 0xFFFF {
+            if differs(end) {
+                end += 1;
+                continue;
+            }
+            let gap = (end..modified.len()).take_while(|&j| !differs(j)).count();
+            if gap < 5 && end + gap < modified.len() && end + gap - start < 0xFFFF {
+                end += gap;
+            } else {
+                break;
+            }
+        }
+
+        out.extend_from_slice(&(start as u32).to_be_bytes()[1..]);
+        out.extend_from_slice(&((end - start) as u16).to_be_bytes());
+        out.extend_from_slice(&modified[start..end]);
+        i = end;
+    }
+    out.extend_from_slice(IPS_END);
+    if modified.len() < original.len() {
+        out.extend_from_slice(&(modified.len() as u32).to_be_bytes()[1..]);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: [u8; 8] = [0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x12, 0x06];
+
+    fn bps_number(mut value: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let low = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(0x80 | low);
+                return out;
+            }
+            out.push(low);
+            value -= 1;
+        }
+    }
+
+    // A BPS patch with the given sizes and commands, checksummed.
+    fn bps(source: &[u8], target: &[u8], target_size: usize, commands: &[u8]) -> Vec<u8> {
+        let mut patch = BPS_MAGIC.to_vec();
+        patch.extend(bps_number(source.len()));
+        patch.extend(bps_number(target_size));
+        patch.extend(bps_number(0));
+        patch.extend_from_slice(commands);
+        patch.extend_from_slice(&crc32_update(0, source).to_le_bytes());
+        patch.extend_from_slice(&crc32_update(0, target).to_le_bytes());
+        let crc = crc32_update(0, &patch);
+        patch.extend_from_slice(&crc.to_le_bytes());
+        patch
+    }
+
+    #[test]
+    fn ips_records_runs_and_truncation() {
+        let mut patch = IPS_MAGIC.to_vec();
+        // Two bytes at 2.
+        patch.extend_from_slice(&[0x00, 0x00, 0x02, 0x00, 0x02, 0xA3, 0x00]);
+        // Three 0xFF at 4.
+        patch.extend_from_slice(&[0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x03, 0xFF]);
+        patch.extend_from_slice(IPS_END);
+        assert_eq!(
+            apply(&ORIGINAL, &patch).unwrap(),
+            [0x00, 0xE0, 0xA3, 0x00, 0xFF, 0xFF, 0xFF, 0x06]
+        );
+
+        patch.extend_from_slice(&[0x00, 0x00, 0x04]);
+        assert_eq!(apply(&ORIGINAL, &patch).unwrap(), [0x00, 0xE0, 0xA3, 0x00]);
+    }
+
+    #[test]
+    fn ips_without_an_end_is_an_error() {
+        let mut patch = IPS_MAGIC.to_vec();
+        patch.extend_from_slice(&[0x00, 0x00, 0x02, 0x00, 0x04, 0xA3]);
+        assert!(apply(&ORIGINAL, &patch).is_err());
+    }
+
+    #[test]
+    fn create_ips_round_trips() {
+        let cases: [&[u8]; 4] = [
+            // One changed byte.
+            &[0x00, 0xE0, 0xA2, 0x2B, 0x60, 0x0C, 0x12, 0x06],
+            // Changes close enough to share a record.
+            &[0x01, 0xE0, 0xA2, 0x2A, 0x60, 0x0D, 0x12, 0x06],
+            // Longer.
+            &[0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x12, 0x06, 0xF0, 0x90],
+            // Shorter.
+            &[0x00, 0xE0, 0xA2, 0x2A],
+        ];
+        for modified in cases {
+            let patch = create_ips(&ORIGINAL, modified).unwrap();
+            assert_eq!(apply(&ORIGINAL, &patch).unwrap(), modified);
+        }
+        let patch = create_ips(&ORIGINAL, &ORIGINAL).unwrap();
+        assert_eq!(patch, b"PATCHEOF");
+    }
+
+    #[test]
+    fn bps_commands() {
+        let target = [0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0D, 0x60, 0x0D, 0xA2, 0x2A];
+        let mut commands = Vec::new();
+        // SourceRead 5 bytes.
+        commands.extend(bps_number((5 - 1) << 2));
+        // TargetRead 1 byte.
+        commands.extend(bps_number(1));
+        commands.push(0x0D);
+        // TargetCopy 2 bytes from 4.
+        commands.extend(bps_number((2 - 1) << 2 | 3));
+        commands.extend(bps_number(4 << 1));
+        // SourceCopy 2 bytes from 2.
+        commands.extend(bps_number((2 - 1) << 2 | 2));
+        commands.extend(bps_number(2 << 1));
+        let patch = bps(&ORIGINAL, &target, target.len(), &commands);
+        assert_eq!(apply(&ORIGINAL, &patch).unwrap(), target);
+    }
+
+    #[test]
+    fn bps_for_another_rom_is_refused() {
+        let patch = bps(
+            &[0x12, 0x00],
+            &ORIGINAL,
+            ORIGINAL.len(),
+            &bps_number(7 << 2 | 1),
+        );
+        assert!(apply(&ORIGINAL, &patch).is_err());
+    }
+
+    #[test]
+    fn bps_sizes_past_memory_are_refused_before_allocating() {
+        let patch = bps(&ORIGINAL, &[], 1 << 60, &[]);
+        let error = apply(&ORIGINAL, &patch).unwrap_err();
+        assert!(error.contains("fit in memory"), "{}", error);
+    }
+
+    // Continuation bytes with no end, longer than any usize.
+    fn over_long_number() -> Vec<u8> {
+        vec![0x7F; 12]
+    }
+
+    #[test]
+    fn bps_over_long_source_size_is_an_error() {
+        let mut patch = BPS_MAGIC.to_vec();
+        patch.extend(over_long_number());
+        patch.push(0x80);
+        patch.extend_from_slice(&[0; 8]);
+        let crc = crc32_update(0, &patch);
+        patch.extend_from_slice(&crc.to_le_bytes());
+        let error = apply(&ORIGINAL, &patch).unwrap_err();
+        assert!(error.contains("bad number"), "{}", error);
+    }
+
+    #[test]
+    fn bps_over_long_copy_offset_is_an_error() {
+        // SourceCopy 1 byte.
+        let mut commands = bps_number(2);
+        commands.extend(over_long_number());
+        commands.push(0x80);
+        let patch = bps(&ORIGINAL, &ORIGINAL, ORIGINAL.len(), &commands);
+        let error = apply(&ORIGINAL, &patch).unwrap_err();
+        assert!(error.contains("bad number"), "{}", error);
+    }
+
+    #[test]
+    fn bps_offsets_out_of_range_are_errors() {
+        // SourceCopy and TargetCopy from isize::MAX bytes on.
+        let mut commands = bps_number(2);
+        commands.extend(bps_number(usize::MAX - 1));
+        commands.extend(bps_number(3));
+        commands.extend(bps_number(usize::MAX - 1));
+        let patch = bps(&ORIGINAL, &ORIGINAL, ORIGINAL.len(), &commands);
+        assert!(apply(&ORIGINAL, &patch).is_err());
+    }
+}
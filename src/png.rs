@@ -0,0 +1,75 @@
+use crate::checksum::{adler32, crc32_update};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+// Encodes an indexed-colour PNG. `pixels` holds one palette index per pixel,
+// and the bit depth is the smallest one that fits the palette.
+pub fn encode_indexed(width: u32, height: u32, palette: &[[u8; 3]], pixels: &[u8]) -> Vec<u8> {
+    assert!(!palette.is_empty() && palette.len() <= 256);
+    assert_eq!(pixels.len(), (width * height) as usize);
+
+    let bit_depth: u8 = match palette.len() {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=16 => 4,
+        _ => 8,
+    };
+    let pixels_per_byte = 8 / bit_depth as usize;
+    let row_bytes = (width as usize).div_ceil(pixels_per_byte);
+
+    let mut raw = Vec::with_capacity((row_bytes + 1) * height as usize);
+    for row in pixels.chunks(width as usize) {
+        raw.push(0);
+        for chunk in row.chunks(pixels_per_byte) {
+            let mut byte = 0u8;
+            for (i, &index) in chunk.iter().enumerate() {
+                let shift = 8 - bit_depth as usize * (i + 1);
+                byte |= (index & ((1u16 << bit_depth) - 1) as u8) << shift;
+            }
+            raw.push(byte);
+        }
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[bit_depth, 3, 0, 0, 0]);
+
+    let plte: Vec<u8> = palette.iter().flatten().copied().collect();
+
+    let mut png = Vec::new();
+    png.extend_from_slice(&SIGNATURE);
+    write_chunk(&mut png, b"IHDR", &ihdr);
+    write_chunk(&mut png, b"PLTE", &plte);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32_update(crc32_update(0, kind), data);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// A zlib stream made of uncompressed deflate blocks. The images are tiny and
+// mostly 1 bit per pixel, so compression isn't worth an encoder.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        out.push(last as u8);
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
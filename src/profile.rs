@@ -0,0 +1,170 @@
+use crate::disasm::{self, Instruction};
+use crate::memory::{Memory, MEMORY_SIZE};
+use std::collections::BTreeMap;
+
+// Rows in each table of the report.
+const REPORT_ROWS: usize = 20;
+
+#[derive(Clone, Copy, Default)]
+pub struct Subroutine {
+    pub calls: u64,
+    // Cycles from each call to its return, nested calls included.
+    pub inclusive: u64,
+    // Cycles spent on the subroutine's own instructions.
+    pub exclusive: u64,
+}
+
+// Where a ROM spends its time: how often each address ran, and the cost of
+// every subroutine, following `CALL`/`RET` pairs.
+pub struct Profile {
+    // Executions by address.
+    pub counts: Vec<u64>,
+    pub cycles: u64,
+    // By entry address.
+    pub subroutines: BTreeMap<u16, Subroutine>,
+    // Calls not yet returned from: the subroutine and `cycles` on entry.
+    frames: Vec<(u16, u64)>,
+}
+
+// `clone_from` reuses the per-address counts, which the heatmap snapshot
+// copies every frame.
+impl Clone for Profile {
+    fn clone(&self) -> Self {
+        Self {
+            counts: self.counts.clone(),
+            cycles: self.cycles,
+            subroutines: self.subroutines.clone(),
+            frames: self.frames.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.counts.clone_from(&source.counts);
+        self.cycles = source.cycles;
+        self.subroutines.clone_from(&source.subroutines);
+        self.frames.clone_from(&source.frames);
+    }
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; MEMORY_SIZE],
+            cycles: 0,
+            subroutines: BTreeMap::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    // Called before the instruction at `pc` runs.
+    pub fn count(&mut self, pc: u16) {
+        if let Some(count) = self.counts.get_mut(pc as usize) {
+            *count += 1;
+        }
+        self.cycles += 1;
+        if let Some(&(entry, _)) = self.frames.last() {
+            self.subroutines.entry(entry).or_default().exclusive += 1;
+        }
+    }
+
+    pub fn call(&mut self, entry: u16) {
+        self.subroutines.entry(entry).or_default().calls += 1;
+        self.frames.push((entry, self.cycles));
+    }
+
+    pub fn ret(&mut self) {
+        if let Some((entry, start)) = self.frames.pop() {
+            self.subroutines.entry(entry).or_default().inclusive += self.cycles - start;
+        }
+    }
+
+    // `subroutines` with the calls still running counted up to now, so a
+    // main loop that never returns still shows its cost.
+    pub fn subroutine_totals(&self) -> BTreeMap<u16, Subroutine> {
+        let mut totals = self.subroutines.clone();
+        for &(entry, start) in &self.frames {
+            totals.entry(entry).or_default().inclusive += self.cycles - start;
+        }
+        totals
+    }
+
+    pub fn hottest(&self) -> Option<(u16, u64)> {
+        (0..MEMORY_SIZE)
+            .max_by_key(|&address| self.counts[address])
+            .filter(|&address| self.counts[address] > 0)
+            .map(|address| (address as u16, self.counts[address]))
+    }
+
+    pub fn share(&self, count: u64) -> f64 {
+        if self.cycles > 0 {
+            count as f64 / self.cycles as f64
+        } else {
+            0.0
+        }
+    }
+
+    // The busiest addresses and subroutines, with their disassembly.
+    pub fn report(&self, memory: &Memory) -> String {
+        let mut lines = vec![
+            format!("{} instructions profiled", self.cycles),
+            String::new(),
+            "Hot spots:".to_string(),
+            format!(
+                "  {:<7} {:>12} {:>7}  instruction",
+                "address", "count", "share"
+            ),
+        ];
+
+        let mut addresses: Vec<usize> = (0..MEMORY_SIZE).filter(|&a| self.counts[a] > 0).collect();
+        addresses.sort_by_key(|&address| std::cmp::Reverse(self.counts[address]));
+        for &address in addresses.iter().take(REPORT_ROWS) {
+            let count = self.counts[address];
+            let instruction = instruction_at(memory, address as u16);
+            let note = match instruction {
+                Instruction::LdVxDt(_) => "  <- polls the delay timer",
+                Instruction::LdKey(_) => "  <- waits for a key",
+                _ => "",
+            };
+            lines.push(format!(
+                "  0x{:03X}   {:>12} {:>6.1}%  {}{}",
+                address,
+                count,
+                self.share(count) * 100.0,
+                instruction,
+                note
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push("Subroutines:".to_string());
+        lines.push(format!(
+            "  {:<7} {:>9} {:>12} {:>7} {:>12} {:>10}",
+            "entry", "calls", "cycles", "share", "own cycles", "per call"
+        ));
+        let totals = self.subroutine_totals();
+        let mut subroutines: Vec<_> = totals.iter().collect();
+        subroutines.sort_by_key(|(_, subroutine)| std::cmp::Reverse(subroutine.inclusive));
+        for (entry, subroutine) in subroutines.into_iter().take(REPORT_ROWS) {
+            lines.push(format!(
+                "  0x{:03X}   {:>9} {:>12} {:>6.1}% {:>12} {:>10.1}",
+                entry,
+                subroutine.calls,
+                subroutine.inclusive,
+                self.share(subroutine.inclusive) * 100.0,
+                subroutine.exclusive,
+                subroutine.inclusive as f64 / subroutine.calls.max(1) as f64
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn instruction_at(memory: &Memory, address: u16) -> Instruction {
+    disasm::decode((memory.read(address) as u16) << 8 | memory.read(address + 1) as u16)
+}
@@ -0,0 +1,139 @@
+// Behaviours that differ between CHIP-8 interpreters. Names follow the
+// quirks test ROM: each flag is true when the quirky behaviour is active.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Quirks {
+    // 8xy1/8xy2/8xy3 reset VF to 0.
+    pub vf_reset: bool,
+    // Fx55/Fx65 leave I pointing past the last register.
+    pub memory: bool,
+    // 8xy6/8xyE shift VX in place and ignore VY.
+    pub shifting: bool,
+    // Bnnn jumps to nnn + VX (x being the high nibble of nnn) instead of V0.
+    pub jumping: bool,
+    // Sprites are cut off at the screen edge instead of wrapping around.
+    pub clipping: bool,
+    // A sprite starting at VX >= 64 or VY >= 32 starts at VX mod 64, VY mod
+    // 32, as on the VIP and SCHIP, instead of drawing nothing.
+    pub wrapping: bool,
+}
+
+const NAMES: [&str; 6] = [
+    "vfreset", "memory", "shifting", "jumping", "clipping", "wrapping",
+];
+
+impl Quirks {
+    fn flag(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "vfreset" => Some(&mut self.vf_reset),
+            "memory" => Some(&mut self.memory),
+            "shifting" => Some(&mut self.shifting),
+            "jumping" => Some(&mut self.jumping),
+            "clipping" => Some(&mut self.clipping),
+            "wrapping" => Some(&mut self.wrapping),
+            _ => None,
+        }
+    }
+
+    // Applies a comma-separated list such as `shifting,-memory`: a bare
+    // name turns a quirk on, a leading `-` turns it off.
+    pub fn apply(&mut self, list: &str) -> Result<(), String> {
+        for item in list
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+        {
+            let (name, on) = match item.strip_prefix('-') {
+                Some(name) => (name, false),
+                None => (item, true),
+            };
+            match self.flag(name) {
+                Some(flag) => *flag = on,
+                None => {
+                    return Err(format!(
+                        "unknown quirk '{}' (expected {})",
+                        name,
+                        NAMES.join(", ")
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn describe(&self) -> String {
+        let flags = [
+            self.vf_reset,
+            self.memory,
+            self.shifting,
+            self.jumping,
+            self.clipping,
+            self.wrapping,
+        ];
+        NAMES
+            .iter()
+            .zip(flags)
+            .map(|(name, on)| format!("{}{}", if on { "" } else { "-" }, name))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+// What this emulator has always done: a mix of VIP and CHIP-48 behaviour.
+impl Default for Quirks {
+    fn default() -> Self {
+        Self {
+            vf_reset: true,
+            memory: true,
+            shifting: true,
+            jumping: false,
+            clipping: true,
+            wrapping: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Variant {
+    // The original COSMAC VIP interpreter.
+    Chip8,
+    // SUPER-CHIP 1.1 on the HP-48, which most 90s games were written for.
+    Schip,
+}
+
+impl Variant {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "chip8" | "chip-8" | "vip" => Some(Self::Chip8),
+            "schip" | "superchip" => Some(Self::Schip),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Chip8 => "chip8",
+            Self::Schip => "schip",
+        }
+    }
+
+    pub fn quirks(&self) -> Quirks {
+        match self {
+            Self::Chip8 => Quirks {
+                vf_reset: true,
+                memory: true,
+                shifting: false,
+                jumping: false,
+                clipping: true,
+                wrapping: false,
+            },
+            Self::Schip => Quirks {
+                vf_reset: false,
+                memory: false,
+                shifting: true,
+                jumping: true,
+                clipping: true,
+                wrapping: false,
+            },
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::audio::{Buzzer, BuzzerStream};
+use crate::display::{self, ScreenTexture};
+use crate::frontend::{AudioSink, Command, Frame, Input, InputSource, VideoSink};
+use crate::keyboard::{Keyboard, Keymap};
+use crate::layout::Layout;
+use crate::ui;
+use raylib::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Video and input both need the window handle, so they share it.
+pub type SharedHandle = Rc<RefCell<RaylibHandle>>;
+
+pub struct WindowVideo {
+    pub rl: SharedHandle,
+    pub thread: RaylibThread,
+    pub screen: ScreenTexture,
+}
+
+impl WindowVideo {
+    pub fn new(rl: SharedHandle, thread: RaylibThread) -> Result<Self, String> {
+        let screen = ScreenTexture::new(&mut rl.borrow_mut(), &thread)?;
+        Ok(Self { rl, thread, screen })
+    }
+}
+
+impl VideoSink for WindowVideo {
+    fn present(&mut self, frame: &Frame) {
+        let mut rl = self.rl.borrow_mut();
+        let layout = Layout::new(
+            rl.get_screen_width(),
+            rl.get_screen_height(),
+            display::WIDTH,
+            display::HEIGHT,
+            frame.scaling,
+        );
+        self.screen.update(frame.display, frame.palette);
+        let mut d = rl.begin_drawing(&self.thread);
+        d.clear_background(Color::BLACK);
+
+        self.screen.draw(&mut d, frame.palette, &layout, frame.grid);
+        if frame.heatmap {
+            ui::render_heatmap(&mut d, &layout, frame.cpu);
+        }
+
+        frame.ui.render_status_bar(
+            &mut d,
+            layout.status_bar,
+            frame.cpu,
+            frame.rom_path,
+            frame.patched,
+        );
+    }
+
+    fn toggle_fullscreen(&mut self) {
+        self.rl.borrow_mut().toggle_fullscreen();
+    }
+}
+
+pub struct WindowInput {
+    pub rl: SharedHandle,
+    pub keyboard: Keyboard,
+    pub keymap: Keymap,
+}
+
+impl WindowInput {
+    pub fn new(rl: SharedHandle) -> Self {
+        Self {
+            rl,
+            keyboard: Keyboard::new(),
+            keymap: Keymap::default(),
+        }
+    }
+}
+
+const COMMAND_KEYS: [(KeyboardKey, Command); 13] = [
+    (KeyboardKey::KEY_SPACE, Command::TogglePause),
+    (KeyboardKey::KEY_TAB, Command::ToggleStepMode),
+    (KeyboardKey::KEY_S, Command::Step),
+    (KeyboardKey::KEY_M, Command::ToggleMute),
+    (KeyboardKey::KEY_F1, Command::ToggleFlicker),
+    (KeyboardKey::KEY_F3, Command::ToggleHeatmap),
+    (KeyboardKey::KEY_F5, Command::Reset),
+    (KeyboardKey::KEY_F6, Command::CycleScaling),
+    (KeyboardKey::KEY_F7, Command::ToggleGrid),
+    (KeyboardKey::KEY_F9, Command::LoadState),
+    (KeyboardKey::KEY_F10, Command::ToggleGifRecording),
+    (KeyboardKey::KEY_F11, Command::ToggleFullscreen),
+    (KeyboardKey::KEY_F12, Command::Screenshot),
+];
+
+impl InputSource for WindowInput {
+    fn poll(&mut self, _frame: u32) -> Input {
+        let rl = self.rl.borrow();
+
+        let mut commands: Vec<Command> = COMMAND_KEYS
+            .iter()
+            .filter(|(key, _)| rl.is_key_pressed(*key))
+            .map(|&(_, command)| command)
+            .collect();
+        if rl.window_should_close() {
+            commands.push(Command::Quit);
+        }
+
+        self.keyboard.update(&rl, &self.keymap);
+        Input {
+            keys: self.keyboard.mask(),
+            commands,
+        }
+    }
+}
+
+pub struct StreamAudio<'a> {
+    pub stream: BuzzerStream<'a>,
+    pub buzzer: Buzzer,
+}
+
+impl AudioSink for StreamAudio<'_> {
+    fn update(&mut self, active: bool) {
+        self.stream.update(&mut self.buzzer, active);
+    }
+
+    fn toggle_mute(&mut self) {
+        self.buzzer.muted = !self.buzzer.muted;
+    }
+}
@@ -0,0 +1,265 @@
+use crate::analysis::{Analysis, Block, PROGRAM_START};
+use crate::disasm::Instruction;
+use std::fmt::Write;
+
+// Whether the instruction ends its block in generated code: anything that
+// can jump, and anything left to the interpreter that may move the program
+// counter.
+fn ends_block(instruction: Instruction) -> bool {
+    use Instruction::*;
+
+    matches!(
+        instruction,
+        Jp(_)
+            | Call(_)
+            | Ret
+            | JpV0(_)
+            | SeImm(..)
+            | SneImm(..)
+            | SeReg(..)
+            | SneReg(..)
+            | Skp(_)
+            | Sknp(_)
+            | LdKey(_)
+            | Sys(_)
+            | Invalid(_)
+    )
+}
+
+fn writes_memory(instruction: Instruction) -> bool {
+    matches!(instruction, Instruction::Store(_) | Instruction::LdBcd(_))
+}
+
+// The analysis's blocks, also split after every block-ending instruction and
+// every memory write, so code a write changes is checked again before it
+// runs.
+fn blocks(analysis: &Analysis) -> Vec<Block> {
+    let mut leaders = analysis.leaders();
+    for (&addr, &instruction) in &analysis.instructions {
+        if ends_block(instruction) || writes_memory(instruction) {
+            leaders.insert(addr + 2);
+        }
+    }
+    analysis.blocks(&leaders)
+}
+
+// Rust for an instruction that doesn't end its block.
+fn statement(instruction: Instruction) -> String {
+    use Instruction::*;
+
+    match instruction {
+        Cls => "cpu.display.clear();".to_string(),
+        LdImm(x, nn) => format!("cpu.registers[{}] = {};", x, nn),
+        AddImm(x, nn) => format!(
+            "cpu.registers[{x}] = cpu.registers[{x}].wrapping_add({});",
+            nn
+        ),
+        LdReg(x, y) => format!("cpu.registers[{}] = cpu.registers[{}];", x, y),
+        Or(x, y) => logic(x, y, "|"),
+        And(x, y) => logic(x, y, "&"),
+        Xor(x, y) => logic(x, y, "^"),
+        AddReg(x, y) => format!(
+            "let (sum, carry) = cpu.registers[{x}].overflowing_add(cpu.registers[{y}]);\n\
+             cpu.registers[{x}] = sum;\n\
+             cpu.registers[15] = carry as u8;"
+        ),
+        Sub(x, y) => subtract(x, x, y),
+        Subn(x, y) => subtract(x, y, x),
+        Shr(x, y) => shift(x, y, "v >> 1", "v & 1"),
+        Shl(x, y) => shift(x, y, "v << 1", "v >> 7"),
+        LdI(addr) => format!("cpu.index = 0x{:03X};", addr),
+        LdVxDt(x) => format!("cpu.registers[{}] = cpu.delay_timer;", x),
+        LdDtVx(x) => format!("cpu.delay_timer = cpu.registers[{}];", x),
+        LdStVx(x) => format!("cpu.sound_timer = cpu.registers[{}];", x),
+        AddI(x) => format!("cpu.index += cpu.registers[{}] as u16;", x),
+        LdFont(x) => format!("cpu.index = cpu.registers[{}] as u16 * 5;", x),
+        // Drawing, randomness and memory go through the interpreter.
+        _ => format!("cpu.execute(Instruction::{:?});", instruction),
+    }
+}
+
+fn logic(x: u8, y: u8, operator: &str) -> String {
+    format!(
+        "cpu.registers[{x}] {operator}= cpu.registers[{y}];\n\
+         if cpu.quirks.vf_reset {{\n    cpu.registers[15] = 0;\n}}"
+    )
+}
+
+// VX = `a` - `b`, with VF set when it didn't borrow.
+fn subtract(x: u8, a: u8, b: u8) -> String {
+    format!(
+        "let (a, b) = (cpu.registers[{a}], cpu.registers[{b}]);\n\
+         cpu.registers[{x}] = a.wrapping_sub(b);\n\
+         cpu.registers[15] = (a >= b) as u8;"
+    )
+}
+
+fn shift(x: u8, y: u8, result: &str, flag: &str) -> String {
+    let source = if x == y {
+        x.to_string()
+    } else {
+        format!("if cpu.quirks.shifting {{ {x} }} else {{ {y} }}")
+    };
+    format!(
+        "let v = cpu.registers[{source}];\n\
+         cpu.registers[{x}] = {result};\n\
+         cpu.registers[15] = {flag};"
+    )
+}
+
+// Rust for an instruction that ends its block, leaving the program counter
+// where the interpreter would.
+fn terminator(instruction: Instruction, next: u16) -> String {
+    use Instruction::*;
+
+    let skip = |condition: String| {
+        let (taken, not_taken) = (next + 2, next);
+        let short = format!("if {condition} {{ 0x{taken:03X} }} else {{ 0x{not_taken:03X} }}");
+        // Laid out the way rustfmt would, so generated files stay formatted.
+        let expression = if short.len() <= 50 {
+            short
+        } else {
+            format!("if {condition} {{\n    0x{taken:03X}\n}} else {{\n    0x{not_taken:03X}\n}}")
+        };
+        format!("cpu.program_counter = {};", expression)
+    };
+    match instruction {
+        Jp(addr) => format!("cpu.program_counter = 0x{:03X};", addr),
+        Call(addr) => format!(
+            "cpu.stack.push(0x{:03X});\ncpu.program_counter = 0x{:03X};",
+            next, addr
+        ),
+        Ret => format!(
+            "cpu.program_counter = cpu.stack.pop().unwrap_or(0x{:03X});",
+            next
+        ),
+        SeImm(x, nn) => skip(format!("cpu.registers[{}] == {}", x, nn)),
+        SneImm(x, nn) => skip(format!("cpu.registers[{}] != {}", x, nn)),
+        SeReg(x, y) => skip(format!("cpu.registers[{}] == cpu.registers[{}]", x, y)),
+        SneReg(x, y) => skip(format!("cpu.registers[{}] != cpu.registers[{}]", x, y)),
+        // Keys, computed jumps and anything unknown go through the
+        // interpreter, which expects the program counter past the opcode.
+        _ => format!(
+            "cpu.program_counter = 0x{:03X};\ncpu.execute(Instruction::{:?});",
+            next, instruction
+        ),
+    }
+}
+
+fn indent(code: &str, depth: usize) -> String {
+    let prefix = " ".repeat(depth * 4);
+    code.lines()
+        .map(|line| format!("{}{}\n", prefix, line))
+        .collect()
+}
+
+fn block_function(block: &Block) -> String {
+    let mut out = format!(
+        "fn block_{:03x}(cpu: &mut Cpu, budget: &mut u32) {{\n",
+        block.start
+    );
+    for (i, &(addr, opcode, instruction)) in block.instructions.iter().enumerate() {
+        let next = addr + 2;
+        let _ = writeln!(
+            out,
+            "    // 0x{:03X}  {:04X}  {}",
+            addr, opcode, instruction
+        );
+        // The dispatcher only enters with instructions left to run.
+        if i > 0 {
+            let _ = writeln!(
+                out,
+                "    if *budget == 0 {{\n        cpu.program_counter = 0x{:03X};\n        return;\n    }}",
+                addr
+            );
+        }
+        out.push_str("    *budget -= 1;\n");
+
+        let last = i + 1 == block.instructions.len();
+        if last && ends_block(instruction) {
+            out.push_str(&indent(&terminator(instruction, next), 1));
+        } else {
+            let code = statement(instruction);
+            if code.contains("let ") {
+                out.push_str("    {\n");
+                out.push_str(&indent(&code, 2));
+                out.push_str("    }\n");
+            } else {
+                out.push_str(&indent(&code, 1));
+            }
+            if last {
+                let _ = writeln!(out, "    cpu.program_counter = 0x{:03X};", next);
+            }
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+// A byte slice literal, sixteen to a line.
+fn byte_list(bytes: &[u8]) -> String {
+    let mut out = String::from("&[\n");
+    for line in bytes.chunks(16) {
+        let line: Vec<_> = line.iter().map(|byte| format!("0x{:02X},", byte)).collect();
+        let _ = writeln!(out, "    {}", line.join(" "));
+    }
+    out.push(']');
+    out
+}
+
+// Translates `program` into a Rust module with a `run_frame` that behaves like
+// `Cpu::run_frame`. Every block reachable from 0x200 becomes a function on
+// the `Cpu`; a block only runs while its bytes in memory still match the
+// ROM, and anything else (computed jump targets, code the program wrote
+// itself) is interpreted an instruction at a time.
+pub fn recompile(name: &str, program: &[u8]) -> String {
+    let analysis = Analysis::new(program);
+    let blocks = blocks(&analysis);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "// Recompiled from {} by `chip8_emulator recompile`.",
+        name
+    );
+    out.push_str(
+        "// Each block runs only while memory still holds the code it was built from.\n\n",
+    );
+    out.push_str("use chip8_emulator::cpu::Cpu;\nuse chip8_emulator::disasm::Instruction;\n\n");
+    let _ = writeln!(
+        out,
+        "#[rustfmt::skip]\npub const ROM: &[u8] = {};\n",
+        byte_list(program)
+    );
+
+    out.push_str("// Runs `cpu.cycles_per_frame` instructions, then ticks the timers.\n");
+    out.push_str("pub fn run_frame(cpu: &mut Cpu) {\n");
+    out.push_str("    let mut budget = cpu.cycles_per_frame;\n");
+    out.push_str("    while budget > 0 {\n");
+    out.push_str("        match cpu.program_counter {\n");
+    for block in &blocks {
+        let _ = writeln!(
+            out,
+            "            0x{:03X} if cpu.memory.bytes(0x{:03X}, {}) == BLOCK_{:03X} => block_{:03x}(cpu, &mut budget),",
+            block.start,
+            block.start,
+            block.end - block.start,
+            block.start,
+            block.start
+        );
+    }
+    out.push_str("            _ => {\n                cpu.cycle();\n                budget -= 1;\n            }\n");
+    out.push_str("        }\n    }\n    cpu.tick_timers();\n}\n");
+
+    for block in &blocks {
+        let start = (block.start - PROGRAM_START) as usize;
+        let end = (block.end - PROGRAM_START) as usize;
+        let _ = writeln!(
+            out,
+            "\n#[rustfmt::skip]\nconst BLOCK_{:03X}: &[u8] = {};\n",
+            block.start,
+            byte_list(&program[start..end])
+        );
+        out.push_str(&block_function(block));
+    }
+    out
+}
@@ -0,0 +1,65 @@
+use crate::display::{Display, Palette, HEIGHT, WIDTH};
+use crate::gif::GifEncoder;
+
+const FRAMES_PER_SECOND: u64 = 60;
+
+pub struct GifRecorder {
+    encoder: GifEncoder,
+    scale: usize,
+    frame_skip: u32,
+    frames_seen: u64,
+    pending: Option<(Vec<u8>, u64)>,
+}
+
+impl GifRecorder {
+    pub fn new(palette: &Palette, scale: usize, frame_skip: u32) -> Self {
+        let scale = scale.max(1);
+        let encoder = GifEncoder::new(
+            (WIDTH * scale) as u16,
+            (HEIGHT * scale) as u16,
+            &palette.colors(),
+        );
+        Self {
+            encoder,
+            scale,
+            frame_skip,
+            frames_seen: 0,
+            pending: None,
+        }
+    }
+
+    // Called once per presented frame. Identical consecutive frames are
+    // merged into one GIF frame with a longer delay.
+    pub fn capture(&mut self, display: &Display) {
+        let frame = self.frames_seen;
+        self.frames_seen += 1;
+        if !frame.is_multiple_of(self.frame_skip as u64 + 1) {
+            return;
+        }
+
+        let pixels = crate::screenshot::scaled_pixels(display, self.scale);
+        if let Some((pending, _)) = &self.pending {
+            if *pending == pixels {
+                return;
+            }
+        }
+        self.flush(frame);
+        self.pending = Some((pixels, frame));
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.flush(self.frames_seen);
+        self.encoder.finish()
+    }
+
+    fn flush(&mut self, until_frame: u64) {
+        if let Some((pixels, start_frame)) = self.pending.take() {
+            // GIF delays are in hundredths of a second; rounding from absolute
+            // frame numbers keeps the clip from drifting.
+            let start = start_frame * 100 / FRAMES_PER_SECOND;
+            let end = until_frame * 100 / FRAMES_PER_SECOND;
+            let delay = (end - start).clamp(2, u16::MAX as u64) as u16;
+            self.encoder.add_frame(&pixels, delay);
+        }
+    }
+}
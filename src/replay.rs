@@ -0,0 +1,163 @@
+use crate::cpu::Cpu;
+use crate::quirks::Quirks;
+use std::fs;
+
+// Text format, one change per line: `<frame> <keypad mask in hex>`, plus a
+// `seed <n>` line so random numbers repeat on playback, and `cycles <n>` and
+// `quirks <list>` lines so the same instructions run between key changes.
+// Logs from before those were recorded play back with the current settings.
+pub struct InputLog {
+    pub seed: u64,
+    pub cycles_per_frame: Option<u32>,
+    pub quirks: Option<Quirks>,
+    events: Vec<(u32, u16)>,
+}
+
+impl InputLog {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            cycles_per_frame: None,
+            quirks: None,
+            events: Vec::new(),
+        }
+    }
+
+    // An empty log for a run of `cpu`, recording its speed and quirks.
+    pub fn for_cpu(seed: u64, cpu: &Cpu) -> Self {
+        Self {
+            cycles_per_frame: Some(cpu.cycles_per_frame),
+            quirks: Some(cpu.quirks),
+            ..Self::new(seed)
+        }
+    }
+
+    // Sets the speed and quirks the log was recorded with.
+    pub fn apply_settings(&self, cpu: &mut Cpu) {
+        if let Some(cycles) = self.cycles_per_frame {
+            cpu.cycles_per_frame = cycles;
+        }
+        if let Some(quirks) = self.quirks {
+            cpu.quirks = quirks;
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = fs::read_to_string(path)?;
+        let mut log = Self::new(0);
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let (Some(first), Some(second), None) = (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(format!("{}:{}: expected two fields", path, line_number + 1).into());
+            };
+
+            if first == "seed" {
+                log.seed = second.parse()?;
+            } else if first == "cycles" {
+                log.cycles_per_frame = Some(second.parse()?);
+            } else if first == "quirks" {
+                let mut quirks = Quirks::default();
+                quirks.apply(second)?;
+                log.quirks = Some(quirks);
+            } else {
+                let frame: u32 = first.parse()?;
+                let keys = u16::from_str_radix(second, 16)?;
+                log.events.push((frame, keys));
+            }
+        }
+
+        log.events.sort_by_key(|&(frame, _)| frame);
+        Ok(log)
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let mut text = String::from("# chip8 input log: <frame> <keypad mask>\n");
+        text.push_str(&format!("seed {}\n", self.seed));
+        if let Some(cycles) = self.cycles_per_frame {
+            text.push_str(&format!("cycles {}\n", cycles));
+        }
+        if let Some(quirks) = &self.quirks {
+            text.push_str(&format!("quirks {}\n", quirks.describe()));
+        }
+        for (frame, keys) in &self.events {
+            text.push_str(&format!("{} {:04X}\n", frame, keys));
+        }
+        fs::write(path, text)
+    }
+
+    pub fn record(&mut self, frame: u32, keys: u16) {
+        let last = self.events.last().map(|&(_, keys)| keys).unwrap_or(0);
+        if keys != last {
+            self.events.push((frame, keys));
+        }
+    }
+
+    // The frame of the last key change.
+    pub fn last_frame(&self) -> u32 {
+        self.events.last().map_or(0, |&(frame, _)| frame)
+    }
+
+    pub fn keys_at(&self, frame: u32) -> u16 {
+        let upto = self.events.partition_point(|&(f, _)| f <= frame);
+        if upto == 0 {
+            0
+        } else {
+            self.events[upto - 1].1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(log: &InputLog, name: &str) -> InputLog {
+        let path = std::env::temp_dir().join(format!("chip8-{}-{}.log", name, std::process::id()));
+        let path = path.to_str().unwrap();
+        log.save(path).unwrap();
+        let loaded = InputLog::load(path).unwrap();
+        fs::remove_file(path).unwrap();
+        loaded
+    }
+
+    #[test]
+    fn records_the_speed_and_quirks_it_ran_with() {
+        let mut cpu = Cpu::new();
+        cpu.cycles_per_frame = 20;
+        cpu.quirks.shifting = false;
+        cpu.quirks.jumping = true;
+        let mut log = InputLog::for_cpu(7, &cpu);
+        log.record(3, 0x0010);
+        log.record(9, 0);
+
+        let loaded = round_trip(&log, "settings");
+        assert_eq!(loaded.seed, 7);
+        assert_eq!(loaded.keys_at(5), 0x0010);
+        assert_eq!(loaded.keys_at(9), 0);
+
+        let mut playback = Cpu::new();
+        loaded.apply_settings(&mut playback);
+        assert_eq!(playback.cycles_per_frame, 20);
+        assert_eq!(playback.quirks, cpu.quirks);
+    }
+
+    #[test]
+    fn older_logs_keep_the_current_settings() {
+        let mut log = InputLog::new(7);
+        log.record(3, 0x0010);
+        let loaded = round_trip(&log, "plain");
+
+        let mut cpu = Cpu::new();
+        cpu.cycles_per_frame = 33;
+        loaded.apply_settings(&mut cpu);
+        assert_eq!(cpu.cycles_per_frame, 33);
+        assert_eq!(cpu.quirks, Quirks::default());
+    }
+}
@@ -1,15 +1,380 @@
+use crate::memory::{MEMORY_SIZE, PROGRAM_START};
+use crate::{cartridge, hexfile, octo, patch, zip};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
 use std::path::Path;
 
+#[derive(Debug)]
+pub enum RomError {
+    Unreadable(io::Error),
+    Empty,
+    TooLarge {
+        size: usize,
+        capacity: usize,
+    },
+    // Only a warning: CHIP-8 instructions are two bytes, but a trailing data
+    // byte is harmless.
+    OddLength {
+        size: usize,
+    },
+    UnknownFormat(&'static str),
+    // A zip, hex or cartridge file that can't be decoded.
+    BadContainer(String),
+    NoRomInArchive,
+    // The archive holds several ROMs and none, or a missing one, was named.
+    ChooseEntry {
+        wanted: Option<String>,
+        entries: Vec<String>,
+    },
+    BadPatch {
+        path: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for RomError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RomError::Unreadable(e) => write!(f, "could not read the file: {}", e),
+            RomError::Empty => write!(f, "the file is empty"),
+            RomError::TooLarge { size, capacity } => write!(
+                f,
+                "{} bytes is too large, only {} bytes fit in memory after 0x{:03X}",
+                size, capacity, PROGRAM_START
+            ),
+            RomError::OddLength { size } => write!(
+                f,
+                "{} bytes is an odd length, the last byte can't be a whole instruction",
+                size
+            ),
+            RomError::UnknownFormat(format) => {
+                write!(f, "this looks like a {}, not a CHIP-8 program", format)
+            }
+            RomError::BadContainer(message) => write!(f, "{}", message),
+            RomError::NoRomInArchive => write!(f, "the archive doesn't contain a ROM"),
+            RomError::ChooseEntry { wanted, entries } => {
+                match wanted {
+                    Some(name) => write!(f, "the archive has no ROM named '{}'", name)?,
+                    None => write!(f, "the archive holds {} ROMs", entries.len())?,
+                }
+                write!(
+                    f,
+                    ", pick one with <archive>:<name> from: {}",
+                    entries.join(", ")
+                )
+            }
+            RomError::BadPatch { path, message } => {
+                write!(f, "can't apply patch '{}': {}", path, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RomError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RomError::Unreadable(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RomError {
+    fn from(e: io::Error) -> Self {
+        RomError::Unreadable(e)
+    }
+}
+
+// Containers and images people mistake for ROMs, by magic number.
+const KNOWN_FORMATS: [(&[u8], &str); 5] = [
+    (b"PK\x03\x04", "zip archive"),
+    (b"\x1f\x8b", "gzip archive"),
+    (b"7z\xbc\xaf\x27\x1c", "7-Zip archive"),
+    (b"Rar!", "RAR archive"),
+    (b"\x89PNG", "PNG image"),
+];
+
+pub fn capacity() -> usize {
+    MEMORY_SIZE - PROGRAM_START
+}
+
+pub struct Rom {
+    pub name: String,
+    pub data: Vec<u8>,
+    // The IPS or BPS patch applied to `data`, if any.
+    pub patch: Option<String>,
+}
+
+impl Rom {
+    pub fn from_bytes(name: &str, data: Vec<u8>) -> Result<Self, RomError> {
+        if data.is_empty() {
+            return Err(RomError::Empty);
+        }
+        if let Some((_, format)) = KNOWN_FORMATS
+            .iter()
+            .find(|(magic, _)| data.starts_with(magic))
+        {
+            return Err(RomError::UnknownFormat(format));
+        }
+        if data.len() > capacity() {
+            return Err(RomError::TooLarge {
+                size: data.len(),
+                capacity: capacity(),
+            });
+        }
+        Ok(Self {
+            name: name.to_string(),
+            data,
+            patch: None,
+        })
+    }
+
+    pub fn from_reader(name: &str, mut reader: impl Read) -> Result<Self, RomError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(name, data)
+    }
+
+    // `-` reads the ROM from stdin, and `<archive>.zip:<name>` picks a ROM
+    // out of a zip archive.
+    pub fn from_path(path: &str) -> Result<Self, RomError> {
+        let (name, data) = read_program(path)?;
+        Self::from_bytes(&name, data)
+    }
+
+    // Like `from_path`, with `patch_path` or a patch next to the ROM applied.
+    pub fn open(path: &str, patch_path: Option<&str>) -> Result<Self, RomError> {
+        let (name, data) = read_program(path)?;
+        let patch_path = patch_path
+            .map(str::to_string)
+            .or_else(|| sibling_patch(path));
+        let Some(patch_path) = patch_path else {
+            return Self::from_bytes(&name, data);
+        };
+        let mut rom = Self::from_bytes(&name, apply_patch(&data, &patch_path)?)?;
+        rom.patch = Some(patch_path);
+        Ok(rom)
+    }
+
+    pub fn warnings(&self) -> Vec<RomError> {
+        let mut warnings = Vec::new();
+        if !self.data.len().is_multiple_of(2) {
+            warnings.push(RomError::OddLength {
+                size: self.data.len(),
+            });
+        }
+        warnings
+    }
+}
+
+fn has_extension(name: &str, extension: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+pub const ROM_EXTENSIONS: [&str; 6] = ["ch8", "c8", "rom", "hex", "8o", "gif"];
+
+pub fn is_rom_name(name: &str) -> bool {
+    ROM_EXTENSIONS
+        .iter()
+        .any(|extension| has_extension(name, extension))
+}
+
+// Splits `roms.zip:Pong.ch8` into the archive path and the entry name.
+pub fn split_entry(path: &str) -> (&str, Option<&str>) {
+    match path.find(".zip:").or_else(|| path.find(".ZIP:")) {
+        Some(pos) => (&path[..pos + 4], Some(&path[pos + 5..])),
+        None => (path, None),
+    }
+}
+
+// Names of the ROMs in a zip archive. When nothing has a ROM extension, every
+// file counts, for archives of extensionless dumps.
+pub fn archive_roms(data: &[u8]) -> Result<Vec<String>, RomError> {
+    let entries = zip::entries(data).map_err(RomError::BadContainer)?;
+    let files: Vec<String> = entries
+        .iter()
+        .filter(|entry| !entry.is_dir())
+        .map(|entry| entry.name.clone())
+        .collect();
+    let roms: Vec<String> = files
+        .iter()
+        .filter(|name| is_rom_name(name))
+        .cloned()
+        .collect();
+    Ok(if roms.is_empty() { files } else { roms })
+}
+
+// Hex dumps, Octo source and cartridges hold a program in many more bytes
+// than it takes up in memory.
+const MAX_SOURCE_SIZE: usize = 1 << 20;
+
+// The most an archive entry may unpack to: what fits in memory, or more for
+// files that are decoded again.
+fn entry_limit(name: &str) -> usize {
+    if ["hex", "8o", "gif"]
+        .iter()
+        .any(|extension| has_extension(name, extension))
+    {
+        MAX_SOURCE_SIZE
+    } else {
+        capacity()
+    }
+}
+
+// Matches the full path inside the archive or just the file name, ignoring
+// case.
+fn unzip(data: &[u8], wanted: Option<&str>) -> Result<(String, Vec<u8>), RomError> {
+    let roms = archive_roms(data)?;
+    let name = match wanted {
+        Some(wanted) => roms.iter().find(|name| {
+            name.eq_ignore_ascii_case(wanted)
+                || name
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|file| file.eq_ignore_ascii_case(wanted))
+        }),
+        None if roms.len() == 1 => roms.first(),
+        None if roms.is_empty() => return Err(RomError::NoRomInArchive),
+        None => None,
+    };
+    let Some(name) = name else {
+        return Err(RomError::ChooseEntry {
+            wanted: wanted.map(str::to_string),
+            entries: roms,
+        });
+    };
+
+    let entries = zip::entries(data).map_err(RomError::BadContainer)?;
+    let entry = entries.iter().find(|entry| &entry.name == name).unwrap();
+    let contents = zip::extract(data, entry, entry_limit(name)).map_err(RomError::BadContainer)?;
+    Ok((name.clone(), contents))
+}
+
+// Unpacks zip archives, hex dumps, Octo cartridges and Octo source files;
+// anything else is already a program.
+// Returns the program's name, `archive.zip:entry` for archives.
+pub fn unpack(
+    name: &str,
+    data: Vec<u8>,
+    entry: Option<&str>,
+) -> Result<(String, Vec<u8>), RomError> {
+    if zip::is_zip(&data) {
+        let (entry_name, data) = unzip(&data, entry)?;
+        return unpack(&format!("{}:{}", name, entry_name), data, None);
+    }
+    if entry.is_some() {
+        return Err(RomError::BadContainer(format!(
+            "{} is not a zip archive",
+            name
+        )));
+    }
+    if cartridge::is_cartridge(&data) {
+        let program = cartridge::decode(&data)
+            .and_then(|cartridge| cartridge.assemble())
+            .map_err(|e| RomError::BadContainer(format!("Octo cartridge: {}", e)))?;
+        return Ok((name.to_string(), program));
+    }
+    if has_extension(name, "8o") {
+        let source = String::from_utf8_lossy(&data);
+        let program = octo::assemble(&source)
+            .map_err(|e| RomError::BadContainer(format!("Octo source: {}", e)))?;
+        return Ok((name.to_string(), program));
+    }
+    if has_extension(name, "hex") {
+        let text = String::from_utf8_lossy(&data);
+        let program = hexfile::parse(&text).map_err(RomError::BadContainer)?;
+        return Ok((name.to_string(), program));
+    }
+    Ok((name.to_string(), data))
+}
+
+// The unpacked program at `path`, without checking that it fits.
+pub fn read_program(path: &str) -> Result<(String, Vec<u8>), RomError> {
+    let (path, entry) = split_entry(path);
+    unpack(path, read(path)?, entry)
+}
+
+// Settings a ROM file carries itself, as command-line options: the speed,
+// quirks and colours of an Octo cartridge. Files that can't be read have
+// none, and loading them reports the error. Stdin is never read here.
+pub fn embedded_settings(path: &str) -> Vec<String> {
+    let (path, entry) = split_entry(path);
+    if path == "-" {
+        return Vec::new();
+    }
+    let Ok(mut data) = read(path) else {
+        return Vec::new();
+    };
+    if zip::is_zip(&data) {
+        match unzip(&data, entry) {
+            Ok((_, contents)) => data = contents,
+            Err(_) => return Vec::new(),
+        }
+    }
+    if !cartridge::is_cartridge(&data) {
+        return Vec::new();
+    }
+    cartridge::decode(&data)
+        .map(|cartridge| cartridge.settings())
+        .unwrap_or_default()
+}
+
+// `Pong.ips` or `Pong.bps` next to `Pong.ch8`. ROMs in archives and on
+// stdin are only patched with `--patch`.
+pub fn sibling_patch(rom_path: &str) -> Option<String> {
+    if rom_path == "-" || split_entry(rom_path).1.is_some() {
+        return None;
+    }
+    patch::EXTENSIONS
+        .iter()
+        .map(|extension| Path::new(rom_path).with_extension(extension))
+        .find(|path| path.is_file())
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+pub fn apply_patch(program: &[u8], patch_path: &str) -> Result<Vec<u8>, RomError> {
+    let error = |message: String| RomError::BadPatch {
+        path: patch_path.to_string(),
+        message,
+    };
+    let patch_data = fs::read(patch_path).map_err(|e| error(e.to_string()))?;
+    patch::apply(program, &patch_data).map_err(error)
+}
+
+// The raw bytes of a file, or of stdin for `-`, without any checks.
+pub fn read(path: &str) -> Result<Vec<u8>, RomError> {
+    if path == "-" {
+        let mut data = Vec::new();
+        io::stdin().lock().read_to_end(&mut data)?;
+        Ok(data)
+    } else {
+        Ok(fs::read(path)?)
+    }
+}
+
+pub fn load(cpu: &mut crate::cpu::Cpu, rom: &Rom) -> Result<(), RomError> {
+    cpu.reset();
+    cpu.memory.load_program(&rom.data)?;
+    for warning in rom.warnings() {
+        eprintln!("Warning: {}: {}", rom.name, warning);
+    }
+    if let Some(patch_path) = &rom.patch {
+        eprintln!("Applied patch: {}", patch_path);
+    }
+    eprintln!("Loaded ROM: {} ({} bytes)", rom.name, rom.data.len());
+    Ok(())
+}
+
 pub fn load_rom(
     cpu: &mut crate::cpu::Cpu,
     rom_path: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    cpu.reset();
-    cpu.memory.load_rom(rom_path)?;
-    eprintln!(
-        "Loaded ROM: {} ({} bytes)",
-        rom_path,
-        Path::new(rom_path).metadata()?.len()
-    );
-    Ok(())
+    patch_path: Option<&str>,
+) -> Result<Rom, RomError> {
+    let rom = Rom::open(rom_path, patch_path)?;
+    load(cpu, &rom)?;
+    Ok(rom)
 }
@@ -0,0 +1,468 @@
+use crate::checksum;
+
+pub struct Entry {
+    pub title: &'static str,
+    pub author: &'static str,
+}
+
+// SHA-1, title and author of every ROM in the bundled chip8-roms collection.
+const KNOWN_ROMS: [(&str, &str, &str); 106] = [
+    (
+        "cf3a8c546038c63cd4cc1de8d171b9bf0d57c0ee",
+        "15 Puzzle (alt)",
+        "Roger Ivie",
+    ),
+    (
+        "ea9af3c09b0d9e265fcd92bcc5d51a2939fdf27a",
+        "15 Puzzle",
+        "Roger Ivie",
+    ),
+    (
+        "feaa2b999737630a6402e990df4d0558f79ba43e",
+        "Addition Problems",
+        "Paul C. Moews",
+    ),
+    ("fca71182a8838b686573e69b22aff945d79fe1d0", "Airplane", ""),
+    (
+        "a27dcf88a931f70c3ccf3c01a5410b263bac48bc",
+        "Animal Race",
+        "Brian Astle",
+    ),
+    (
+        "ac621d9fcada302ba6965768229ef130630bc525",
+        "Astro Dodge",
+        "Revival Studios, 2008",
+    ),
+    (
+        "3368d56efeb584c509bafb548f1ee5e71ac1bc70",
+        "Biorhythm",
+        "Jef Winsor",
+    ),
+    (
+        "d40abc54374e4343639f993e897e00904ddf85d9",
+        "Blinky",
+        "Hans Christian Egeberg, 1991",
+    ),
+    (
+        "f4169141735d8d60e51409ca7e73f4adedcefef2",
+        "Blinky (alt)",
+        "Hans Christian Egeberg",
+    ),
+    (
+        "6f6509f38220e057a7e32ebb22dd353c1078e3e7",
+        "Blitz",
+        "David Winter",
+    ),
+    (
+        "b3fed4ed1eb0ed693c9731dbe53b29a76236c781",
+        "Bowling",
+        "Gooitzen van der Wal",
+    ),
+    (
+        "237756a4014fb3aa82a29246a7cdd534f8dc2dbb",
+        "Breakout (Brix hack)",
+        "David Winter, 1997",
+    ),
+    (
+        "193915dcde1365ae054c4eaa21a35baa27cd3356",
+        "Breakout",
+        "Carmelo Cortez, 1979",
+    ),
+    (
+        "91442577a6bbf8c3267f2df95fdfc50baebe176d",
+        "Brick (Brix hack, 1990)",
+        "",
+    ),
+    (
+        "f13766c14aeb02ad8d4d103cb5eadd282d20cddc",
+        "Brix",
+        "Andreas Gustafsson, 1990",
+    ),
+    ("5c82520906073287a3ef781746c67207ca084d93", "Cave", ""),
+    (
+        "614a2b3d0bb5d62a16d963ac2d3a79eb3dd22742",
+        "Coin Flipping",
+        "Carmelo Cortez, 1978",
+    ),
+    (
+        "2d10c07b532f4fa7c07a07324ba26ca39fe484fd",
+        "Connect 4",
+        "David Winter",
+    ),
+    (
+        "35158696bd94ea22ef34e899fff1f15f7154d4fd",
+        "Craps",
+        "Camerlo Cortez, 1978",
+    ),
+    (
+        "8e5f19d8ae9f3346779613359610967a5ed95fa8",
+        "Deflection",
+        "John Fort",
+    ),
+    ("3b2bf5dc7ffb5f3fbe168e802079f79730535ca8", "Figures", ""),
+    ("ae71a7b081a947f1760cdc147759803aea45e751", "Filter", ""),
+    (
+        "5260f8931e0e9f41e555b382a14a88368e3ed886",
+        "Guess (alt)",
+        "David Winter",
+    ),
+    (
+        "137cb8397456f53fcab216124458238bc18c0965",
+        "Guess",
+        "David Winter",
+    ),
+    (
+        "dbb52193db4063149c3d8768ab47dd740d90955c",
+        "Hi-Lo",
+        "Jef Winsor, 1978",
+    ),
+    (
+        "050f07a54371da79f924dd0227b89d07b4f2aed0",
+        "Hidden",
+        "David Winter, 1996",
+    ),
+    (
+        "fc724ae0125f5f1ac94a79fe3afc6318b1f57556",
+        "Kaleidoscope",
+        "Joseph Weisbecker, 1978",
+    ),
+    ("72fb3e0a4572bdb81f484df7948a8bc736fe78d0", "Landing", ""),
+    (
+        "72e8f3a10a32bd7fb91322ecab87249f95e81e57",
+        "Lunar Lander (Udo Pernisz, 1979)",
+        "",
+    ),
+    (
+        "669e32b6f42f52da658e428f501aabcdfa37fb2e",
+        "Mastermind FourRow (Robert Lindley, 1978)",
+        "",
+    ),
+    (
+        "d979858bb9ffd07b48f52f92a8bcac0199f3623e",
+        "Merlin",
+        "David Winter",
+    ),
+    (
+        "0d0cc129dad3c45ba672f85fec71a668232212cc",
+        "Missile",
+        "David Winter",
+    ),
+    (
+        "fa7c04f68d78e0faf6d136a3babe3943fc2e02f1",
+        "Most Dangerous Game",
+        "Peter Maruhnic",
+    ),
+    (
+        "4031dae5c7545a1adc160a661be36f19fc1d47b2",
+        "Nim",
+        "Carmelo Cortez, 1978",
+    ),
+    ("a18f1e3897416180b32e47ddc82cba9aca2c8d52", "Paddles", ""),
+    (
+        "607c4f7f4e4dce9f99d96b3182bfe7e88bb090ee",
+        "Pong (1 player)",
+        "",
+    ),
+    ("a60611339661e3ab2d8af024ad1da5880a6f8665", "Pong (alt)", ""),
+    (
+        "1830eb401ba8789a477dfcf294873a5479ebcfe8",
+        "Pong 2 (Pong hack)",
+        "David Winter, 1997",
+    ),
+    (
+        "b232ef880bd6060fb45fa6effed7edf0ae95670e",
+        "Pong",
+        "Paul Vervalin, 1990",
+    ),
+    (
+        "726cb39afa7e17725af7fab37d153277d86bff77",
+        "Programmable Spacefighters",
+        "Jef Winsor",
+    ),
+    ("1293db0ccccbe7dd3fc5a09a2abc5d7b175e18e0", "Puzzle", ""),
+    (
+        "ff639eceaf221ae66151a03779b41fae7118d2d8",
+        "Reversi",
+        "Philip Baltzer",
+    ),
+    (
+        "5e70f91ca08e9b9e9de61670492e3db2d7f7d57a",
+        "Rocket Launch",
+        "Jonas Lindstedt",
+    ),
+    (
+        "e2005db6391f589534dd2d63a95b429338bd667c",
+        "Rocket Launcher",
+        "",
+    ),
+    (
+        "3d1d029d6e31206d245c0ba881c0d1f003953bad",
+        "Rocket",
+        "Joseph Weisbecker, 1978",
+    ),
+    (
+        "29a41ab4d0aa3bc0d6a9d2fa71d533fe463344b3",
+        "Rush Hour (alt)",
+        "Hap, 2006",
+    ),
+    (
+        "4639f86beb0a203ae512b85d3b56d813b2dea7b4",
+        "Rush Hour",
+        "Hap, 2006",
+    ),
+    (
+        "24960090b2afc9de2a4cb3ee7daf6a21456bb49b",
+        "Russian Roulette",
+        "Carmelo Cortez, 1978",
+    ),
+    (
+        "448f9d30d2157ab42679b809d4fb0b43d145f74f",
+        "Sequence Shoot",
+        "Joyce Weisbecker",
+    ),
+    (
+        "443550abf646bc7f475ef0466f8e1232ec7474f3",
+        "Shooting Stars",
+        "Philip Baltzer, 1978",
+    ),
+    (
+        "7623fa0fa915979226566b24107360e7537735f4",
+        "Slide",
+        "Joyce Weisbecker",
+    ),
+    ("6df358d77961a0bf21e98876f9f616791cba31e3", "Soccer", ""),
+    (
+        "aa4f1a282bd64a2364102abf5737a4205365a2b4",
+        "Space Flight",
+        "",
+    ),
+    (
+        "ed829190e37815771e7a8c675ba0074996a2ddb0",
+        "Space Intercept",
+        "Joseph Weisbecker, 1978",
+    ),
+    (
+        "f100197f0f2f05b4f3c8c31ab9c2c3930d3e9571",
+        "Space Invaders (alt)",
+        "David Winter",
+    ),
+    (
+        "5c28a5f85289c9d859f95fd5eadbdcb1c30bb08b",
+        "Space Invaders",
+        "David Winter",
+    ),
+    (
+        "1bd92042717c3bc4f7f34cab34be2887145a6704",
+        "Spooky Spot",
+        "Joseph Weisbecker, 1978",
+    ),
+    (
+        "a58ec7cc63707f9e7274026de27c15ec1d9945bd",
+        "Squash",
+        "David Winter",
+    ),
+    (
+        "89aadf7c28bcd1c11e71ad9bd6eeaf0e7be474f3",
+        "Submarine",
+        "Carmelo Cortez, 1978",
+    ),
+    (
+        "83a2f9c8153be955c28e788bd803aa1d25131330",
+        "Sum Fun",
+        "Joyce Weisbecker",
+    ),
+    (
+        "1bdb4ddaa7049266fa3226851f28855a365cfd12",
+        "Syzygy",
+        "Roy Trevino, 1990",
+    ),
+    ("18b9d15f4c159e1f0ed58c2d8ec1d89325d3a3b6", "Tank", ""),
+    (
+        "775e82a36c93f1b41b42eca94b55acbc4a48cebe",
+        "Tapeworm",
+        "JDR, 1999",
+    ),
+    (
+        "5f518084744bf3cb8733f6e5454dfd1634320563",
+        "Tetris",
+        "Fran Dachille, 1991",
+    ),
+    (
+        "429d455a4bc53167942bf6fd934d72b0f648dce3",
+        "Tic-Tac-Toe",
+        "David Winter",
+    ),
+    ("67996195539c0ddcd98533a01dffeec6a53a6da1", "Timebomb", ""),
+    ("a6a6cb2351c20b8f904da07c0ce91bd8161e9317", "Tron", ""),
+    (
+        "bdb92475acfe11bc7814a2f5eade13fcd09b756a",
+        "UFO",
+        "Lutz V, 1992",
+    ),
+    (
+        "ade839585ddeb0e3633177df03c1d91589e629eb",
+        "Vers",
+        "JMN, 1991",
+    ),
+    (
+        "da710f631f8e35534d0b9170bcf892a60f49c43d",
+        "Vertical Brix",
+        "Paul Robson, 1996",
+    ),
+    (
+        "09ce01c54ddddda42ca5cd171f1ffcfd47355d12",
+        "Wall",
+        "David Winter",
+    ),
+    (
+        "d666688a8fce468a7d88b536bc1ef5f35ba12031",
+        "Wipe Off",
+        "Joseph Weisbecker",
+    ),
+    (
+        "a1c1e0e7b01004be3ee77c69030e6b536cb316e6",
+        "Worm V4",
+        "RB-Revival Studios, 2007",
+    ),
+    ("bc158d819890f16f105b8a316eeeefe4a0bad875", "X-Mirror", ""),
+    (
+        "f2e9c480af31a4039af02dd7a2b8d5d1f859704d",
+        "ZeroPong",
+        "zeroZshadow, 2007",
+    ),
+    (
+        "8b70080adbac44513ec60005734a816372b845ec",
+        "Maze (alt)",
+        "David Winter, 199x",
+    ),
+    (
+        "b9272ae1acdaaa79ab649f6b48b72088ca2b1d74",
+        "Maze",
+        "David Winter, 199x",
+    ),
+    (
+        "507e7dc6783565071dfe4b72154af431d4466958",
+        "Particle Demo",
+        "zeroZshadow, 2008",
+    ),
+    (
+        "a0073e944d5ae9ca14324543fdf818907de80449",
+        "Sierpinski",
+        "Sergey Naydenov, 2010",
+    ),
+    (
+        "0085dd8fce4f7ac2e39ba73cf67cc043f9ba4812",
+        "Stars",
+        "Sergey Naydenov, 2010",
+    ),
+    (
+        "032408f1f1d8e6058ecf0f23f421783c87701b39",
+        "Trip8 Demo (2008)",
+        "Revival Studios",
+    ),
+    (
+        "09f47bea104b86169b9aeb3bdee6e26315ed0a53",
+        "Zero Demo",
+        "zeroZshadow, 2007",
+    ),
+    (
+        "72c2cbfea48000e25891dd4968ae9f1adef1e7e3",
+        "BMP Viewer - Hello (C8 example)",
+        "Hap, 2005",
+    ),
+    (
+        "a82ca5c53e1dcedfab4f65efef02229145771b7d",
+        "Chip8 Picture",
+        "",
+    ),
+    (
+        "d92c71b955b7634370571bd707715cf8bb0e2fb4",
+        "Chip8 emulator Logo",
+        "Garstyciuks",
+    ),
+    (
+        "016345d75eef34448840845a9590d41e6bfdf46a",
+        "Clock Program",
+        "Bill Fisher, 1981",
+    ),
+    (
+        "082c71b67e36e033c2e615ad89ba4ed5d55a56d0",
+        "Delay Timer Test",
+        "Matthew Mikolay, 2010",
+    ),
+    (
+        "064492173cf4ccac3cce8fe307fc164b397013b9",
+        "Division Test",
+        "Sergey Naydenov, 2010",
+    ),
+    (
+        "49c7234a1733db355560a13c57b26f055533c233",
+        "Fishie",
+        "Hap, 2005",
+    ),
+    (
+        "ac7c8db7865beb22c9ec9001c9c0319e02f5d5c2",
+        "Framed MK1",
+        "GV Samways, 1980",
+    ),
+    (
+        "eb72a25bd58e122e65a540807e7a1816abaa4f41",
+        "Framed MK2",
+        "GV Samways, 1980",
+    ),
+    ("1ba58656810b67fd131eb9af3e3987863bf26c90", "IBM Logo", ""),
+    (
+        "5b29263763be401c31d805bc35a4cd211d552881",
+        "Jumping X and O",
+        "Harry Kleinberg, 1977",
+    ),
+    (
+        "0ebc4b92c6059d6193565644fb00108161d03d23",
+        "Keypad Test",
+        "Hap, 2006",
+    ),
+    (
+        "efa6bc8f1f35baaa16700d68a83dc4919797e2fe",
+        "Life",
+        "GV Samways, 1980",
+    ),
+    (
+        "4a4123320d841ed04d8c1cd2ad6132a06b83dfa0",
+        "Minimal game",
+        "Revival Studios, 2007",
+    ),
+    (
+        "f1e036fb93b482b1ddfcb2bc1a4de43c8cf51def",
+        "Random Number Test",
+        "Matthew Mikolay, 2010",
+    ),
+    (
+        "2dbb5b53121ec84cb2377fcb645e57cc8b5eaa09",
+        "SQRT Test",
+        "Sergey Naydenov, 2010",
+    ),
+    (
+        "30f27e5cee5b325fd1681ee98a14de60bfbe951f",
+        "1-chip8-logo",
+        "",
+    ),
+    ("b9bbc12cee3f7b9d3b1f69161f7d7a2d86953379", "2-ibm-logo", ""),
+    ("b2dacf6d85785d6c2315ce449912c8a8a5954e2e", "3-corax+", ""),
+    ("55a6716dacc2f93dce3d39fb8d231083016a1cc0", "4-flags", ""),
+    ("e2149cb836131a142ca7e2dc2f2283381ae5faaa", "5-quirks", ""),
+    ("455b9fc69cc06e2b5b72f7d1ac5f6c86ac349e77", "6-keypad", ""),
+    ("b119651b5aa08557a85ca2ad5de3d1a86796b66b", "7-beep", ""),
+    (
+        "477b3e09c43839ea5478b4f0e24536edab594f89",
+        "8-scrolling",
+        "",
+    ),
+];
+
+pub fn lookup(program: &[u8]) -> Option<Entry> {
+    let sha1 = checksum::hex(&checksum::sha1(program));
+    KNOWN_ROMS
+        .iter()
+        .find(|(hash, _, _)| *hash == sha1)
+        .map(|&(_, title, author)| Entry { title, author })
+}
@@ -0,0 +1,141 @@
+use crate::display::{Display, Palette, HEIGHT, WIDTH};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+// One palette index per output pixel, each CHIP-8 pixel repeated `scale`
+// times in both directions.
+pub fn scaled_pixels(display: &Display, scale: usize) -> Vec<u8> {
+    let width = WIDTH * scale;
+    let mut pixels = vec![0u8; width * HEIGHT * scale];
+    for (y, row) in pixels.chunks_mut(width).enumerate() {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            *pixel = display.get_pixel(x / scale, y / scale) as u8;
+        }
+    }
+    pixels
+}
+
+pub fn encode_png(display: &Display, palette: &Palette, scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let pixels = scaled_pixels(display, scale);
+    crate::png::encode_indexed(
+        (WIDTH * scale) as u32,
+        (HEIGHT * scale) as u32,
+        &palette.colors(),
+        &pixels,
+    )
+}
+
+pub fn write_file(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    std::fs::write(path, data)
+}
+
+pub fn write_png(
+    path: &Path,
+    display: &Display,
+    palette: &Palette,
+    scale: usize,
+) -> std::io::Result<()> {
+    write_file(path, &encode_png(display, palette, scale))
+}
+
+// `screenshots/<rom> <timestamp>.<extension>`, with a `-2`, `-3`, ... suffix
+// for further captures within the same second.
+pub fn capture_path(rom_path: &str, extension: &str) -> PathBuf {
+    let rom_stem = Path::new(rom_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("chip8");
+
+    let name = format!("{} {}", rom_stem, timestamp());
+    unused_path(Path::new(SCREENSHOT_DIR), &name, extension)
+}
+
+// `<dir>/<name>.<extension>`, or the first `<name>-N.<extension>` from 2 up
+// that doesn't exist yet.
+fn unused_path(dir: &Path, name: &str, extension: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}.{}", name, extension));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.{}", name, n, extension));
+        n += 1;
+    }
+    path
+}
+
+pub fn save(
+    display: &Display,
+    palette: &Palette,
+    scale: usize,
+    rom_path: &str,
+) -> std::io::Result<PathBuf> {
+    let path = capture_path(rom_path, "png");
+    write_png(&path, display, palette, scale)?;
+    Ok(path)
+}
+
+// UTC, formatted as YYYY-MM-DD_HH-MM-SS so names sort chronologically.
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let days = (secs / 86400) as i64;
+    let time = secs % 86400;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm).
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        (time / 60) % 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_in_the_same_second_get_a_suffix() {
+        let dir = std::env::temp_dir().join(format!("chip8-captures-{}", std::process::id()));
+        let name = "Pong 2026-10-19_12-00-00";
+        let mut paths = Vec::new();
+        for _ in 0..3 {
+            let path = unused_path(&dir, name, "png");
+            write_file(&path, b"png").unwrap();
+            paths.push(path);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            paths,
+            [
+                dir.join("Pong 2026-10-19_12-00-00.png"),
+                dir.join("Pong 2026-10-19_12-00-00-2.png"),
+                dir.join("Pong 2026-10-19_12-00-00-3.png"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use crate::memory::Memory;
+use crate::png;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// Output pixels per sprite pixel in PNG exports.
+const PNG_SCALE: usize = 4;
+// Background, unlit, lit, and the frame around sprites `DRW` used.
+const PNG_PALETTE: [[u8; 3]; 4] = [
+    [0x30, 0x30, 0x30],
+    [0x00, 0x00, 0x00],
+    [0xFF, 0xFF, 0xFF],
+    [0xFF, 0x80, 0x00],
+];
+// Sprite pixels across a line of the text grid and the PNG sheet.
+const GRID_WIDTH: usize = 80;
+
+// Every `I`/`n` pair `DRW` used, and how often. `n` = 0 is a SCHIP 16x16
+// sprite.
+#[derive(Clone, Default)]
+pub struct SpriteLog {
+    pub draws: BTreeMap<(u16, u8), u64>,
+}
+
+impl SpriteLog {
+    pub fn record(&mut self, index: u16, n: u8) {
+        *self.draws.entry((index, n)).or_default() += 1;
+    }
+
+    // How many draws read any of the sprite's bytes.
+    pub fn uses(&self, sprite: &Sprite) -> u64 {
+        let (start, end) = (
+            sprite.address as usize,
+            sprite.address as usize + sprite.len(),
+        );
+        self.draws
+            .iter()
+            .map(|(&(index, n), &count)| (Sprite::drawn(index, n), count))
+            .filter(|(drawn, _)| {
+                (drawn.address as usize) < end && start < drawn.address as usize + drawn.len()
+            })
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Sprite {
+    pub address: u16,
+    // 8, or 16 for a SCHIP 16x16 sprite.
+    pub width: u8,
+    pub height: u8,
+}
+
+impl Sprite {
+    // What `DRW` with `I` = `index` draws.
+    pub fn drawn(index: u16, n: u8) -> Self {
+        let (width, height) = if n == 0 { (16, 16) } else { (8, n) };
+        Self {
+            address: index,
+            width,
+            height,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.width as usize / 8 * self.height as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Each row's bits, left to right.
+    pub fn rows(&self, memory: &Memory) -> Vec<Vec<bool>> {
+        let bytes = memory.bytes(self.address, self.len());
+        let row_bytes = self.width as usize / 8;
+        (0..self.height as usize)
+            .map(|row| {
+                (0..self.width as usize)
+                    .map(|x| {
+                        let byte = bytes.get(row * row_bytes + x / 8).copied().unwrap_or(0);
+                        byte & (0x80 >> (x % 8)) != 0
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn art(row: &[bool]) -> String {
+    row.iter().map(|&lit| if lit { '#' } else { '.' }).collect()
+}
+
+// Sprites of one shape laid end to end from `start` up to `end`.
+pub fn slice(start: u16, end: u16, width: u8, height: u8) -> Vec<Sprite> {
+    let shape = Sprite {
+        address: start,
+        width,
+        height,
+    };
+    (start..end)
+        .step_by(shape.len().max(1))
+        .map(|address| Sprite { address, ..shape })
+        .collect()
+}
+
+// The sprites drawn as text, several to a line, each under its address with
+// a `*` when `DRW` used it.
+pub fn grid(sprites: &[Sprite], memory: &Memory, log: &SpriteLog) -> String {
+    let mut out = String::new();
+    let Some(widest) = sprites.iter().map(|sprite| sprite.width).max() else {
+        return out;
+    };
+    let cell = widest as usize + 2;
+    for line in sprites.chunks((GRID_WIDTH / cell).max(1)) {
+        let rows: Vec<_> = line.iter().map(|sprite| sprite.rows(memory)).collect();
+        let headers: Vec<String> = line
+            .iter()
+            .map(|sprite| {
+                let mark = if log.uses(sprite) > 0 { "*" } else { "" };
+                format!("{:<cell$}", format!("0x{:03X}{}", sprite.address, mark))
+            })
+            .collect();
+        let _ = writeln!(out, "{}", headers.concat().trim_end());
+        let height = line.iter().map(|sprite| sprite.height).max().unwrap_or(0);
+        for y in 0..height as usize {
+            let cells: Vec<String> = rows
+                .iter()
+                .map(|rows| {
+                    format!(
+                        "{:<cell$}",
+                        rows.get(y).map_or(String::new(), |row| art(row))
+                    )
+                })
+                .collect();
+            let _ = writeln!(out, "{}", cells.concat().trim_end());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// Every distinct sprite `DRW` used, busiest first.
+pub fn drawn(log: &SpriteLog) -> Vec<(Sprite, u64)> {
+    let mut drawn: Vec<_> = log
+        .draws
+        .iter()
+        .map(|(&(index, n), &count)| (Sprite::drawn(index, n), count))
+        .collect();
+    drawn.sort_by_key(|&(sprite, count)| (std::cmp::Reverse(count), sprite.address));
+    drawn
+}
+
+// Assembler source for the sprites: a label and one `db` line per row, with
+// the row drawn in the comment.
+pub fn db_lines(sprites: &[Sprite], memory: &Memory, log: &SpriteLog) -> String {
+    let mut out = String::new();
+    for sprite in sprites {
+        let _ = writeln!(
+            out,
+            "; 0x{:03X}, {}x{}, drawn {} time(s)",
+            sprite.address,
+            sprite.width,
+            sprite.height,
+            log.uses(sprite)
+        );
+        let _ = writeln!(out, "sprite_{:03X}:", sprite.address);
+        let bytes = memory.bytes(sprite.address, sprite.len());
+        let row_bytes = sprite.width as usize / 8;
+        for (row, bits) in bytes.chunks(row_bytes).zip(sprite.rows(memory)) {
+            let values: Vec<String> = row.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+            let _ = writeln!(out, "    db {}  ; {}", values.join(", "), art(&bits));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// The sprites as a PNG sheet, each framed, in orange if `DRW` used it.
+pub fn png_sheet(sprites: &[Sprite], memory: &Memory, log: &SpriteLog) -> Vec<u8> {
+    let cell_width = sprites.iter().map(|s| s.width as usize).max().unwrap_or(8) + 2;
+    let cell_height = sprites.iter().map(|s| s.height as usize).max().unwrap_or(1) + 2;
+    let columns = (GRID_WIDTH / cell_width).clamp(1, sprites.len().max(1));
+    let rows = sprites.len().div_ceil(columns).max(1);
+    let width = columns * cell_width;
+    let mut pixels = vec![0u8; width * rows * cell_height];
+
+    for (i, sprite) in sprites.iter().enumerate() {
+        let left = i % columns * cell_width;
+        let top = i / columns * cell_height;
+        let frame = if log.uses(sprite) > 0 { 3 } else { 0 };
+        for y in 0..sprite.height as usize + 2 {
+            for x in 0..sprite.width as usize + 2 {
+                pixels[(top + y) * width + left + x] = frame;
+            }
+        }
+        for (y, row) in sprite.rows(memory).iter().enumerate() {
+            for (x, &lit) in row.iter().enumerate() {
+                pixels[(top + y + 1) * width + left + x + 1] = 1 + lit as u8;
+            }
+        }
+    }
+
+    let scaled_width = width * PNG_SCALE;
+    let scaled: Vec<u8> = (0..rows * cell_height * PNG_SCALE)
+        .flat_map(|y| (0..scaled_width).map(move |x| (x, y)))
+        .map(|(x, y)| pixels[y / PNG_SCALE * width + x / PNG_SCALE])
+        .collect();
+    png::encode_indexed(
+        scaled_width as u32,
+        (rows * cell_height * PNG_SCALE) as u32,
+        &PNG_PALETTE,
+        &scaled,
+    )
+}
+
+// Which sprites to export.
+pub enum Selection {
+    // Everything in the range.
+    Range,
+    // Everything `DRW` used.
+    Drawn,
+    // Addresses, each with a height or the range's.
+    Sprites(Vec<(u16, Option<u8>)>),
+}
+
+pub struct SpriteOptions {
+    pub rom_path: String,
+    // Inclusive; the whole program when not given.
+    pub range: Option<(u16, u16)>,
+    pub width: u8,
+    pub height: u8,
+    pub replays: Vec<String>,
+    pub frames: Option<u32>,
+    pub select: Selection,
+    pub png_path: Option<String>,
+    pub db: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(sprites: &[Sprite]) -> Vec<u16> {
+        sprites.iter().map(|sprite| sprite.address).collect()
+    }
+
+    // Width and height from the PNG's IHDR chunk.
+    fn png_size(png: &[u8]) -> (u32, u32) {
+        assert_eq!(&png[12..16], b"IHDR");
+        (
+            u32::from_be_bytes(png[16..20].try_into().unwrap()),
+            u32::from_be_bytes(png[20..24].try_into().unwrap()),
+        )
+    }
+
+    #[test]
+    fn slice_steps_by_the_sprite_size_and_stops_before_end() {
+        assert_eq!(addresses(&slice(0x200, 0x20A, 8, 5)), [0x200, 0x205]);
+        // A sprite starting before `end` is kept even if it runs past it.
+        assert_eq!(addresses(&slice(0x200, 0x20B, 8, 5)), [0x200, 0x205, 0x20A]);
+        assert_eq!(addresses(&slice(0x200, 0x240, 16, 16)), [0x200, 0x220]);
+        assert!(slice(0x200, 0x200, 8, 5).is_empty());
+        let sprites = slice(0x300, 0x302, 8, 1);
+        assert_eq!(
+            sprites[1],
+            Sprite {
+                address: 0x301,
+                width: 8,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn wide_draws_cover_thirty_two_bytes() {
+        let mut log = SpriteLog::default();
+        log.record(0x300, 0);
+        log.record(0x300, 0);
+        log.record(0x340, 3);
+        let uses = |address, height| {
+            log.uses(&Sprite {
+                address,
+                width: 8,
+                height,
+            })
+        };
+        assert_eq!(uses(0x31F, 5), 2);
+        assert_eq!(uses(0x320, 5), 0);
+        assert_eq!(uses(0x2FC, 5), 2);
+        assert_eq!(uses(0x2FB, 5), 0);
+        assert_eq!(uses(0x342, 1), 1);
+        assert_eq!(log.uses(&Sprite::drawn(0x31F, 0)), 2);
+        assert_eq!(log.uses(&Sprite::drawn(0x321, 0)), 1);
+    }
+
+    #[test]
+    fn db_lines_for_a_font_digit() {
+        let memory = crate::cpu::Cpu::new().memory;
+        let mut log = SpriteLog::default();
+        log.record(0x000, 5);
+        log.record(0x000, 5);
+        let expected = "\
+; 0x000, 8x5, drawn 2 time(s)
+sprite_000:
+    db 0xF0  ; ####....
+    db 0x90  ; #..#....
+    db 0x90  ; #..#....
+    db 0x90  ; #..#....
+    db 0xF0  ; ####....
+
+";
+        assert_eq!(
+            db_lines(&slice(0x000, 0x005, 8, 5), &memory, &log),
+            expected
+        );
+    }
+
+    #[test]
+    fn png_sheet_size_follows_the_sprites() {
+        let memory = Memory::new();
+        let log = SpriteLog::default();
+        // 18x18 cells, four to a line, in three lines.
+        let wide = slice(0x200, 0x200 + 10 * 32, 16, 16);
+        assert_eq!(wide.len(), 10);
+        let scale = PNG_SCALE as u32;
+        assert_eq!(
+            png_size(&png_sheet(&wide, &memory, &log)),
+            (4 * 18 * scale, 3 * 18 * scale)
+        );
+        // Two 10x7 cells on one line.
+        let narrow = slice(0x200, 0x20A, 8, 5);
+        assert_eq!(
+            png_size(&png_sheet(&narrow, &memory, &log)),
+            (2 * 10 * scale, 7 * scale)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::cpu::Cpu;
+use crate::display::{HEIGHT, WIDTH};
+use crate::memory::MEMORY_SIZE;
+use std::fs;
+
+const MAGIC: &[u8; 4] = b"C8ST";
+const VERSION: u8 = 1;
+
+// Binary layout after the magic and version byte: V0-VF, I, PC, DT, ST, the
+// stack (length byte then big-endian entries), all of memory, then the
+// display one bit per pixel, row by row. The RNG and keypad aren't saved.
+pub fn encode(cpu: &Cpu) -> Vec<u8> {
+    let mut data = Vec::with_capacity(4200);
+    data.extend_from_slice(MAGIC);
+    data.push(VERSION);
+    data.extend_from_slice(&cpu.registers);
+    data.extend_from_slice(&cpu.index.to_be_bytes());
+    data.extend_from_slice(&cpu.program_counter.to_be_bytes());
+    data.push(cpu.delay_timer);
+    data.push(cpu.sound_timer);
+
+    data.push(cpu.stack.len() as u8);
+    for addr in &cpu.stack {
+        data.extend_from_slice(&addr.to_be_bytes());
+    }
+
+    data.extend((0..MEMORY_SIZE as u16).map(|addr| cpu.memory.read(addr)));
+
+    for y in 0..HEIGHT {
+        for byte in 0..WIDTH / 8 {
+            let bits = (0..8).fold(0u8, |bits, bit| {
+                bits << 1 | cpu.display.get_pixel(byte * 8 + bit, y) as u8
+            });
+            data.push(bits);
+        }
+    }
+
+    data
+}
+
+pub fn decode(cpu: &mut Cpu, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut rest = data.strip_prefix(MAGIC).ok_or("not a CHIP-8 save state")?;
+    let mut take = |len: usize| -> Result<&[u8], Box<dyn std::error::Error>> {
+        if rest.len() < len {
+            return Err("save state is truncated".into());
+        }
+        let (head, tail) = rest.split_at(len);
+        rest = tail;
+        Ok(head)
+    };
+
+    let version = take(1)?[0];
+    if version != VERSION {
+        return Err(format!("unsupported save state version {}", version).into());
+    }
+
+    let registers: [u8; 16] = take(16)?.try_into()?;
+    let index = u16::from_be_bytes(take(2)?.try_into()?);
+    let program_counter = u16::from_be_bytes(take(2)?.try_into()?);
+    let timers = take(2)?;
+    let (delay_timer, sound_timer) = (timers[0], timers[1]);
+
+    let depth = take(1)?[0] as usize;
+    let stack: Vec<u16> = take(depth * 2)?
+        .chunks(2)
+        .map(|addr| u16::from_be_bytes([addr[0], addr[1]]))
+        .collect();
+
+    let memory = take(MEMORY_SIZE)?.to_vec();
+    let pixels = take(WIDTH * HEIGHT / 8)?.to_vec();
+
+    cpu.registers = registers;
+    cpu.index = index;
+    cpu.program_counter = program_counter;
+    cpu.delay_timer = delay_timer;
+    cpu.sound_timer = sound_timer;
+    cpu.stack = stack;
+    for (addr, &byte) in memory.iter().enumerate() {
+        cpu.memory.write(addr as u16, byte);
+    }
+    for (i, &bits) in pixels.iter().enumerate() {
+        let (y, x) = (i / (WIDTH / 8), i % (WIDTH / 8) * 8);
+        for bit in 0..8 {
+            cpu.display.set_pixel(x + bit, y, bits & (0x80 >> bit) != 0);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn save(cpu: &Cpu, path: &str) -> std::io::Result<()> {
+    fs::write(path, encode(cpu))
+}
+
+pub fn load(cpu: &mut Cpu, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    decode(cpu, &fs::read(path)?)
+}
@@ -0,0 +1,58 @@
+use crate::display::{Display, Palette, HEIGHT, WIDTH};
+use std::fmt::Write;
+
+// Each renderer returns a full frame of text for a raw-mode terminal: lines
+// end in "\r\n" and colours are 24-bit ANSI escapes.
+
+fn set_colors(out: &mut String, fg: [u8; 3], bg: [u8; 3]) {
+    let _ = write!(
+        out,
+        "\x1b[38;2;{};{};{};48;2;{};{};{}m",
+        fg[0], fg[1], fg[2], bg[0], bg[1], bg[2]
+    );
+}
+
+// 64x16 cells: the upper half block shows the top pixel in the foreground
+// colour and the bottom pixel in the background colour.
+pub fn render_half_blocks(display: &Display, palette: &Palette) -> String {
+    let colors = palette.colors();
+    let mut out = String::new();
+    for row in 0..HEIGHT / 2 {
+        let mut current = None;
+        for x in 0..WIDTH {
+            let top = display.get_pixel(x, row * 2) as usize;
+            let bottom = display.get_pixel(x, row * 2 + 1) as usize;
+            if current != Some((top, bottom)) {
+                set_colors(&mut out, colors[top], colors[bottom]);
+                current = Some((top, bottom));
+            }
+            out.push('▀');
+        }
+        out.push_str("\x1b[0m\r\n");
+    }
+    out
+}
+
+// 32x8 cells of 2x4 braille dots. Only one colour per cell is possible, so
+// lit pixels are dots on the background colour.
+pub fn render_braille(display: &Display, palette: &Palette) -> String {
+    const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+    let mut out = String::new();
+    for row in 0..HEIGHT / 4 {
+        set_colors(&mut out, palette.foreground, palette.background);
+        for col in 0..WIDTH / 2 {
+            let mut bits = 0;
+            for (dy, dots) in DOTS.iter().enumerate() {
+                for (dx, &dot) in dots.iter().enumerate() {
+                    if display.get_pixel(col * 2 + dx, row * 4 + dy) {
+                        bits |= dot;
+                    }
+                }
+            }
+            out.push(char::from_u32(0x2800 + bits).unwrap_or(' '));
+        }
+        out.push_str("\x1b[0m\r\n");
+    }
+    out
+}
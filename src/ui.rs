@@ -1,13 +1,21 @@
 use crate::cpu::Cpu;
+#[cfg(feature = "gui")]
+use crate::layout::{Layout, Rect};
+#[cfg(feature = "gui")]
+use crate::memory::MEMORY_SIZE;
+#[cfg(feature = "gui")]
+use crate::profile;
+#[cfg(feature = "gui")]
 use raylib::prelude::*;
 
-pub const WINDOW_WIDTH: i32 = 1200;
-pub const WINDOW_HEIGHT: i32 = 800;
-pub const STATUS_BAR_HEIGHT: i32 = 25;
-
-const STATUS_BAR_BOUNDS: Rectangle =
-    Rectangle::new(0.0, 0.0, WINDOW_WIDTH as f32, STATUS_BAR_HEIGHT as f32);
+// Memory as laid out by the heatmap: 128 bytes to a row, the same 2:1 shape
+// as the screen it covers.
+#[cfg(feature = "gui")]
+const HEATMAP_COLUMNS: usize = 128;
+#[cfg(feature = "gui")]
+const HEATMAP_ROWS: usize = MEMORY_SIZE / HEATMAP_COLUMNS;
 
+#[derive(Clone)]
 pub struct UI {
     pub paused: bool,
     pub step_mode: bool,
@@ -21,48 +29,132 @@ impl UI {
         }
     }
 
-    pub fn render_status_bar(&self, d: &mut RaylibDrawHandle, cpu: &Cpu, rom_path: &str) {
-        let pressed_keys = cpu.keyboard.pressed_keys();
-        let mut pressed_text = String::new();
-        for (i, &pressed) in pressed_keys.iter().enumerate() {
-            if pressed {
-                let key_name = match i {
-                    0x0 => "0",
-                    0x1 => "1",
-                    0x2 => "2",
-                    0x3 => "3",
-                    0x4 => "4",
-                    0x5 => "5",
-                    0x6 => "6",
-                    0x7 => "7",
-                    0x8 => "8",
-                    0x9 => "9",
-                    0xA => "A",
-                    0xB => "B",
-                    0xC => "C",
-                    0xD => "D",
-                    0xE => "E",
-                    0xF => "F",
-                    _ => "?",
-                };
-                pressed_text.push_str(key_name);
-                pressed_text.push(' ');
-            }
-        }
-        if pressed_text.is_empty() {
-            pressed_text = "None".to_string();
+    #[cfg(feature = "gui")]
+    pub fn render_status_bar(
+        &self,
+        d: &mut RaylibDrawHandle,
+        bounds: Rect,
+        cpu: &Cpu,
+        rom_path: &str,
+        patched: bool,
+    ) {
+        let status_text = status_text(cpu, rom_path, patched, d.get_fps());
+        let bounds = Rectangle::new(bounds.x, bounds.y, bounds.width, bounds.height);
+        d.gui_status_bar(bounds, &status_text);
+    }
+}
+
+#[cfg(feature = "gui")]
+fn rectangle(rect: Rect) -> Rectangle {
+    Rectangle::new(rect.x, rect.y, rect.width, rect.height)
+}
+
+// Blue for rarely run code through red to yellow for the hottest.
+#[cfg(feature = "gui")]
+fn heat_color(heat: f32) -> Color {
+    let ramp = |from: u8, to: u8, t: f32| (from as f32 + (to as f32 - from as f32) * t) as u8;
+    if heat < 0.5 {
+        let t = heat * 2.0;
+        Color::new(ramp(40, 255, t), ramp(80, 40, t), ramp(255, 40, t), 220)
+    } else {
+        let t = (heat - 0.5) * 2.0;
+        Color::new(255, ramp(40, 240, t), 40, 220)
+    }
+}
+
+// Every byte of memory over the screen, coloured by how often the
+// instruction there ran on a log scale, with the PC outlined. The panel names
+// the hottest address, which is usually a busy-wait loop.
+#[cfg(feature = "gui")]
+pub fn render_heatmap(d: &mut RaylibDrawHandle, layout: &Layout, cpu: &Cpu) {
+    let Some(profile) = &cpu.profile else {
+        return;
+    };
+    let screen = layout.screen;
+    let cell_width = screen.width / HEATMAP_COLUMNS as f32;
+    let cell_height = screen.height / HEATMAP_ROWS as f32;
+    let cell = |address: usize, bytes: f32| {
+        Rectangle::new(
+            screen.x + (address % HEATMAP_COLUMNS) as f32 * cell_width,
+            screen.y + (address / HEATMAP_COLUMNS) as f32 * cell_height,
+            cell_width * bytes,
+            cell_height,
+        )
+    };
+
+    d.draw_rectangle_rec(rectangle(screen), Color::new(0, 0, 0, 200));
+    let Some((hottest, most)) = profile.hottest() else {
+        return;
+    };
+    let scale = (most as f32 + 1.0).ln();
+    for (address, &count) in profile.counts.iter().enumerate() {
+        if count > 0 {
+            let heat = (count as f32 + 1.0).ln() / scale;
+            // Both bytes of the instruction.
+            d.draw_rectangle_rec(cell(address, 2.0), heat_color(heat));
         }
+    }
+    d.draw_rectangle_lines_ex(cell(cpu.program_counter as usize, 2.0), 1.0, Color::WHITE);
 
-        let rom_name = std::path::Path::new(rom_path)
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or(rom_path);
+    let text = format!(
+        "Hottest: 0x{:03X} {} ({:.1}% of {} instructions)",
+        hottest,
+        profile::instruction_at(&cpu.memory, hottest),
+        profile.share(most) * 100.0,
+        profile.cycles
+    );
+    let panel = layout.panel;
+    let size = 20;
+    let y = panel.y + (panel.height - size as f32) / 2.0;
+    d.draw_text(&text, panel.x as i32 + 10, y as i32, size, Color::WHITE);
+}
 
-        let status_text = format!(
-            "ROM: {} | Space: Pause | TAB: Step | I: 0x{:04X} | DT: {:3} | ST: {:3} | FPS: {} | Keys: {} | PC: 0x{:04X}",
-            rom_name, cpu.index, cpu.delay_timer, cpu.sound_timer, d.get_fps(), pressed_text.trim(), cpu.program_counter
-        );
+impl Default for UI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        d.gui_status_bar(STATUS_BAR_BOUNDS, &status_text);
+pub fn status_text(cpu: &Cpu, rom_path: &str, patched: bool, fps: u32) -> String {
+    let pressed_keys = cpu.keyboard.pressed_keys();
+    let mut pressed_text = String::new();
+    for (i, &pressed) in pressed_keys.iter().enumerate() {
+        if pressed {
+            let key_name = match i {
+                0x0 => "0",
+                0x1 => "1",
+                0x2 => "2",
+                0x3 => "3",
+                0x4 => "4",
+                0x5 => "5",
+                0x6 => "6",
+                0x7 => "7",
+                0x8 => "8",
+                0x9 => "9",
+                0xA => "A",
+                0xB => "B",
+                0xC => "C",
+                0xD => "D",
+                0xE => "E",
+                0xF => "F",
+                _ => "?",
+            };
+            pressed_text.push_str(key_name);
+            pressed_text.push(' ');
+        }
+    }
+    if pressed_text.is_empty() {
+        pressed_text = "None".to_string();
     }
+
+    let rom_name = std::path::Path::new(rom_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(rom_path);
+    let patched = if patched { " (patched)" } else { "" };
+
+    format!(
+        "ROM: {}{} | Space: Pause | TAB: Step | I: 0x{:04X} | DT: {:3} | ST: {:3} | FPS: {} | Keys: {} | PC: 0x{:04X}",
+        rom_name, patched, cpu.index, cpu.delay_timer, cpu.sound_timer, fps, pressed_text.trim(), cpu.program_counter
+    )
 }
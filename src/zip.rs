@@ -0,0 +1,259 @@
+use crate::checksum::crc32_update;
+use crate::inflate::inflate;
+
+const LOCAL_HEADER: &[u8] = b"PK\x03\x04";
+const CENTRAL_HEADER: &[u8] = b"PK\x01\x02";
+const END_OF_DIRECTORY: &[u8] = b"PK\x05\x06";
+
+pub struct Entry {
+    pub name: String,
+    method: u16,
+    flags: u16,
+    crc: u32,
+    compressed_size: usize,
+    size: usize,
+    offset: usize,
+}
+
+impl Entry {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+fn u16_at(data: &[u8], pos: usize) -> Result<u16, String> {
+    data.get(pos..pos + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| "truncated archive".to_string())
+}
+
+fn u32_at(data: &[u8], pos: usize) -> Result<u32, String> {
+    data.get(pos..pos + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or_else(|| "truncated archive".to_string())
+}
+
+pub fn is_zip(data: &[u8]) -> bool {
+    data.starts_with(LOCAL_HEADER) || data.starts_with(END_OF_DIRECTORY)
+}
+
+// Reads the central directory. Zip64 and multi-disk archives aren't
+// supported; ROM collections are nowhere near 4 GB.
+pub fn entries(data: &[u8]) -> Result<Vec<Entry>, String> {
+    // The end record is at most 22 bytes plus a 64 KB comment from the end.
+    let search_start = data.len().saturating_sub(22 + 0xFFFF);
+    let end = (search_start..data.len().saturating_sub(21))
+        .rev()
+        .find(|&pos| data[pos..].starts_with(END_OF_DIRECTORY))
+        .ok_or("no zip end of central directory record")?;
+
+    let count = u16_at(data, end + 10)? as usize;
+    let mut pos = u32_at(data, end + 16)? as usize;
+    let mut entries = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        if !data
+            .get(pos..)
+            .is_some_and(|rest| rest.starts_with(CENTRAL_HEADER))
+        {
+            return Err("corrupt zip central directory".to_string());
+        }
+        let name_len = u16_at(data, pos + 28)? as usize;
+        let extra_len = u16_at(data, pos + 30)? as usize;
+        let comment_len = u16_at(data, pos + 32)? as usize;
+        let name = data
+            .get(pos + 46..pos + 46 + name_len)
+            .ok_or("truncated archive")?;
+
+        entries.push(Entry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            flags: u16_at(data, pos + 8)?,
+            method: u16_at(data, pos + 10)?,
+            crc: u32_at(data, pos + 16)?,
+            compressed_size: u32_at(data, pos + 20)? as usize,
+            size: u32_at(data, pos + 24)? as usize,
+            offset: u32_at(data, pos + 42)? as usize,
+        });
+        pos += 46 + name_len + extra_len + comment_len;
+    }
+
+    Ok(entries)
+}
+
+// Refuses entries bigger than `limit`, by their recorded size and by what
+// they actually unpack to.
+pub fn extract(data: &[u8], entry: &Entry, limit: usize) -> Result<Vec<u8>, String> {
+    if entry.flags & 1 != 0 {
+        return Err(format!("{} is encrypted", entry.name));
+    }
+    if entry.size > limit {
+        return Err(format!(
+            "{} is {} bytes, more than the {} allowed",
+            entry.name, entry.size, limit
+        ));
+    }
+    let pos = entry.offset;
+    if !data
+        .get(pos..)
+        .is_some_and(|rest| rest.starts_with(LOCAL_HEADER))
+    {
+        return Err(format!("corrupt local header for {}", entry.name));
+    }
+    let start = pos + 30 + u16_at(data, pos + 26)? as usize + u16_at(data, pos + 28)? as usize;
+    let compressed = data
+        .get(start..start + entry.compressed_size)
+        .ok_or("truncated archive")?;
+
+    let contents = match entry.method {
+        0 => compressed.to_vec(),
+        8 => inflate(compressed, entry.size).map_err(|e| format!("{}: {}", entry.name, e))?,
+        method => {
+            return Err(format!(
+                "{} uses compression method {}, only stored and deflate are supported",
+                entry.name, method
+            ))
+        }
+    };
+
+    if contents.len() != entry.size || crc32_update(0, &contents) != entry.crc {
+        return Err(format!("{} failed its CRC check", entry.name));
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rom::{self, RomError};
+
+    // "PONG PONG PONG PONG" compressed with fixed codes.
+    const DEFLATED: [u8; 9] = [0x0B, 0xF0, 0xF7, 0x73, 0x57, 0x08, 0x40, 0x21, 0x00];
+    const PONG: &[u8] = b"PONG PONG PONG PONG";
+
+    // An archive of (name, method, stored bytes, unpacked contents).
+    fn archive(files: &[(&str, u16, &[u8], &[u8])]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut directory = Vec::new();
+        for &(name, method, stored, contents) in files {
+            let mut fields = Vec::new();
+            fields.extend_from_slice(&0u16.to_le_bytes()); // flags
+            fields.extend_from_slice(&method.to_le_bytes());
+            fields.extend_from_slice(&[0; 4]); // time and date
+            fields.extend_from_slice(&crc32_update(0, contents).to_le_bytes());
+            fields.extend_from_slice(&(stored.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+            fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            fields.extend_from_slice(&0u16.to_le_bytes()); // extra length
+
+            directory.extend_from_slice(CENTRAL_HEADER);
+            directory.extend_from_slice(&[20, 0, 20, 0]); // versions
+            directory.extend_from_slice(&fields);
+            directory.extend_from_slice(&[0; 6]); // comment, disk, internal attributes
+            directory.extend_from_slice(&[0; 4]); // external attributes
+            directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+
+            data.extend_from_slice(LOCAL_HEADER);
+            data.extend_from_slice(&[20, 0]); // version
+            data.extend_from_slice(&fields);
+            data.extend_from_slice(name.as_bytes());
+            data.extend_from_slice(stored);
+        }
+        let directory_start = data.len() as u32;
+        data.extend_from_slice(&directory);
+        data.extend_from_slice(END_OF_DIRECTORY);
+        data.extend_from_slice(&[0; 4]); // disks
+        data.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        data.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        data.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        data.extend_from_slice(&directory_start.to_le_bytes());
+        data.extend_from_slice(&[0; 2]); // comment length
+        data
+    }
+
+    #[test]
+    fn stored_and_deflated_entries() {
+        let data = archive(&[
+            ("stored.ch8", 0, PONG, PONG),
+            ("roms/deflated.ch8", 8, &DEFLATED, PONG),
+        ]);
+        assert!(is_zip(&data));
+        let entries = entries(&data).unwrap();
+        let names: Vec<_> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["stored.ch8", "roms/deflated.ch8"]);
+        for entry in &entries {
+            assert_eq!(extract(&data, entry, 100).unwrap(), PONG);
+        }
+    }
+
+    #[test]
+    fn entries_over_the_limit_are_refused() {
+        let data = archive(&[("deflated.ch8", 8, &DEFLATED, PONG)]);
+        let entry = &entries(&data).unwrap()[0];
+        assert!(extract(&data, entry, PONG.len() - 1).is_err());
+    }
+
+    #[test]
+    fn entries_that_unpack_past_their_size_are_refused() {
+        let mut data = archive(&[("deflated.ch8", 8, &DEFLATED, PONG)]);
+        // Claim fewer bytes than the data inflates to, in the central
+        // directory that `extract` trusts.
+        let directory = data.len() - 22 - 46 - "deflated.ch8".len();
+        data[directory + 24] = 4;
+        let entry = &entries(&data).unwrap()[0];
+        assert!(extract(&data, entry, 100).is_err());
+    }
+
+    #[test]
+    fn corrupt_contents_fail_the_crc_check() {
+        let mut data = archive(&[("stored.ch8", 0, PONG, PONG)]);
+        data[30 + "stored.ch8".len()] = b'X';
+        let entry = &entries(&data).unwrap()[0];
+        assert!(extract(&data, entry, 100).is_err());
+    }
+
+    #[test]
+    fn picks_an_entry_by_path_or_file_name() {
+        let data = archive(&[
+            ("readme.txt", 0, b"hello", b"hello"),
+            ("games/pong.ch8", 8, &DEFLATED, PONG),
+            ("games/blank.ch8", 0, &[0x00, 0xE0], &[0x00, 0xE0]),
+        ]);
+        let (name, program) = rom::unpack("roms.zip", data.clone(), Some("PONG.CH8")).unwrap();
+        assert_eq!(name, "roms.zip:games/pong.ch8");
+        assert_eq!(program, PONG);
+        let (_, program) = rom::unpack("roms.zip", data, Some("games/blank.ch8")).unwrap();
+        assert_eq!(program, [0x00, 0xE0]);
+    }
+
+    #[test]
+    fn a_single_rom_needs_no_name() {
+        let data = archive(&[
+            ("readme.txt", 0, b"hello", b"hello"),
+            ("pong.ch8", 8, &DEFLATED, PONG),
+        ]);
+        let (name, program) = rom::unpack("roms.zip", data, None).unwrap();
+        assert_eq!(name, "roms.zip:pong.ch8");
+        assert_eq!(program, PONG);
+    }
+
+    #[test]
+    fn several_roms_or_a_missing_one_list_the_choices() {
+        let data = archive(&[
+            ("pong.ch8", 8, &DEFLATED, PONG),
+            ("blank.ch8", 0, &[0x00, 0xE0], &[0x00, 0xE0]),
+        ]);
+        for wanted in [None, Some("tetris.ch8")] {
+            match rom::unpack("roms.zip", data.clone(), wanted) {
+                Err(RomError::ChooseEntry {
+                    wanted: found,
+                    entries,
+                }) => {
+                    assert_eq!(found.as_deref(), wanted);
+                    assert_eq!(entries, ["pong.ch8", "blank.ch8"]);
+                }
+                _ => panic!("expected ChooseEntry for {:?}", wanted),
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+// The cached engine must run every bundled ROM exactly as the interpreter
+// does, including programs that rewrite code they have already run.
+
+use chip8_emulator::cli::Options;
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::{lockstep, rom};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+const INSTRUCTIONS: u64 = 20_000;
+
+fn find_roms(dir: &Path, roms: &mut Vec<String>) {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            find_roms(&path, roms);
+        } else if rom::is_rom_name(&path.to_string_lossy()) {
+            roms.push(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+// The CPU `check-engine` starts from, settings from the ROM database
+// included.
+fn cpu(rom_path: &str) -> Cpu {
+    let settings = Options::from_args(&[rom_path.to_string()]).unwrap();
+    let rom = rom::Rom::open(rom_path, None).unwrap();
+    let mut cpu = Cpu::new();
+    cpu.memory.load_program(&rom.data).unwrap();
+    cpu.quirks = settings.quirks;
+    cpu.cycles_per_frame = settings.cycles_per_frame();
+    cpu.seed_rng(1);
+    cpu
+}
+
+fn program(code: &[(u16, &[u8])]) -> Cpu {
+    let mut program = Vec::new();
+    for &(address, bytes) in code {
+        let offset = address as usize - 0x200;
+        if program.len() < offset + bytes.len() {
+            program.resize(offset + bytes.len(), 0);
+        }
+        program[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+    let mut cpu = Cpu::new();
+    cpu.memory.load_program(&program).unwrap();
+    cpu.seed_rng(1);
+    cpu
+}
+
+#[test]
+fn bundled_roms_run_the_same_under_both_engines() {
+    let mut roms = Vec::new();
+    find_roms(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("chip8-roms"),
+        &mut roms,
+    );
+    assert!(!roms.is_empty());
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut failures = Vec::new();
+    for rom_path in &roms {
+        let cpu = cpu(rom_path);
+        let frames = INSTRUCTIONS.div_ceil(cpu.cycles_per_frame.max(1) as u64);
+        match panic::catch_unwind(AssertUnwindSafe(|| lockstep::check(&cpu, frames))) {
+            Ok(Ok(_)) => {}
+            Ok(Err(difference)) => failures.push(format!("{}: {}", rom_path, difference)),
+            // An invalid opcode stops both engines; only the cached engine
+            // stopping on its own is a failure.
+            Err(_) => {
+                let mut interpreter = cpu.clone();
+                let stopped = panic::catch_unwind(AssertUnwindSafe(|| {
+                    for _ in 0..frames {
+                        interpreter.run_frame();
+                    }
+                }))
+                .is_err();
+                if !stopped {
+                    failures.push(format!("{}: the cached engine panicked", rom_path));
+                }
+            }
+        }
+    }
+    panic::set_hook(hook);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn code_rewritten_by_fx55_runs_the_new_instruction() {
+    let cpu = program(&[
+        // ADD VA, 1, rewritten to ADD VA, 5 on the first pass.
+        (0x200, &[0x7A, 0x01]),
+        // SNE VB, 0; JP 0x208; JP 0x206
+        (0x202, &[0x4B, 0x00, 0x12, 0x08, 0x12, 0x06]),
+        // ADD VB, 1; LD V0, 0x7A; LD V1, 5; LD I, 0x200; LD [I], V1; JP 0x200
+        (
+            0x208,
+            &[
+                0x7B, 0x01, 0x60, 0x7A, 0x61, 0x05, 0xA2, 0x00, 0xF1, 0x55, 0x12, 0x00,
+            ],
+        ),
+    ]);
+    lockstep::check(&cpu, 2).unwrap();
+
+    let mut interpreter = cpu.clone();
+    interpreter.run_frame();
+    assert_eq!(interpreter.program_counter, 0x206);
+    assert_eq!(interpreter.registers[0xA], 6);
+}
+
+#[test]
+fn code_rewritten_by_fx33_runs_the_new_instruction() {
+    let cpu = program(&[
+        // LD VA, 42; JP 0x310, rewritten to JP 0x300 by the BCD digits
+        // 0, 4, 2 written from 0x203.
+        (0x200, &[0x6A, 0x2A, 0x13, 0x10]),
+        // LD VB, 1; JP 0x302
+        (0x300, &[0x6B, 0x01, 0x13, 0x02]),
+        // LD I, 0x203; LD B, VA; JP 0x202
+        (0x310, &[0xA2, 0x03, 0xFA, 0x33, 0x12, 0x02]),
+    ]);
+    lockstep::check(&cpu, 2).unwrap();
+
+    let mut interpreter = cpu.clone();
+    interpreter.run_frame();
+    assert_eq!(interpreter.program_counter, 0x302);
+    assert_eq!(interpreter.registers[0xB], 1);
+}
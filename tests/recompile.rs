@@ -0,0 +1,100 @@
+// Recompiled ROMs must draw exactly what the interpreter draws. The modules
+// under recompiled/ are `recompile` output, checked in so they can be built;
+// regenerate them with
+//   cargo run -- recompile "chip8-roms/programs/IBM Logo.ch8" tests/recompiled/ibm_logo.rs
+//   cargo run -- recompile "chip8-roms/tests/3-corax+.ch8" tests/recompiled/corax_plus.rs
+
+use chip8_emulator::cli::Options;
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::display::Display;
+use chip8_emulator::{recompile, state};
+use std::path::Path;
+
+#[path = "recompiled/corax_plus.rs"]
+mod corax_plus;
+#[path = "recompiled/ibm_logo.rs"]
+mod ibm_logo;
+
+const FRAMES: u32 = 300;
+
+struct Recompiled {
+    rom_path: &'static str,
+    rom: &'static [u8],
+    source: &'static str,
+    run_frame: fn(&mut Cpu),
+}
+
+const RECOMPILED: [Recompiled; 2] = [
+    Recompiled {
+        rom_path: "chip8-roms/programs/IBM Logo.ch8",
+        rom: ibm_logo::ROM,
+        source: include_str!("recompiled/ibm_logo.rs"),
+        run_frame: ibm_logo::run_frame,
+    },
+    Recompiled {
+        rom_path: "chip8-roms/tests/3-corax+.ch8",
+        rom: corax_plus::ROM,
+        source: include_str!("recompiled/corax_plus.rs"),
+        run_frame: corax_plus::run_frame,
+    },
+];
+
+fn rom_path(recompiled: &Recompiled) -> String {
+    format!("{}/{}", env!("CARGO_MANIFEST_DIR"), recompiled.rom_path)
+}
+
+// The CPU the interpreter would start with, settings from the ROM database
+// included.
+fn cpu(recompiled: &Recompiled) -> Cpu {
+    let settings = Options::from_args(&[rom_path(recompiled)]).unwrap();
+    let mut cpu = Cpu::new();
+    cpu.memory.load_program(recompiled.rom).unwrap();
+    cpu.quirks = settings.quirks;
+    cpu.cycles_per_frame = settings.cycles_per_frame();
+    cpu.seed_rng(1);
+    cpu
+}
+
+#[test]
+fn recompiled_sources_are_current() {
+    for recompiled in &RECOMPILED {
+        let path = rom_path(recompiled);
+        let program = std::fs::read(&path).unwrap();
+        let name = Path::new(&path).file_name().unwrap().to_string_lossy();
+        assert_eq!(recompiled.rom, program, "{}", recompiled.rom_path);
+        assert!(
+            recompile::recompile(&name, &program) == recompiled.source,
+            "tests/recompiled is out of date for {}; regenerate it",
+            recompiled.rom_path
+        );
+    }
+}
+
+#[test]
+fn recompiled_framebuffers_match_the_interpreter() {
+    for recompiled in &RECOMPILED {
+        let mut interpreter = cpu(recompiled);
+        let mut native = cpu(recompiled);
+        for frame in 1..=FRAMES {
+            interpreter.run_frame();
+            (recompiled.run_frame)(&mut native);
+            assert!(
+                interpreter.display == native.display,
+                "{}: the display differs after frame {}",
+                recompiled.rom_path,
+                frame
+            );
+            assert!(
+                state::encode(&interpreter) == state::encode(&native),
+                "{}: the machine state differs after frame {}",
+                recompiled.rom_path,
+                frame
+            );
+        }
+        assert!(
+            native.display != Display::new(),
+            "{}: nothing was drawn",
+            recompiled.rom_path
+        );
+    }
+}
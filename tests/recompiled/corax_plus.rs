@@ -0,0 +1,3008 @@
+// Recompiled from 3-corax+.ch8 by `chip8_emulator recompile`.
+// Each block runs only while memory still holds the code it was built from.
+
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::disasm::Instruction;
+
+#[rustfmt::skip]
+pub const ROM: &[u8] = &[
+    0x12, 0x0A, 0x60, 0x01, 0x00, 0xEE, 0x60, 0x02, 0x12, 0xA6, 0x00, 0xE0, 0x68, 0x32, 0x6B, 0x1A,
+    0xA4, 0xF1, 0xD8, 0xB4, 0x68, 0x3A, 0xA4, 0xF5, 0xD8, 0xB4, 0x68, 0x02, 0x69, 0x06, 0x6A, 0x0B,
+    0x6B, 0x01, 0x65, 0x2A, 0x66, 0x2B, 0xA4, 0xB5, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA5,
+    0x36, 0x2B, 0xA4, 0xA1, 0xDA, 0xB4, 0x6B, 0x06, 0xA4, 0xB9, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4,
+    0xA4, 0xA1, 0x45, 0x2A, 0xA4, 0xA5, 0xDA, 0xB4, 0x6B, 0x0B, 0xA4, 0xBD, 0xD8, 0xB4, 0xA4, 0xED,
+    0xD9, 0xB4, 0xA4, 0xA1, 0x55, 0x60, 0xA4, 0xA5, 0xDA, 0xB4, 0x6B, 0x10, 0xA4, 0xC5, 0xD8, 0xB4,
+    0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA1, 0x76, 0xFF, 0x46, 0x2A, 0xA4, 0xA5, 0xDA, 0xB4, 0x7B, 0x05,
+    0xA4, 0xCD, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA1, 0x95, 0x60, 0xA4, 0xA5, 0xDA, 0xB4,
+    0x7B, 0x05, 0xA4, 0xAD, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA5, 0x12, 0x90, 0xA4, 0xA1,
+    0xDA, 0xB4, 0x68, 0x12, 0x69, 0x16, 0x6A, 0x1B, 0x6B, 0x01, 0xA4, 0xB1, 0xD8, 0xB4, 0xA4, 0xED,
+    0xD9, 0xB4, 0x60, 0x00, 0x22, 0x02, 0xA4, 0xA5, 0x40, 0x00, 0xA4, 0xA1, 0xDA, 0xB4, 0x7B, 0x05,
+    0xA4, 0xA9, 0xD8, 0xB4, 0xA4, 0xE1, 0xD9, 0xB4, 0xA4, 0xA5, 0x40, 0x02, 0xA4, 0xA1, 0x30, 0x00,
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xA9, 0xD9, 0xB4, 0xA4, 0xA1, 0x65, 0x2A,
+    0x67, 0x00, 0x87, 0x50, 0x47, 0x2A, 0xA4, 0xA5, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4,
+    0xA4, 0xAD, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x0B, 0x67, 0x2A, 0x87, 0x61, 0x47, 0x2B, 0xA4, 0xA5,
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xB1, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x78,
+    0x67, 0x1F, 0x87, 0x62, 0x47, 0x18, 0xA4, 0xA5, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4,
+    0xA4, 0xB5, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x78, 0x67, 0x1F, 0x87, 0x63, 0x47, 0x67, 0xA4, 0xA5,
+    0xDA, 0xB4, 0x68, 0x22, 0x69, 0x26, 0x6A, 0x2B, 0x6B, 0x01, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xB9,
+    0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x8C, 0x67, 0x8C, 0x87, 0x64, 0x47, 0x18, 0xA4, 0xA5, 0xDA, 0xB4,
+    0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xBD, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x8C, 0x67, 0x78,
+    0x87, 0x65, 0x47, 0xEC, 0xA4, 0xA5, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xC5,
+    0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x78, 0x67, 0x8C, 0x87, 0x67, 0x47, 0xEC, 0xA4, 0xA5, 0xDA, 0xB4,
+    0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xC1, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x0F, 0x86, 0x66,
+    0x46, 0x07, 0xA4, 0xA5, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xE1, 0xD9, 0xB4,
+    0xA4, 0xA1, 0x66, 0xE0, 0x86, 0x6E, 0x46, 0xC0, 0xA4, 0xA5, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE5,
+    0xD8, 0xB4, 0xA4, 0xC1, 0xD9, 0xB4, 0xA4, 0x9E, 0xF1, 0x65, 0xA4, 0xA5, 0x30, 0xAA, 0xA4, 0xA1,
+    0x31, 0x55, 0xA4, 0xA1, 0xDA, 0xB4, 0x68, 0x32, 0x69, 0x36, 0x6A, 0x3B, 0x6B, 0x01, 0xA4, 0xE5,
+    0xD8, 0xB4, 0xA4, 0xBD, 0xD9, 0xB4, 0xA4, 0x9E, 0x60, 0x00, 0x61, 0x30, 0xF1, 0x55, 0xA4, 0x9E,
+    0xF0, 0x65, 0x81, 0x00, 0xA4, 0x9F, 0xF0, 0x65, 0xA4, 0xA5, 0x30, 0x30, 0xA4, 0xA1, 0x31, 0x00,
+    0xA4, 0xA1, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE5, 0xD8, 0xB4, 0xA4, 0xB5, 0xD9, 0xB4, 0xA4, 0x9E,
+    0x66, 0x89, 0xF6, 0x33, 0xF2, 0x65, 0xA4, 0xA1, 0x30, 0x01, 0x14, 0x32, 0x31, 0x03, 0x14, 0x32,
+    0x32, 0x07, 0x14, 0x32, 0xA4, 0x9E, 0x66, 0x41, 0xF6, 0x33, 0xF2, 0x65, 0xA4, 0xA1, 0x30, 0x00,
+    0x14, 0x32, 0x31, 0x06, 0x14, 0x32, 0x32, 0x05, 0x14, 0x32, 0xA4, 0x9E, 0x66, 0x04, 0xF6, 0x33,
+    0xF2, 0x65, 0xA4, 0xA1, 0x30, 0x00, 0x14, 0x32, 0x31, 0x00, 0x14, 0x32, 0x32, 0x04, 0x14, 0x32,
+    0xA4, 0xA5, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE5, 0xD8, 0xB4, 0xA4, 0xE1, 0xD9, 0xB4, 0xA4, 0xA1,
+    0x66, 0x04, 0xF6, 0x1E, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE9, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4,
+    0xA4, 0xA5, 0x66, 0xFF, 0x76, 0x0A, 0x36, 0x09, 0xA4, 0xA1, 0x86, 0x66, 0x36, 0x04, 0xA4, 0xA1,
+    0x66, 0xFF, 0x60, 0x0A, 0x86, 0x04, 0x36, 0x09, 0xA4, 0xA1, 0x86, 0x66, 0x36, 0x04, 0xA4, 0xA1,
+    0x66, 0xFF, 0x86, 0x6E, 0x86, 0x66, 0x36, 0x7F, 0xA4, 0xA1, 0x86, 0x66, 0x86, 0x6E, 0x36, 0x7E,
+    0xA4, 0xA1, 0x66, 0x05, 0x76, 0xF6, 0x36, 0xFB, 0xA4, 0xA1, 0x66, 0x05, 0x86, 0x05, 0x36, 0xFB,
+    0xA4, 0xA1, 0x66, 0x05, 0x80, 0x67, 0x30, 0xFB, 0xA4, 0xA1, 0xDA, 0xB4, 0x14, 0x9C, 0xAA, 0x55,
+    0x00, 0x00, 0xA0, 0x40, 0xA0, 0x00, 0xA0, 0xC0, 0x80, 0xE0, 0xA0, 0xA0, 0xE0, 0xC0, 0x40, 0x40,
+    0xE0, 0xE0, 0x20, 0xC0, 0xE0, 0xE0, 0x60, 0x20, 0xE0, 0xA0, 0xE0, 0x20, 0x20, 0xE0, 0xC0, 0x20,
+    0xC0, 0x60, 0x80, 0xE0, 0xE0, 0xE0, 0x20, 0x40, 0x40, 0xE0, 0xE0, 0xA0, 0xE0, 0xE0, 0xE0, 0x20,
+    0xC0, 0x40, 0xA0, 0xE0, 0xA0, 0xC0, 0xE0, 0xA0, 0xE0, 0xE0, 0x80, 0x80, 0xE0, 0xC0, 0xA0, 0xA0,
+    0xC0, 0xE0, 0xC0, 0x80, 0xE0, 0xE0, 0x80, 0xC0, 0x80, 0x00, 0xA0, 0xA0, 0x40, 0xA0, 0x40, 0xA0,
+    0xA0, 0x0A, 0xAE, 0xA2, 0x42, 0x38, 0x08, 0x30, 0xB8,
+];
+
+// Runs `cpu.cycles_per_frame` instructions, then ticks the timers.
+pub fn run_frame(cpu: &mut Cpu) {
+    let mut budget = cpu.cycles_per_frame;
+    while budget > 0 {
+        match cpu.program_counter {
+            0x200 if cpu.memory.bytes(0x200, 2) == BLOCK_200 => block_200(cpu, &mut budget),
+            0x202 if cpu.memory.bytes(0x202, 4) == BLOCK_202 => block_202(cpu, &mut budget),
+            0x20A if cpu.memory.bytes(0x20A, 40) == BLOCK_20A => block_20a(cpu, &mut budget),
+            0x232 if cpu.memory.bytes(0x232, 2) == BLOCK_232 => block_232(cpu, &mut budget),
+            0x234 if cpu.memory.bytes(0x234, 16) == BLOCK_234 => block_234(cpu, &mut budget),
+            0x244 if cpu.memory.bytes(0x244, 2) == BLOCK_244 => block_244(cpu, &mut budget),
+            0x246 if cpu.memory.bytes(0x246, 16) == BLOCK_246 => block_246(cpu, &mut budget),
+            0x256 if cpu.memory.bytes(0x256, 2) == BLOCK_256 => block_256(cpu, &mut budget),
+            0x258 if cpu.memory.bytes(0x258, 18) == BLOCK_258 => block_258(cpu, &mut budget),
+            0x26A if cpu.memory.bytes(0x26A, 2) == BLOCK_26A => block_26a(cpu, &mut budget),
+            0x26C if cpu.memory.bytes(0x26C, 16) == BLOCK_26C => block_26c(cpu, &mut budget),
+            0x27C if cpu.memory.bytes(0x27C, 2) == BLOCK_27C => block_27c(cpu, &mut budget),
+            0x27E if cpu.memory.bytes(0x27E, 16) == BLOCK_27E => block_27e(cpu, &mut budget),
+            0x290 if cpu.memory.bytes(0x290, 22) == BLOCK_290 => block_290(cpu, &mut budget),
+            0x2A6 if cpu.memory.bytes(0x2A6, 4) == BLOCK_2A6 => block_2a6(cpu, &mut budget),
+            0x2AA if cpu.memory.bytes(0x2AA, 2) == BLOCK_2AA => block_2aa(cpu, &mut budget),
+            0x2AC if cpu.memory.bytes(0x2AC, 16) == BLOCK_2AC => block_2ac(cpu, &mut budget),
+            0x2BC if cpu.memory.bytes(0x2BC, 2) == BLOCK_2BC => block_2bc(cpu, &mut budget),
+            0x2BE if cpu.memory.bytes(0x2BE, 2) == BLOCK_2BE => block_2be(cpu, &mut budget),
+            0x2C0 if cpu.memory.bytes(0x2C0, 2) == BLOCK_2C0 => block_2c0(cpu, &mut budget),
+            0x2C2 if cpu.memory.bytes(0x2C2, 20) == BLOCK_2C2 => block_2c2(cpu, &mut budget),
+            0x2D6 if cpu.memory.bytes(0x2D6, 2) == BLOCK_2D6 => block_2d6(cpu, &mut budget),
+            0x2D8 if cpu.memory.bytes(0x2D8, 22) == BLOCK_2D8 => block_2d8(cpu, &mut budget),
+            0x2EE if cpu.memory.bytes(0x2EE, 2) == BLOCK_2EE => block_2ee(cpu, &mut budget),
+            0x2F0 if cpu.memory.bytes(0x2F0, 22) == BLOCK_2F0 => block_2f0(cpu, &mut budget),
+            0x306 if cpu.memory.bytes(0x306, 2) == BLOCK_306 => block_306(cpu, &mut budget),
+            0x308 if cpu.memory.bytes(0x308, 22) == BLOCK_308 => block_308(cpu, &mut budget),
+            0x31E if cpu.memory.bytes(0x31E, 2) == BLOCK_31E => block_31e(cpu, &mut budget),
+            0x320 if cpu.memory.bytes(0x320, 28) == BLOCK_320 => block_320(cpu, &mut budget),
+            0x33C if cpu.memory.bytes(0x33C, 2) == BLOCK_33C => block_33c(cpu, &mut budget),
+            0x33E if cpu.memory.bytes(0x33E, 22) == BLOCK_33E => block_33e(cpu, &mut budget),
+            0x354 if cpu.memory.bytes(0x354, 2) == BLOCK_354 => block_354(cpu, &mut budget),
+            0x356 if cpu.memory.bytes(0x356, 22) == BLOCK_356 => block_356(cpu, &mut budget),
+            0x36C if cpu.memory.bytes(0x36C, 2) == BLOCK_36C => block_36c(cpu, &mut budget),
+            0x36E if cpu.memory.bytes(0x36E, 20) == BLOCK_36E => block_36e(cpu, &mut budget),
+            0x382 if cpu.memory.bytes(0x382, 2) == BLOCK_382 => block_382(cpu, &mut budget),
+            0x384 if cpu.memory.bytes(0x384, 20) == BLOCK_384 => block_384(cpu, &mut budget),
+            0x398 if cpu.memory.bytes(0x398, 2) == BLOCK_398 => block_398(cpu, &mut budget),
+            0x39A if cpu.memory.bytes(0x39A, 20) == BLOCK_39A => block_39a(cpu, &mut budget),
+            0x3AE if cpu.memory.bytes(0x3AE, 2) == BLOCK_3AE => block_3ae(cpu, &mut budget),
+            0x3B0 if cpu.memory.bytes(0x3B0, 2) == BLOCK_3B0 => block_3b0(cpu, &mut budget),
+            0x3B2 if cpu.memory.bytes(0x3B2, 2) == BLOCK_3B2 => block_3b2(cpu, &mut budget),
+            0x3B4 if cpu.memory.bytes(0x3B4, 26) == BLOCK_3B4 => block_3b4(cpu, &mut budget),
+            0x3CE if cpu.memory.bytes(0x3CE, 14) == BLOCK_3CE => block_3ce(cpu, &mut budget),
+            0x3DC if cpu.memory.bytes(0x3DC, 2) == BLOCK_3DC => block_3dc(cpu, &mut budget),
+            0x3DE if cpu.memory.bytes(0x3DE, 2) == BLOCK_3DE => block_3de(cpu, &mut budget),
+            0x3E0 if cpu.memory.bytes(0x3E0, 2) == BLOCK_3E0 => block_3e0(cpu, &mut budget),
+            0x3E2 if cpu.memory.bytes(0x3E2, 18) == BLOCK_3E2 => block_3e2(cpu, &mut budget),
+            0x3F4 if cpu.memory.bytes(0x3F4, 6) == BLOCK_3F4 => block_3f4(cpu, &mut budget),
+            0x3FA if cpu.memory.bytes(0x3FA, 2) == BLOCK_3FA => block_3fa(cpu, &mut budget),
+            0x3FC if cpu.memory.bytes(0x3FC, 2) == BLOCK_3FC => block_3fc(cpu, &mut budget),
+            0x3FE if cpu.memory.bytes(0x3FE, 2) == BLOCK_3FE => block_3fe(cpu, &mut budget),
+            0x400 if cpu.memory.bytes(0x400, 2) == BLOCK_400 => block_400(cpu, &mut budget),
+            0x402 if cpu.memory.bytes(0x402, 2) == BLOCK_402 => block_402(cpu, &mut budget),
+            0x404 if cpu.memory.bytes(0x404, 6) == BLOCK_404 => block_404(cpu, &mut budget),
+            0x40A if cpu.memory.bytes(0x40A, 6) == BLOCK_40A => block_40a(cpu, &mut budget),
+            0x410 if cpu.memory.bytes(0x410, 2) == BLOCK_410 => block_410(cpu, &mut budget),
+            0x412 if cpu.memory.bytes(0x412, 2) == BLOCK_412 => block_412(cpu, &mut budget),
+            0x414 if cpu.memory.bytes(0x414, 2) == BLOCK_414 => block_414(cpu, &mut budget),
+            0x416 if cpu.memory.bytes(0x416, 2) == BLOCK_416 => block_416(cpu, &mut budget),
+            0x418 if cpu.memory.bytes(0x418, 2) == BLOCK_418 => block_418(cpu, &mut budget),
+            0x41A if cpu.memory.bytes(0x41A, 6) == BLOCK_41A => block_41a(cpu, &mut budget),
+            0x420 if cpu.memory.bytes(0x420, 6) == BLOCK_420 => block_420(cpu, &mut budget),
+            0x426 if cpu.memory.bytes(0x426, 2) == BLOCK_426 => block_426(cpu, &mut budget),
+            0x428 if cpu.memory.bytes(0x428, 2) == BLOCK_428 => block_428(cpu, &mut budget),
+            0x42A if cpu.memory.bytes(0x42A, 2) == BLOCK_42A => block_42a(cpu, &mut budget),
+            0x42C if cpu.memory.bytes(0x42C, 2) == BLOCK_42C => block_42c(cpu, &mut budget),
+            0x42E if cpu.memory.bytes(0x42E, 2) == BLOCK_42E => block_42e(cpu, &mut budget),
+            0x430 if cpu.memory.bytes(0x430, 2) == BLOCK_430 => block_430(cpu, &mut budget),
+            0x432 if cpu.memory.bytes(0x432, 38) == BLOCK_432 => block_432(cpu, &mut budget),
+            0x458 if cpu.memory.bytes(0x458, 2) == BLOCK_458 => block_458(cpu, &mut budget),
+            0x45A if cpu.memory.bytes(0x45A, 4) == BLOCK_45A => block_45a(cpu, &mut budget),
+            0x45E if cpu.memory.bytes(0x45E, 2) == BLOCK_45E => block_45e(cpu, &mut budget),
+            0x460 if cpu.memory.bytes(0x460, 8) == BLOCK_460 => block_460(cpu, &mut budget),
+            0x468 if cpu.memory.bytes(0x468, 2) == BLOCK_468 => block_468(cpu, &mut budget),
+            0x46A if cpu.memory.bytes(0x46A, 4) == BLOCK_46A => block_46a(cpu, &mut budget),
+            0x46E if cpu.memory.bytes(0x46E, 2) == BLOCK_46E => block_46e(cpu, &mut budget),
+            0x470 if cpu.memory.bytes(0x470, 8) == BLOCK_470 => block_470(cpu, &mut budget),
+            0x478 if cpu.memory.bytes(0x478, 2) == BLOCK_478 => block_478(cpu, &mut budget),
+            0x47A if cpu.memory.bytes(0x47A, 6) == BLOCK_47A => block_47a(cpu, &mut budget),
+            0x480 if cpu.memory.bytes(0x480, 2) == BLOCK_480 => block_480(cpu, &mut budget),
+            0x482 if cpu.memory.bytes(0x482, 6) == BLOCK_482 => block_482(cpu, &mut budget),
+            0x488 if cpu.memory.bytes(0x488, 2) == BLOCK_488 => block_488(cpu, &mut budget),
+            0x48A if cpu.memory.bytes(0x48A, 6) == BLOCK_48A => block_48a(cpu, &mut budget),
+            0x490 if cpu.memory.bytes(0x490, 2) == BLOCK_490 => block_490(cpu, &mut budget),
+            0x492 if cpu.memory.bytes(0x492, 6) == BLOCK_492 => block_492(cpu, &mut budget),
+            0x498 if cpu.memory.bytes(0x498, 2) == BLOCK_498 => block_498(cpu, &mut budget),
+            0x49A if cpu.memory.bytes(0x49A, 2) == BLOCK_49A => block_49a(cpu, &mut budget),
+            0x49C if cpu.memory.bytes(0x49C, 2) == BLOCK_49C => block_49c(cpu, &mut budget),
+            _ => {
+                cpu.cycle();
+                budget -= 1;
+            }
+        }
+    }
+    cpu.tick_timers();
+}
+
+#[rustfmt::skip]
+const BLOCK_200: &[u8] = &[
+    0x12, 0x0A,
+];
+
+fn block_200(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x200  120A  JP 0x20A
+    *budget -= 1;
+    cpu.program_counter = 0x20A;
+}
+
+#[rustfmt::skip]
+const BLOCK_202: &[u8] = &[
+    0x60, 0x01, 0x00, 0xEE,
+];
+
+fn block_202(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x202  6001  LD V0, 0x01
+    *budget -= 1;
+    cpu.registers[0] = 1;
+    // 0x204  00EE  RET
+    if *budget == 0 {
+        cpu.program_counter = 0x204;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = cpu.stack.pop().unwrap_or(0x206);
+}
+
+#[rustfmt::skip]
+const BLOCK_20A: &[u8] = &[
+    0x00, 0xE0, 0x68, 0x32, 0x6B, 0x1A, 0xA4, 0xF1, 0xD8, 0xB4, 0x68, 0x3A, 0xA4, 0xF5, 0xD8, 0xB4,
+    0x68, 0x02, 0x69, 0x06, 0x6A, 0x0B, 0x6B, 0x01, 0x65, 0x2A, 0x66, 0x2B, 0xA4, 0xB5, 0xD8, 0xB4,
+    0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA5, 0x36, 0x2B,
+];
+
+fn block_20a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x20A  00E0  CLS
+    *budget -= 1;
+    cpu.display.clear();
+    // 0x20C  6832  LD V8, 0x32
+    if *budget == 0 {
+        cpu.program_counter = 0x20C;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[8] = 50;
+    // 0x20E  6B1A  LD VB, 0x1A
+    if *budget == 0 {
+        cpu.program_counter = 0x20E;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 26;
+    // 0x210  A4F1  LD I, 0x4F1
+    if *budget == 0 {
+        cpu.program_counter = 0x210;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4F1;
+    // 0x212  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x212;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x214  683A  LD V8, 0x3A
+    if *budget == 0 {
+        cpu.program_counter = 0x214;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[8] = 58;
+    // 0x216  A4F5  LD I, 0x4F5
+    if *budget == 0 {
+        cpu.program_counter = 0x216;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4F5;
+    // 0x218  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x218;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x21A  6802  LD V8, 0x02
+    if *budget == 0 {
+        cpu.program_counter = 0x21A;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[8] = 2;
+    // 0x21C  6906  LD V9, 0x06
+    if *budget == 0 {
+        cpu.program_counter = 0x21C;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[9] = 6;
+    // 0x21E  6A0B  LD VA, 0x0B
+    if *budget == 0 {
+        cpu.program_counter = 0x21E;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[10] = 11;
+    // 0x220  6B01  LD VB, 0x01
+    if *budget == 0 {
+        cpu.program_counter = 0x220;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 1;
+    // 0x222  652A  LD V5, 0x2A
+    if *budget == 0 {
+        cpu.program_counter = 0x222;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[5] = 42;
+    // 0x224  662B  LD V6, 0x2B
+    if *budget == 0 {
+        cpu.program_counter = 0x224;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 43;
+    // 0x226  A4B5  LD I, 0x4B5
+    if *budget == 0 {
+        cpu.program_counter = 0x226;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4B5;
+    // 0x228  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x228;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x22A  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x22A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x22C  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x22C;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x22E  A4A5  LD I, 0x4A5
+    if *budget == 0 {
+        cpu.program_counter = 0x22E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    // 0x230  362B  SE V6, 0x2B
+    if *budget == 0 {
+        cpu.program_counter = 0x230;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 43 { 0x234 } else { 0x232 };
+}
+
+#[rustfmt::skip]
+const BLOCK_232: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_232(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x232  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x234;
+}
+
+#[rustfmt::skip]
+const BLOCK_234: &[u8] = &[
+    0xDA, 0xB4, 0x6B, 0x06, 0xA4, 0xB9, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA1, 0x45, 0x2A,
+];
+
+fn block_234(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x234  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x236  6B06  LD VB, 0x06
+    if *budget == 0 {
+        cpu.program_counter = 0x236;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 6;
+    // 0x238  A4B9  LD I, 0x4B9
+    if *budget == 0 {
+        cpu.program_counter = 0x238;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4B9;
+    // 0x23A  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x23A;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x23C  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x23C;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x23E  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x23E;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x240  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x240;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x242  452A  SNE V5, 0x2A
+    if *budget == 0 {
+        cpu.program_counter = 0x242;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[5] != 42 { 0x246 } else { 0x244 };
+}
+
+#[rustfmt::skip]
+const BLOCK_244: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_244(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x244  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x246;
+}
+
+#[rustfmt::skip]
+const BLOCK_246: &[u8] = &[
+    0xDA, 0xB4, 0x6B, 0x0B, 0xA4, 0xBD, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA1, 0x55, 0x60,
+];
+
+fn block_246(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x246  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x248  6B0B  LD VB, 0x0B
+    if *budget == 0 {
+        cpu.program_counter = 0x248;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 11;
+    // 0x24A  A4BD  LD I, 0x4BD
+    if *budget == 0 {
+        cpu.program_counter = 0x24A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4BD;
+    // 0x24C  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x24C;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x24E  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x24E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x250  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x250;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x252  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x252;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x254  5560  SE V5, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x254;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[5] == cpu.registers[6] {
+        0x258
+    } else {
+        0x256
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_256: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_256(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x256  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x258;
+}
+
+#[rustfmt::skip]
+const BLOCK_258: &[u8] = &[
+    0xDA, 0xB4, 0x6B, 0x10, 0xA4, 0xC5, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA1, 0x76, 0xFF,
+    0x46, 0x2A,
+];
+
+fn block_258(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x258  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x25A  6B10  LD VB, 0x10
+    if *budget == 0 {
+        cpu.program_counter = 0x25A;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 16;
+    // 0x25C  A4C5  LD I, 0x4C5
+    if *budget == 0 {
+        cpu.program_counter = 0x25C;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C5;
+    // 0x25E  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x25E;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x260  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x260;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x262  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x262;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x264  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x264;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x266  76FF  ADD V6, 0xFF
+    if *budget == 0 {
+        cpu.program_counter = 0x266;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = cpu.registers[6].wrapping_add(255);
+    // 0x268  462A  SNE V6, 0x2A
+    if *budget == 0 {
+        cpu.program_counter = 0x268;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] != 42 { 0x26C } else { 0x26A };
+}
+
+#[rustfmt::skip]
+const BLOCK_26A: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_26a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x26A  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x26C;
+}
+
+#[rustfmt::skip]
+const BLOCK_26C: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xCD, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA1, 0x95, 0x60,
+];
+
+fn block_26c(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x26C  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x26E  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x26E;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x270  A4CD  LD I, 0x4CD
+    if *budget == 0 {
+        cpu.program_counter = 0x270;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4CD;
+    // 0x272  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x272;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x274  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x274;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x276  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x276;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x278  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x278;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x27A  9560  SNE V5, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x27A;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[5] != cpu.registers[6] {
+        0x27E
+    } else {
+        0x27C
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_27C: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_27c(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x27C  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x27E;
+}
+
+#[rustfmt::skip]
+const BLOCK_27E: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xAD, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA5, 0x12, 0x90,
+];
+
+fn block_27e(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x27E  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x280  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x280;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x282  A4AD  LD I, 0x4AD
+    if *budget == 0 {
+        cpu.program_counter = 0x282;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4AD;
+    // 0x284  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x284;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x286  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x286;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x288  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x288;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x28A  A4A5  LD I, 0x4A5
+    if *budget == 0 {
+        cpu.program_counter = 0x28A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    // 0x28C  1290  JP 0x290
+    if *budget == 0 {
+        cpu.program_counter = 0x28C;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = 0x290;
+}
+
+#[rustfmt::skip]
+const BLOCK_290: &[u8] = &[
+    0xDA, 0xB4, 0x68, 0x12, 0x69, 0x16, 0x6A, 0x1B, 0x6B, 0x01, 0xA4, 0xB1, 0xD8, 0xB4, 0xA4, 0xED,
+    0xD9, 0xB4, 0x60, 0x00, 0x22, 0x02,
+];
+
+fn block_290(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x290  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x292  6812  LD V8, 0x12
+    if *budget == 0 {
+        cpu.program_counter = 0x292;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[8] = 18;
+    // 0x294  6916  LD V9, 0x16
+    if *budget == 0 {
+        cpu.program_counter = 0x294;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[9] = 22;
+    // 0x296  6A1B  LD VA, 0x1B
+    if *budget == 0 {
+        cpu.program_counter = 0x296;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[10] = 27;
+    // 0x298  6B01  LD VB, 0x01
+    if *budget == 0 {
+        cpu.program_counter = 0x298;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 1;
+    // 0x29A  A4B1  LD I, 0x4B1
+    if *budget == 0 {
+        cpu.program_counter = 0x29A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4B1;
+    // 0x29C  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x29C;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x29E  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x29E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x2A0  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2A0;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x2A2  6000  LD V0, 0x00
+    if *budget == 0 {
+        cpu.program_counter = 0x2A2;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = 0;
+    // 0x2A4  2202  CALL 0x202
+    if *budget == 0 {
+        cpu.program_counter = 0x2A4;
+        return;
+    }
+    *budget -= 1;
+    cpu.stack.push(0x2A6);
+    cpu.program_counter = 0x202;
+}
+
+#[rustfmt::skip]
+const BLOCK_2A6: &[u8] = &[
+    0xA4, 0xA5, 0x40, 0x00,
+];
+
+fn block_2a6(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2A6  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    // 0x2A8  4000  SNE V0, 0x00
+    if *budget == 0 {
+        cpu.program_counter = 0x2A8;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] != 0 { 0x2AC } else { 0x2AA };
+}
+
+#[rustfmt::skip]
+const BLOCK_2AA: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_2aa(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2AA  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x2AC;
+}
+
+#[rustfmt::skip]
+const BLOCK_2AC: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xA9, 0xD8, 0xB4, 0xA4, 0xE1, 0xD9, 0xB4, 0xA4, 0xA5, 0x40, 0x02,
+];
+
+fn block_2ac(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2AC  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x2AE  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x2AE;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x2B0  A4A9  LD I, 0x4A9
+    if *budget == 0 {
+        cpu.program_counter = 0x2B0;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A9;
+    // 0x2B2  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2B2;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x2B4  A4E1  LD I, 0x4E1
+    if *budget == 0 {
+        cpu.program_counter = 0x2B4;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E1;
+    // 0x2B6  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2B6;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x2B8  A4A5  LD I, 0x4A5
+    if *budget == 0 {
+        cpu.program_counter = 0x2B8;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    // 0x2BA  4002  SNE V0, 0x02
+    if *budget == 0 {
+        cpu.program_counter = 0x2BA;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] != 2 { 0x2BE } else { 0x2BC };
+}
+
+#[rustfmt::skip]
+const BLOCK_2BC: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_2bc(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2BC  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x2BE;
+}
+
+#[rustfmt::skip]
+const BLOCK_2BE: &[u8] = &[
+    0x30, 0x00,
+];
+
+fn block_2be(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2BE  3000  SE V0, 0x00
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] == 0 { 0x2C2 } else { 0x2C0 };
+}
+
+#[rustfmt::skip]
+const BLOCK_2C0: &[u8] = &[
+    0xDA, 0xB4,
+];
+
+fn block_2c0(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2C0  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    cpu.program_counter = 0x2C2;
+}
+
+#[rustfmt::skip]
+const BLOCK_2C2: &[u8] = &[
+    0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xA9, 0xD9, 0xB4, 0xA4, 0xA1, 0x65, 0x2A, 0x67, 0x00,
+    0x87, 0x50, 0x47, 0x2A,
+];
+
+fn block_2c2(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2C2  7B05  ADD VB, 0x05
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x2C4  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x2C4;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x2C6  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2C6;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x2C8  A4A9  LD I, 0x4A9
+    if *budget == 0 {
+        cpu.program_counter = 0x2C8;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A9;
+    // 0x2CA  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2CA;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x2CC  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x2CC;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x2CE  652A  LD V5, 0x2A
+    if *budget == 0 {
+        cpu.program_counter = 0x2CE;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[5] = 42;
+    // 0x2D0  6700  LD V7, 0x00
+    if *budget == 0 {
+        cpu.program_counter = 0x2D0;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = 0;
+    // 0x2D2  8750  LD V7, V5
+    if *budget == 0 {
+        cpu.program_counter = 0x2D2;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = cpu.registers[5];
+    // 0x2D4  472A  SNE V7, 0x2A
+    if *budget == 0 {
+        cpu.program_counter = 0x2D4;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[7] != 42 { 0x2D8 } else { 0x2D6 };
+}
+
+#[rustfmt::skip]
+const BLOCK_2D6: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_2d6(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2D6  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x2D8;
+}
+
+#[rustfmt::skip]
+const BLOCK_2D8: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xAD, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x0B,
+    0x67, 0x2A, 0x87, 0x61, 0x47, 0x2B,
+];
+
+fn block_2d8(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2D8  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x2DA  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x2DA;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x2DC  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x2DC;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x2DE  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2DE;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x2E0  A4AD  LD I, 0x4AD
+    if *budget == 0 {
+        cpu.program_counter = 0x2E0;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4AD;
+    // 0x2E2  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2E2;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x2E4  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x2E4;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x2E6  660B  LD V6, 0x0B
+    if *budget == 0 {
+        cpu.program_counter = 0x2E6;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 11;
+    // 0x2E8  672A  LD V7, 0x2A
+    if *budget == 0 {
+        cpu.program_counter = 0x2E8;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = 42;
+    // 0x2EA  8761  OR V7, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x2EA;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] |= cpu.registers[6];
+    if cpu.quirks.vf_reset {
+        cpu.registers[15] = 0;
+    }
+    // 0x2EC  472B  SNE V7, 0x2B
+    if *budget == 0 {
+        cpu.program_counter = 0x2EC;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[7] != 43 { 0x2F0 } else { 0x2EE };
+}
+
+#[rustfmt::skip]
+const BLOCK_2EE: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_2ee(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2EE  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x2F0;
+}
+
+#[rustfmt::skip]
+const BLOCK_2F0: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xB1, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x78,
+    0x67, 0x1F, 0x87, 0x62, 0x47, 0x18,
+];
+
+fn block_2f0(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x2F0  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x2F2  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x2F2;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x2F4  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x2F4;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x2F6  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2F6;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x2F8  A4B1  LD I, 0x4B1
+    if *budget == 0 {
+        cpu.program_counter = 0x2F8;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4B1;
+    // 0x2FA  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x2FA;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x2FC  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x2FC;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x2FE  6678  LD V6, 0x78
+    if *budget == 0 {
+        cpu.program_counter = 0x2FE;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 120;
+    // 0x300  671F  LD V7, 0x1F
+    if *budget == 0 {
+        cpu.program_counter = 0x300;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = 31;
+    // 0x302  8762  AND V7, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x302;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] &= cpu.registers[6];
+    if cpu.quirks.vf_reset {
+        cpu.registers[15] = 0;
+    }
+    // 0x304  4718  SNE V7, 0x18
+    if *budget == 0 {
+        cpu.program_counter = 0x304;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[7] != 24 { 0x308 } else { 0x306 };
+}
+
+#[rustfmt::skip]
+const BLOCK_306: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_306(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x306  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x308;
+}
+
+#[rustfmt::skip]
+const BLOCK_308: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xB5, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x78,
+    0x67, 0x1F, 0x87, 0x63, 0x47, 0x67,
+];
+
+fn block_308(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x308  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x30A  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x30A;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x30C  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x30C;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x30E  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x30E;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x310  A4B5  LD I, 0x4B5
+    if *budget == 0 {
+        cpu.program_counter = 0x310;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4B5;
+    // 0x312  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x312;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x314  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x314;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x316  6678  LD V6, 0x78
+    if *budget == 0 {
+        cpu.program_counter = 0x316;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 120;
+    // 0x318  671F  LD V7, 0x1F
+    if *budget == 0 {
+        cpu.program_counter = 0x318;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = 31;
+    // 0x31A  8763  XOR V7, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x31A;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] ^= cpu.registers[6];
+    if cpu.quirks.vf_reset {
+        cpu.registers[15] = 0;
+    }
+    // 0x31C  4767  SNE V7, 0x67
+    if *budget == 0 {
+        cpu.program_counter = 0x31C;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[7] != 103 {
+        0x320
+    } else {
+        0x31E
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_31E: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_31e(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x31E  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x320;
+}
+
+#[rustfmt::skip]
+const BLOCK_320: &[u8] = &[
+    0xDA, 0xB4, 0x68, 0x22, 0x69, 0x26, 0x6A, 0x2B, 0x6B, 0x01, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xB9,
+    0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x8C, 0x67, 0x8C, 0x87, 0x64, 0x47, 0x18,
+];
+
+fn block_320(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x320  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x322  6822  LD V8, 0x22
+    if *budget == 0 {
+        cpu.program_counter = 0x322;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[8] = 34;
+    // 0x324  6926  LD V9, 0x26
+    if *budget == 0 {
+        cpu.program_counter = 0x324;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[9] = 38;
+    // 0x326  6A2B  LD VA, 0x2B
+    if *budget == 0 {
+        cpu.program_counter = 0x326;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[10] = 43;
+    // 0x328  6B01  LD VB, 0x01
+    if *budget == 0 {
+        cpu.program_counter = 0x328;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 1;
+    // 0x32A  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x32A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x32C  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x32C;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x32E  A4B9  LD I, 0x4B9
+    if *budget == 0 {
+        cpu.program_counter = 0x32E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4B9;
+    // 0x330  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x330;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x332  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x332;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x334  668C  LD V6, 0x8C
+    if *budget == 0 {
+        cpu.program_counter = 0x334;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 140;
+    // 0x336  678C  LD V7, 0x8C
+    if *budget == 0 {
+        cpu.program_counter = 0x336;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = 140;
+    // 0x338  8764  ADD V7, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x338;
+        return;
+    }
+    *budget -= 1;
+    {
+        let (sum, carry) = cpu.registers[7].overflowing_add(cpu.registers[6]);
+        cpu.registers[7] = sum;
+        cpu.registers[15] = carry as u8;
+    }
+    // 0x33A  4718  SNE V7, 0x18
+    if *budget == 0 {
+        cpu.program_counter = 0x33A;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[7] != 24 { 0x33E } else { 0x33C };
+}
+
+#[rustfmt::skip]
+const BLOCK_33C: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_33c(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x33C  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x33E;
+}
+
+#[rustfmt::skip]
+const BLOCK_33E: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xBD, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x8C,
+    0x67, 0x78, 0x87, 0x65, 0x47, 0xEC,
+];
+
+fn block_33e(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x33E  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x340  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x340;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x342  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x342;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x344  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x344;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x346  A4BD  LD I, 0x4BD
+    if *budget == 0 {
+        cpu.program_counter = 0x346;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4BD;
+    // 0x348  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x348;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x34A  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x34A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x34C  668C  LD V6, 0x8C
+    if *budget == 0 {
+        cpu.program_counter = 0x34C;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 140;
+    // 0x34E  6778  LD V7, 0x78
+    if *budget == 0 {
+        cpu.program_counter = 0x34E;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = 120;
+    // 0x350  8765  SUB V7, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x350;
+        return;
+    }
+    *budget -= 1;
+    {
+        let (a, b) = (cpu.registers[7], cpu.registers[6]);
+        cpu.registers[7] = a.wrapping_sub(b);
+        cpu.registers[15] = (a >= b) as u8;
+    }
+    // 0x352  47EC  SNE V7, 0xEC
+    if *budget == 0 {
+        cpu.program_counter = 0x352;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[7] != 236 {
+        0x356
+    } else {
+        0x354
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_354: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_354(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x354  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x356;
+}
+
+#[rustfmt::skip]
+const BLOCK_356: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xC5, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x78,
+    0x67, 0x8C, 0x87, 0x67, 0x47, 0xEC,
+];
+
+fn block_356(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x356  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x358  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x358;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x35A  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x35A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x35C  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x35C;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x35E  A4C5  LD I, 0x4C5
+    if *budget == 0 {
+        cpu.program_counter = 0x35E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C5;
+    // 0x360  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x360;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x362  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x362;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x364  6678  LD V6, 0x78
+    if *budget == 0 {
+        cpu.program_counter = 0x364;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 120;
+    // 0x366  678C  LD V7, 0x8C
+    if *budget == 0 {
+        cpu.program_counter = 0x366;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[7] = 140;
+    // 0x368  8767  SUBN V7, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x368;
+        return;
+    }
+    *budget -= 1;
+    {
+        let (a, b) = (cpu.registers[6], cpu.registers[7]);
+        cpu.registers[7] = a.wrapping_sub(b);
+        cpu.registers[15] = (a >= b) as u8;
+    }
+    // 0x36A  47EC  SNE V7, 0xEC
+    if *budget == 0 {
+        cpu.program_counter = 0x36A;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[7] != 236 {
+        0x36E
+    } else {
+        0x36C
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_36C: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_36c(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x36C  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x36E;
+}
+
+#[rustfmt::skip]
+const BLOCK_36E: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xC1, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x0F,
+    0x86, 0x66, 0x46, 0x07,
+];
+
+fn block_36e(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x36E  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x370  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x370;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x372  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x372;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x374  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x374;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x376  A4C1  LD I, 0x4C1
+    if *budget == 0 {
+        cpu.program_counter = 0x376;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C1;
+    // 0x378  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x378;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x37A  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x37A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x37C  660F  LD V6, 0x0F
+    if *budget == 0 {
+        cpu.program_counter = 0x37C;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 15;
+    // 0x37E  8666  SHR V6, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x37E;
+        return;
+    }
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v >> 1;
+        cpu.registers[15] = v & 1;
+    }
+    // 0x380  4607  SNE V6, 0x07
+    if *budget == 0 {
+        cpu.program_counter = 0x380;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] != 7 { 0x384 } else { 0x382 };
+}
+
+#[rustfmt::skip]
+const BLOCK_382: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_382(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x382  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x384;
+}
+
+#[rustfmt::skip]
+const BLOCK_384: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xC9, 0xD8, 0xB4, 0xA4, 0xE1, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0xE0,
+    0x86, 0x6E, 0x46, 0xC0,
+];
+
+fn block_384(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x384  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x386  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x386;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x388  A4C9  LD I, 0x4C9
+    if *budget == 0 {
+        cpu.program_counter = 0x388;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C9;
+    // 0x38A  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x38A;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x38C  A4E1  LD I, 0x4E1
+    if *budget == 0 {
+        cpu.program_counter = 0x38C;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E1;
+    // 0x38E  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x38E;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x390  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x390;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x392  66E0  LD V6, 0xE0
+    if *budget == 0 {
+        cpu.program_counter = 0x392;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 224;
+    // 0x394  866E  SHL V6, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x394;
+        return;
+    }
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v << 1;
+        cpu.registers[15] = v >> 7;
+    }
+    // 0x396  46C0  SNE V6, 0xC0
+    if *budget == 0 {
+        cpu.program_counter = 0x396;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] != 192 {
+        0x39A
+    } else {
+        0x398
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_398: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_398(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x398  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x39A;
+}
+
+#[rustfmt::skip]
+const BLOCK_39A: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE5, 0xD8, 0xB4, 0xA4, 0xC1, 0xD9, 0xB4, 0xA4, 0x9E, 0xF1, 0x65,
+    0xA4, 0xA5, 0x30, 0xAA,
+];
+
+fn block_39a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x39A  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x39C  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x39C;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x39E  A4E5  LD I, 0x4E5
+    if *budget == 0 {
+        cpu.program_counter = 0x39E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E5;
+    // 0x3A0  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x3A0;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x3A2  A4C1  LD I, 0x4C1
+    if *budget == 0 {
+        cpu.program_counter = 0x3A2;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4C1;
+    // 0x3A4  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x3A4;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x3A6  A49E  LD I, 0x49E
+    if *budget == 0 {
+        cpu.program_counter = 0x3A6;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x49E;
+    // 0x3A8  F165  LD V1, [I]
+    if *budget == 0 {
+        cpu.program_counter = 0x3A8;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Load(1));
+    // 0x3AA  A4A5  LD I, 0x4A5
+    if *budget == 0 {
+        cpu.program_counter = 0x3AA;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    // 0x3AC  30AA  SE V0, 0xAA
+    if *budget == 0 {
+        cpu.program_counter = 0x3AC;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] == 170 {
+        0x3B0
+    } else {
+        0x3AE
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_3AE: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_3ae(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3AE  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x3B0;
+}
+
+#[rustfmt::skip]
+const BLOCK_3B0: &[u8] = &[
+    0x31, 0x55,
+];
+
+fn block_3b0(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3B0  3155  SE V1, 0x55
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[1] == 85 { 0x3B4 } else { 0x3B2 };
+}
+
+#[rustfmt::skip]
+const BLOCK_3B2: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_3b2(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3B2  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x3B4;
+}
+
+#[rustfmt::skip]
+const BLOCK_3B4: &[u8] = &[
+    0xDA, 0xB4, 0x68, 0x32, 0x69, 0x36, 0x6A, 0x3B, 0x6B, 0x01, 0xA4, 0xE5, 0xD8, 0xB4, 0xA4, 0xBD,
+    0xD9, 0xB4, 0xA4, 0x9E, 0x60, 0x00, 0x61, 0x30, 0xF1, 0x55,
+];
+
+fn block_3b4(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3B4  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x3B6  6832  LD V8, 0x32
+    if *budget == 0 {
+        cpu.program_counter = 0x3B6;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[8] = 50;
+    // 0x3B8  6936  LD V9, 0x36
+    if *budget == 0 {
+        cpu.program_counter = 0x3B8;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[9] = 54;
+    // 0x3BA  6A3B  LD VA, 0x3B
+    if *budget == 0 {
+        cpu.program_counter = 0x3BA;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[10] = 59;
+    // 0x3BC  6B01  LD VB, 0x01
+    if *budget == 0 {
+        cpu.program_counter = 0x3BC;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = 1;
+    // 0x3BE  A4E5  LD I, 0x4E5
+    if *budget == 0 {
+        cpu.program_counter = 0x3BE;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E5;
+    // 0x3C0  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x3C0;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x3C2  A4BD  LD I, 0x4BD
+    if *budget == 0 {
+        cpu.program_counter = 0x3C2;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4BD;
+    // 0x3C4  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x3C4;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x3C6  A49E  LD I, 0x49E
+    if *budget == 0 {
+        cpu.program_counter = 0x3C6;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x49E;
+    // 0x3C8  6000  LD V0, 0x00
+    if *budget == 0 {
+        cpu.program_counter = 0x3C8;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = 0;
+    // 0x3CA  6130  LD V1, 0x30
+    if *budget == 0 {
+        cpu.program_counter = 0x3CA;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[1] = 48;
+    // 0x3CC  F155  LD [I], V1
+    if *budget == 0 {
+        cpu.program_counter = 0x3CC;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Store(1));
+    cpu.program_counter = 0x3CE;
+}
+
+#[rustfmt::skip]
+const BLOCK_3CE: &[u8] = &[
+    0xA4, 0x9E, 0xF0, 0x65, 0x81, 0x00, 0xA4, 0x9F, 0xF0, 0x65, 0xA4, 0xA5, 0x30, 0x30,
+];
+
+fn block_3ce(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3CE  A49E  LD I, 0x49E
+    *budget -= 1;
+    cpu.index = 0x49E;
+    // 0x3D0  F065  LD V0, [I]
+    if *budget == 0 {
+        cpu.program_counter = 0x3D0;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Load(0));
+    // 0x3D2  8100  LD V1, V0
+    if *budget == 0 {
+        cpu.program_counter = 0x3D2;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[1] = cpu.registers[0];
+    // 0x3D4  A49F  LD I, 0x49F
+    if *budget == 0 {
+        cpu.program_counter = 0x3D4;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x49F;
+    // 0x3D6  F065  LD V0, [I]
+    if *budget == 0 {
+        cpu.program_counter = 0x3D6;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Load(0));
+    // 0x3D8  A4A5  LD I, 0x4A5
+    if *budget == 0 {
+        cpu.program_counter = 0x3D8;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    // 0x3DA  3030  SE V0, 0x30
+    if *budget == 0 {
+        cpu.program_counter = 0x3DA;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] == 48 { 0x3DE } else { 0x3DC };
+}
+
+#[rustfmt::skip]
+const BLOCK_3DC: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_3dc(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3DC  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x3DE;
+}
+
+#[rustfmt::skip]
+const BLOCK_3DE: &[u8] = &[
+    0x31, 0x00,
+];
+
+fn block_3de(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3DE  3100  SE V1, 0x00
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[1] == 0 { 0x3E2 } else { 0x3E0 };
+}
+
+#[rustfmt::skip]
+const BLOCK_3E0: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_3e0(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3E0  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x3E2;
+}
+
+#[rustfmt::skip]
+const BLOCK_3E2: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE5, 0xD8, 0xB4, 0xA4, 0xB5, 0xD9, 0xB4, 0xA4, 0x9E, 0x66, 0x89,
+    0xF6, 0x33,
+];
+
+fn block_3e2(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3E2  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x3E4  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x3E4;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x3E6  A4E5  LD I, 0x4E5
+    if *budget == 0 {
+        cpu.program_counter = 0x3E6;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E5;
+    // 0x3E8  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x3E8;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x3EA  A4B5  LD I, 0x4B5
+    if *budget == 0 {
+        cpu.program_counter = 0x3EA;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4B5;
+    // 0x3EC  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x3EC;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x3EE  A49E  LD I, 0x49E
+    if *budget == 0 {
+        cpu.program_counter = 0x3EE;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x49E;
+    // 0x3F0  6689  LD V6, 0x89
+    if *budget == 0 {
+        cpu.program_counter = 0x3F0;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 137;
+    // 0x3F2  F633  LD B, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x3F2;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::LdBcd(6));
+    cpu.program_counter = 0x3F4;
+}
+
+#[rustfmt::skip]
+const BLOCK_3F4: &[u8] = &[
+    0xF2, 0x65, 0xA4, 0xA1, 0x30, 0x01,
+];
+
+fn block_3f4(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3F4  F265  LD V2, [I]
+    *budget -= 1;
+    cpu.execute(Instruction::Load(2));
+    // 0x3F6  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x3F6;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x3F8  3001  SE V0, 0x01
+    if *budget == 0 {
+        cpu.program_counter = 0x3F8;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] == 1 { 0x3FC } else { 0x3FA };
+}
+
+#[rustfmt::skip]
+const BLOCK_3FA: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_3fa(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3FA  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_3FC: &[u8] = &[
+    0x31, 0x03,
+];
+
+fn block_3fc(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3FC  3103  SE V1, 0x03
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[1] == 3 { 0x400 } else { 0x3FE };
+}
+
+#[rustfmt::skip]
+const BLOCK_3FE: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_3fe(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x3FE  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_400: &[u8] = &[
+    0x32, 0x07,
+];
+
+fn block_400(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x400  3207  SE V2, 0x07
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[2] == 7 { 0x404 } else { 0x402 };
+}
+
+#[rustfmt::skip]
+const BLOCK_402: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_402(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x402  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_404: &[u8] = &[
+    0xA4, 0x9E, 0x66, 0x41, 0xF6, 0x33,
+];
+
+fn block_404(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x404  A49E  LD I, 0x49E
+    *budget -= 1;
+    cpu.index = 0x49E;
+    // 0x406  6641  LD V6, 0x41
+    if *budget == 0 {
+        cpu.program_counter = 0x406;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 65;
+    // 0x408  F633  LD B, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x408;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::LdBcd(6));
+    cpu.program_counter = 0x40A;
+}
+
+#[rustfmt::skip]
+const BLOCK_40A: &[u8] = &[
+    0xF2, 0x65, 0xA4, 0xA1, 0x30, 0x00,
+];
+
+fn block_40a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x40A  F265  LD V2, [I]
+    *budget -= 1;
+    cpu.execute(Instruction::Load(2));
+    // 0x40C  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x40C;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x40E  3000  SE V0, 0x00
+    if *budget == 0 {
+        cpu.program_counter = 0x40E;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] == 0 { 0x412 } else { 0x410 };
+}
+
+#[rustfmt::skip]
+const BLOCK_410: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_410(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x410  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_412: &[u8] = &[
+    0x31, 0x06,
+];
+
+fn block_412(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x412  3106  SE V1, 0x06
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[1] == 6 { 0x416 } else { 0x414 };
+}
+
+#[rustfmt::skip]
+const BLOCK_414: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_414(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x414  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_416: &[u8] = &[
+    0x32, 0x05,
+];
+
+fn block_416(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x416  3205  SE V2, 0x05
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[2] == 5 { 0x41A } else { 0x418 };
+}
+
+#[rustfmt::skip]
+const BLOCK_418: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_418(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x418  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_41A: &[u8] = &[
+    0xA4, 0x9E, 0x66, 0x04, 0xF6, 0x33,
+];
+
+fn block_41a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x41A  A49E  LD I, 0x49E
+    *budget -= 1;
+    cpu.index = 0x49E;
+    // 0x41C  6604  LD V6, 0x04
+    if *budget == 0 {
+        cpu.program_counter = 0x41C;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 4;
+    // 0x41E  F633  LD B, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x41E;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::LdBcd(6));
+    cpu.program_counter = 0x420;
+}
+
+#[rustfmt::skip]
+const BLOCK_420: &[u8] = &[
+    0xF2, 0x65, 0xA4, 0xA1, 0x30, 0x00,
+];
+
+fn block_420(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x420  F265  LD V2, [I]
+    *budget -= 1;
+    cpu.execute(Instruction::Load(2));
+    // 0x422  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x422;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x424  3000  SE V0, 0x00
+    if *budget == 0 {
+        cpu.program_counter = 0x424;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] == 0 { 0x428 } else { 0x426 };
+}
+
+#[rustfmt::skip]
+const BLOCK_426: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_426(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x426  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_428: &[u8] = &[
+    0x31, 0x00,
+];
+
+fn block_428(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x428  3100  SE V1, 0x00
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[1] == 0 { 0x42C } else { 0x42A };
+}
+
+#[rustfmt::skip]
+const BLOCK_42A: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_42a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x42A  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_42C: &[u8] = &[
+    0x32, 0x04,
+];
+
+fn block_42c(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x42C  3204  SE V2, 0x04
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[2] == 4 { 0x430 } else { 0x42E };
+}
+
+#[rustfmt::skip]
+const BLOCK_42E: &[u8] = &[
+    0x14, 0x32,
+];
+
+fn block_42e(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x42E  1432  JP 0x432
+    *budget -= 1;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_430: &[u8] = &[
+    0xA4, 0xA5,
+];
+
+fn block_430(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x430  A4A5  LD I, 0x4A5
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    cpu.program_counter = 0x432;
+}
+
+#[rustfmt::skip]
+const BLOCK_432: &[u8] = &[
+    0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE5, 0xD8, 0xB4, 0xA4, 0xE1, 0xD9, 0xB4, 0xA4, 0xA1, 0x66, 0x04,
+    0xF6, 0x1E, 0xDA, 0xB4, 0x7B, 0x05, 0xA4, 0xE9, 0xD8, 0xB4, 0xA4, 0xED, 0xD9, 0xB4, 0xA4, 0xA5,
+    0x66, 0xFF, 0x76, 0x0A, 0x36, 0x09,
+];
+
+fn block_432(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x432  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x434  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x434;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x436  A4E5  LD I, 0x4E5
+    if *budget == 0 {
+        cpu.program_counter = 0x436;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E5;
+    // 0x438  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x438;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x43A  A4E1  LD I, 0x4E1
+    if *budget == 0 {
+        cpu.program_counter = 0x43A;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E1;
+    // 0x43C  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x43C;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x43E  A4A1  LD I, 0x4A1
+    if *budget == 0 {
+        cpu.program_counter = 0x43E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    // 0x440  6604  LD V6, 0x04
+    if *budget == 0 {
+        cpu.program_counter = 0x440;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 4;
+    // 0x442  F61E  ADD I, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x442;
+        return;
+    }
+    *budget -= 1;
+    cpu.index += cpu.registers[6] as u16;
+    // 0x444  DAB4  DRW VA, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x444;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    // 0x446  7B05  ADD VB, 0x05
+    if *budget == 0 {
+        cpu.program_counter = 0x446;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[11] = cpu.registers[11].wrapping_add(5);
+    // 0x448  A4E9  LD I, 0x4E9
+    if *budget == 0 {
+        cpu.program_counter = 0x448;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4E9;
+    // 0x44A  D8B4  DRW V8, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x44A;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(8, 11, 4));
+    // 0x44C  A4ED  LD I, 0x4ED
+    if *budget == 0 {
+        cpu.program_counter = 0x44C;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4ED;
+    // 0x44E  D9B4  DRW V9, VB, 4
+    if *budget == 0 {
+        cpu.program_counter = 0x44E;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(9, 11, 4));
+    // 0x450  A4A5  LD I, 0x4A5
+    if *budget == 0 {
+        cpu.program_counter = 0x450;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x4A5;
+    // 0x452  66FF  LD V6, 0xFF
+    if *budget == 0 {
+        cpu.program_counter = 0x452;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = 255;
+    // 0x454  760A  ADD V6, 0x0A
+    if *budget == 0 {
+        cpu.program_counter = 0x454;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = cpu.registers[6].wrapping_add(10);
+    // 0x456  3609  SE V6, 0x09
+    if *budget == 0 {
+        cpu.program_counter = 0x456;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 9 { 0x45A } else { 0x458 };
+}
+
+#[rustfmt::skip]
+const BLOCK_458: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_458(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x458  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x45A;
+}
+
+#[rustfmt::skip]
+const BLOCK_45A: &[u8] = &[
+    0x86, 0x66, 0x36, 0x04,
+];
+
+fn block_45a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x45A  8666  SHR V6, V6
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v >> 1;
+        cpu.registers[15] = v & 1;
+    }
+    // 0x45C  3604  SE V6, 0x04
+    if *budget == 0 {
+        cpu.program_counter = 0x45C;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 4 { 0x460 } else { 0x45E };
+}
+
+#[rustfmt::skip]
+const BLOCK_45E: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_45e(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x45E  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x460;
+}
+
+#[rustfmt::skip]
+const BLOCK_460: &[u8] = &[
+    0x66, 0xFF, 0x60, 0x0A, 0x86, 0x04, 0x36, 0x09,
+];
+
+fn block_460(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x460  66FF  LD V6, 0xFF
+    *budget -= 1;
+    cpu.registers[6] = 255;
+    // 0x462  600A  LD V0, 0x0A
+    if *budget == 0 {
+        cpu.program_counter = 0x462;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = 10;
+    // 0x464  8604  ADD V6, V0
+    if *budget == 0 {
+        cpu.program_counter = 0x464;
+        return;
+    }
+    *budget -= 1;
+    {
+        let (sum, carry) = cpu.registers[6].overflowing_add(cpu.registers[0]);
+        cpu.registers[6] = sum;
+        cpu.registers[15] = carry as u8;
+    }
+    // 0x466  3609  SE V6, 0x09
+    if *budget == 0 {
+        cpu.program_counter = 0x466;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 9 { 0x46A } else { 0x468 };
+}
+
+#[rustfmt::skip]
+const BLOCK_468: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_468(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x468  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x46A;
+}
+
+#[rustfmt::skip]
+const BLOCK_46A: &[u8] = &[
+    0x86, 0x66, 0x36, 0x04,
+];
+
+fn block_46a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x46A  8666  SHR V6, V6
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v >> 1;
+        cpu.registers[15] = v & 1;
+    }
+    // 0x46C  3604  SE V6, 0x04
+    if *budget == 0 {
+        cpu.program_counter = 0x46C;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 4 { 0x470 } else { 0x46E };
+}
+
+#[rustfmt::skip]
+const BLOCK_46E: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_46e(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x46E  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x470;
+}
+
+#[rustfmt::skip]
+const BLOCK_470: &[u8] = &[
+    0x66, 0xFF, 0x86, 0x6E, 0x86, 0x66, 0x36, 0x7F,
+];
+
+fn block_470(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x470  66FF  LD V6, 0xFF
+    *budget -= 1;
+    cpu.registers[6] = 255;
+    // 0x472  866E  SHL V6, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x472;
+        return;
+    }
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v << 1;
+        cpu.registers[15] = v >> 7;
+    }
+    // 0x474  8666  SHR V6, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x474;
+        return;
+    }
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v >> 1;
+        cpu.registers[15] = v & 1;
+    }
+    // 0x476  367F  SE V6, 0x7F
+    if *budget == 0 {
+        cpu.program_counter = 0x476;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 127 {
+        0x47A
+    } else {
+        0x478
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_478: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_478(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x478  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x47A;
+}
+
+#[rustfmt::skip]
+const BLOCK_47A: &[u8] = &[
+    0x86, 0x66, 0x86, 0x6E, 0x36, 0x7E,
+];
+
+fn block_47a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x47A  8666  SHR V6, V6
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v >> 1;
+        cpu.registers[15] = v & 1;
+    }
+    // 0x47C  866E  SHL V6, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x47C;
+        return;
+    }
+    *budget -= 1;
+    {
+        let v = cpu.registers[6];
+        cpu.registers[6] = v << 1;
+        cpu.registers[15] = v >> 7;
+    }
+    // 0x47E  367E  SE V6, 0x7E
+    if *budget == 0 {
+        cpu.program_counter = 0x47E;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 126 {
+        0x482
+    } else {
+        0x480
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_480: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_480(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x480  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x482;
+}
+
+#[rustfmt::skip]
+const BLOCK_482: &[u8] = &[
+    0x66, 0x05, 0x76, 0xF6, 0x36, 0xFB,
+];
+
+fn block_482(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x482  6605  LD V6, 0x05
+    *budget -= 1;
+    cpu.registers[6] = 5;
+    // 0x484  76F6  ADD V6, 0xF6
+    if *budget == 0 {
+        cpu.program_counter = 0x484;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[6] = cpu.registers[6].wrapping_add(246);
+    // 0x486  36FB  SE V6, 0xFB
+    if *budget == 0 {
+        cpu.program_counter = 0x486;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 251 {
+        0x48A
+    } else {
+        0x488
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_488: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_488(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x488  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x48A;
+}
+
+#[rustfmt::skip]
+const BLOCK_48A: &[u8] = &[
+    0x66, 0x05, 0x86, 0x05, 0x36, 0xFB,
+];
+
+fn block_48a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x48A  6605  LD V6, 0x05
+    *budget -= 1;
+    cpu.registers[6] = 5;
+    // 0x48C  8605  SUB V6, V0
+    if *budget == 0 {
+        cpu.program_counter = 0x48C;
+        return;
+    }
+    *budget -= 1;
+    {
+        let (a, b) = (cpu.registers[6], cpu.registers[0]);
+        cpu.registers[6] = a.wrapping_sub(b);
+        cpu.registers[15] = (a >= b) as u8;
+    }
+    // 0x48E  36FB  SE V6, 0xFB
+    if *budget == 0 {
+        cpu.program_counter = 0x48E;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[6] == 251 {
+        0x492
+    } else {
+        0x490
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_490: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_490(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x490  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x492;
+}
+
+#[rustfmt::skip]
+const BLOCK_492: &[u8] = &[
+    0x66, 0x05, 0x80, 0x67, 0x30, 0xFB,
+];
+
+fn block_492(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x492  6605  LD V6, 0x05
+    *budget -= 1;
+    cpu.registers[6] = 5;
+    // 0x494  8067  SUBN V0, V6
+    if *budget == 0 {
+        cpu.program_counter = 0x494;
+        return;
+    }
+    *budget -= 1;
+    {
+        let (a, b) = (cpu.registers[6], cpu.registers[0]);
+        cpu.registers[0] = a.wrapping_sub(b);
+        cpu.registers[15] = (a >= b) as u8;
+    }
+    // 0x496  30FB  SE V0, 0xFB
+    if *budget == 0 {
+        cpu.program_counter = 0x496;
+        return;
+    }
+    *budget -= 1;
+    cpu.program_counter = if cpu.registers[0] == 251 {
+        0x49A
+    } else {
+        0x498
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_498: &[u8] = &[
+    0xA4, 0xA1,
+];
+
+fn block_498(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x498  A4A1  LD I, 0x4A1
+    *budget -= 1;
+    cpu.index = 0x4A1;
+    cpu.program_counter = 0x49A;
+}
+
+#[rustfmt::skip]
+const BLOCK_49A: &[u8] = &[
+    0xDA, 0xB4,
+];
+
+fn block_49a(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x49A  DAB4  DRW VA, VB, 4
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(10, 11, 4));
+    cpu.program_counter = 0x49C;
+}
+
+#[rustfmt::skip]
+const BLOCK_49C: &[u8] = &[
+    0x14, 0x9C,
+];
+
+fn block_49c(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x49C  149C  JP 0x49C
+    *budget -= 1;
+    cpu.program_counter = 0x49C;
+}
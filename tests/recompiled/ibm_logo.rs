@@ -0,0 +1,192 @@
+// Recompiled from IBM Logo.ch8 by `chip8_emulator recompile`.
+// Each block runs only while memory still holds the code it was built from.
+
+use chip8_emulator::cpu::Cpu;
+use chip8_emulator::disasm::Instruction;
+
+#[rustfmt::skip]
+pub const ROM: &[u8] = &[
+    0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x61, 0x08, 0xD0, 0x1F, 0x70, 0x09, 0xA2, 0x39, 0xD0, 0x1F,
+    0xA2, 0x48, 0x70, 0x08, 0xD0, 0x1F, 0x70, 0x04, 0xA2, 0x57, 0xD0, 0x1F, 0x70, 0x08, 0xA2, 0x66,
+    0xD0, 0x1F, 0x70, 0x08, 0xA2, 0x75, 0xD0, 0x1F, 0x12, 0x28, 0xFF, 0x00, 0xFF, 0x00, 0x3C, 0x00,
+    0x3C, 0x00, 0x3C, 0x00, 0x3C, 0x00, 0xFF, 0x00, 0xFF, 0xFF, 0x00, 0xFF, 0x00, 0x38, 0x00, 0x3F,
+    0x00, 0x3F, 0x00, 0x38, 0x00, 0xFF, 0x00, 0xFF, 0x80, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0x80, 0x00,
+    0x80, 0x00, 0xE0, 0x00, 0xE0, 0x00, 0x80, 0xF8, 0x00, 0xFC, 0x00, 0x3E, 0x00, 0x3F, 0x00, 0x3B,
+    0x00, 0x39, 0x00, 0xF8, 0x00, 0xF8, 0x03, 0x00, 0x07, 0x00, 0x0F, 0x00, 0xBF, 0x00, 0xFB, 0x00,
+    0xF3, 0x00, 0xE3, 0x00, 0x43, 0xE0, 0x00, 0xE0, 0x00, 0x80, 0x00, 0x80, 0x00, 0x80, 0x00, 0x80,
+    0x00, 0xE0, 0x00, 0xE0,
+];
+
+// Runs `cpu.cycles_per_frame` instructions, then ticks the timers.
+pub fn run_frame(cpu: &mut Cpu) {
+    let mut budget = cpu.cycles_per_frame;
+    while budget > 0 {
+        match cpu.program_counter {
+            0x200 if cpu.memory.bytes(0x200, 40) == BLOCK_200 => block_200(cpu, &mut budget),
+            0x228 if cpu.memory.bytes(0x228, 2) == BLOCK_228 => block_228(cpu, &mut budget),
+            _ => {
+                cpu.cycle();
+                budget -= 1;
+            }
+        }
+    }
+    cpu.tick_timers();
+}
+
+#[rustfmt::skip]
+const BLOCK_200: &[u8] = &[
+    0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x61, 0x08, 0xD0, 0x1F, 0x70, 0x09, 0xA2, 0x39, 0xD0, 0x1F,
+    0xA2, 0x48, 0x70, 0x08, 0xD0, 0x1F, 0x70, 0x04, 0xA2, 0x57, 0xD0, 0x1F, 0x70, 0x08, 0xA2, 0x66,
+    0xD0, 0x1F, 0x70, 0x08, 0xA2, 0x75, 0xD0, 0x1F,
+];
+
+fn block_200(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x200  00E0  CLS
+    *budget -= 1;
+    cpu.display.clear();
+    // 0x202  A22A  LD I, 0x22A
+    if *budget == 0 {
+        cpu.program_counter = 0x202;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x22A;
+    // 0x204  600C  LD V0, 0x0C
+    if *budget == 0 {
+        cpu.program_counter = 0x204;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = 12;
+    // 0x206  6108  LD V1, 0x08
+    if *budget == 0 {
+        cpu.program_counter = 0x206;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[1] = 8;
+    // 0x208  D01F  DRW V0, V1, 15
+    if *budget == 0 {
+        cpu.program_counter = 0x208;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(0, 1, 15));
+    // 0x20A  7009  ADD V0, 0x09
+    if *budget == 0 {
+        cpu.program_counter = 0x20A;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = cpu.registers[0].wrapping_add(9);
+    // 0x20C  A239  LD I, 0x239
+    if *budget == 0 {
+        cpu.program_counter = 0x20C;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x239;
+    // 0x20E  D01F  DRW V0, V1, 15
+    if *budget == 0 {
+        cpu.program_counter = 0x20E;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(0, 1, 15));
+    // 0x210  A248  LD I, 0x248
+    if *budget == 0 {
+        cpu.program_counter = 0x210;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x248;
+    // 0x212  7008  ADD V0, 0x08
+    if *budget == 0 {
+        cpu.program_counter = 0x212;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = cpu.registers[0].wrapping_add(8);
+    // 0x214  D01F  DRW V0, V1, 15
+    if *budget == 0 {
+        cpu.program_counter = 0x214;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(0, 1, 15));
+    // 0x216  7004  ADD V0, 0x04
+    if *budget == 0 {
+        cpu.program_counter = 0x216;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = cpu.registers[0].wrapping_add(4);
+    // 0x218  A257  LD I, 0x257
+    if *budget == 0 {
+        cpu.program_counter = 0x218;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x257;
+    // 0x21A  D01F  DRW V0, V1, 15
+    if *budget == 0 {
+        cpu.program_counter = 0x21A;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(0, 1, 15));
+    // 0x21C  7008  ADD V0, 0x08
+    if *budget == 0 {
+        cpu.program_counter = 0x21C;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = cpu.registers[0].wrapping_add(8);
+    // 0x21E  A266  LD I, 0x266
+    if *budget == 0 {
+        cpu.program_counter = 0x21E;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x266;
+    // 0x220  D01F  DRW V0, V1, 15
+    if *budget == 0 {
+        cpu.program_counter = 0x220;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(0, 1, 15));
+    // 0x222  7008  ADD V0, 0x08
+    if *budget == 0 {
+        cpu.program_counter = 0x222;
+        return;
+    }
+    *budget -= 1;
+    cpu.registers[0] = cpu.registers[0].wrapping_add(8);
+    // 0x224  A275  LD I, 0x275
+    if *budget == 0 {
+        cpu.program_counter = 0x224;
+        return;
+    }
+    *budget -= 1;
+    cpu.index = 0x275;
+    // 0x226  D01F  DRW V0, V1, 15
+    if *budget == 0 {
+        cpu.program_counter = 0x226;
+        return;
+    }
+    *budget -= 1;
+    cpu.execute(Instruction::Drw(0, 1, 15));
+    cpu.program_counter = 0x228;
+}
+
+#[rustfmt::skip]
+const BLOCK_228: &[u8] = &[
+    0x12, 0x28,
+];
+
+fn block_228(cpu: &mut Cpu, budget: &mut u32) {
+    // 0x228  1228  JP 0x228
+    *budget -= 1;
+    cpu.program_counter = 0x228;
+}